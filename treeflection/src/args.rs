@@ -0,0 +1,47 @@
+//! Parsing of the arguments given to custom actions.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Parse argument `index` of a custom action into T.
+///
+/// The argument is read as JSON, so numbers, bools, arrays and objects work as expected.
+/// If that fails it is read as a JSON string, so strings and unit enum variants don't need quotes.
+/// A missing argument is read as null, so `Option` can be used for optional trailing arguments.
+pub fn parse_arg<T: DeserializeOwned>(args: &[String], index: usize) -> Result<T, String> {
+    match args.get(index) {
+        Some (arg) => {
+            let json_result = serde_json::from_str(arg);
+            let json_is_valid = json_result.is_ok() || serde_json::from_str::<Value>(arg).is_ok();
+            json_result.or_else(|json_err| {
+                serde_json::from_value(Value::String(arg.clone())).map_err(|string_err| {
+                    // report the error from whichever way the argument was meant to be read
+                    let err = if json_is_valid { json_err } else { string_err };
+                    format!("Invalid value '{}' for argument {} (needs to be: {}): {}", arg, index + 1, type_name::<T>(), err)
+                })
+            })
+        }
+        None => {
+            serde_json::from_value(Value::Null).map_err(|_| format!("Missing argument {} (needs to be: {})", index + 1, type_name::<T>()))
+        }
+    }
+}
+
+/// The name of T without module paths e.g. `Option<String>` instead of `core::option::Option<alloc::string::String>`
+fn type_name<T>() -> String {
+    let mut result = String::new();
+    let mut segment = String::new();
+    for c in std::any::type_name::<T>().chars() {
+        if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else if c == ':' {
+            segment.clear();
+        } else {
+            result.push_str(&segment);
+            result.push(c);
+            segment.clear();
+        }
+    }
+    result.push_str(&segment);
+    result
+}
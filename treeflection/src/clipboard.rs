@@ -0,0 +1,26 @@
+//! Storage for the `copy` and `paste` actions.
+//!
+//! Each type gets its own slot, so copying a value only affects pastes into values of the same type.
+//! The clipboard is per thread, matching the thread that owns the root node.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    static CLIPBOARD: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Store a clone of `value`, replacing any previously copied value of the same type
+pub fn copy<T: Clone + 'static>(value: &T) {
+    CLIPBOARD.with(|clipboard| {
+        clipboard.borrow_mut().insert(TypeId::of::<T>(), Box::new(value.clone()));
+    });
+}
+
+/// Returns a clone of the last copied value of type T, or None if nothing of that type has been copied
+pub fn paste<T: Clone + 'static>() -> Option<T> {
+    CLIPBOARD.with(|clipboard| {
+        clipboard.borrow().get(&TypeId::of::<T>()).and_then(|x| x.downcast_ref::<T>()).cloned()
+    })
+}
@@ -0,0 +1,218 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::condition;
+use crate::node::Node;
+
+/// A CommandQueue lets any thread submit commands for a root node that lives on another thread, e.g. the game thread.
+///
+/// Commands are submitted through a `CommandHandle`, which can be cloned and sent to other threads.
+/// The thread owning the root node runs the commands at a safe point by calling `process`, e.g. once per frame.
+/// Besides regular commands, `if` lines are run as described in the `condition` module.
+///
+/// ```
+/// use treeflection::CommandQueue;
+///
+/// let mut root = vec!(1, 2, 3);
+/// let mut queue = CommandQueue::new();
+///
+/// let handle = queue.handle();
+/// let console = std::thread::spawn(move || handle.submit("[1]:get").wait());
+///
+/// while !console.is_finished() {
+///     queue.process(&mut root);
+/// }
+/// assert_eq!(console.join().unwrap(), Ok(String::from("2")));
+/// ```
+pub struct CommandQueue {
+    sender:   Sender<Request>,
+    receiver: Receiver<Request>,
+    budget:   Budget,
+}
+
+/// Limits how much work a single call to `CommandQueue::process` does.
+/// Commands that do not fit in the budget are left in the queue for the next call.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Budget {
+    /// Maximum number of commands to run
+    pub max_commands: Option<usize>,
+    /// Stop running commands once this much time has passed.
+    /// The command running when the time runs out is not interrupted.
+    pub max_duration: Option<Duration>,
+}
+
+/// Submits commands to a `CommandQueue` from any thread
+#[derive(Clone)]
+pub struct CommandHandle {
+    sender: Sender<Request>,
+}
+
+/// The result of a submitted command, available once the owning thread has processed it.
+///
+/// Block on the result with `wait` or `.await` it as a Future.
+/// The result is Err if the command could not be parsed or the CommandQueue was dropped before running it.
+pub struct PendingCommand {
+    slot: Arc<Slot>,
+}
+
+struct Request {
+    command: String,
+    slot:    Arc<Slot>,
+}
+
+struct Slot {
+    state:   Mutex<SlotState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct SlotState {
+    result: Option<Result<String, String>>,
+    waker:  Option<Waker>,
+    done:   bool,
+}
+
+impl CommandQueue {
+    /// Create a new empty CommandQueue without a budget
+    pub fn new() -> CommandQueue {
+        let (sender, receiver) = channel();
+        CommandQueue {
+            sender,
+            receiver,
+            budget: Budget::default(),
+        }
+    }
+
+    /// Create a handle for submitting commands to this queue
+    pub fn handle(&self) -> CommandHandle {
+        CommandHandle { sender: self.sender.clone() }
+    }
+
+    /// Set the budget used by `process`
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+
+    /// Get the budget used by `process`
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// Run queued commands on `root` in the order they were submitted, until the queue is empty or the budget is used up.
+    /// Does not block if there are no commands.
+    /// Returns the number of commands that were run.
+    pub fn process<T: Node>(&mut self, root: &mut T) -> usize {
+        let start = Instant::now();
+        let mut count = 0;
+        loop {
+            if let Some(max_commands) = self.budget.max_commands {
+                if count >= max_commands {
+                    break;
+                }
+            }
+            if let Some(max_duration) = self.budget.max_duration {
+                if start.elapsed() >= max_duration {
+                    break;
+                }
+            }
+
+            match self.receiver.try_recv() {
+                Ok (request) => {
+                    let result = condition::run(root, &request.command);
+                    request.slot.complete(result);
+                    count += 1;
+                }
+                Err (_) => break
+            }
+        }
+        count
+    }
+}
+
+impl Default for CommandQueue {
+    fn default() -> CommandQueue {
+        CommandQueue::new()
+    }
+}
+
+impl CommandHandle {
+    /// Queue a command to be run by the owning thread
+    pub fn submit<S: Into<String>>(&self, command: S) -> PendingCommand {
+        let slot = Arc::new(Slot {
+            state:   Mutex::new(SlotState::default()),
+            condvar: Condvar::new(),
+        });
+        // if the queue has been dropped the returned request is dropped too, which completes it with an error
+        self.sender.send(Request { command: command.into(), slot: slot.clone() }).ok();
+        PendingCommand { slot }
+    }
+}
+
+impl PendingCommand {
+    /// Block until the command has been run
+    pub fn wait(self) -> Result<String, String> {
+        let mut state = self.slot.state.lock().unwrap();
+        while !state.done {
+            state = self.slot.condvar.wait(state).unwrap();
+        }
+        state.result.take().unwrap()
+    }
+
+    /// Block until the command has been run or the timeout has passed.
+    /// Returns the PendingCommand back on timeout so it can be waited on again.
+    pub fn wait_timeout(self, timeout: Duration) -> Result<Result<String, String>, PendingCommand> {
+        let result = {
+            let state = self.slot.state.lock().unwrap();
+            let (mut state, _) = self.slot.condvar.wait_timeout_while(state, timeout, |x| !x.done).unwrap();
+            state.result.take()
+        };
+        result.ok_or(self)
+    }
+
+    /// Returns true if the command has been run
+    pub fn is_done(&self) -> bool {
+        self.slot.state.lock().unwrap().done
+    }
+}
+
+impl Future for PendingCommand {
+    type Output = Result<String, String>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.slot.state.lock().unwrap();
+        if state.done {
+            match state.result.take() {
+                Some (result) => Poll::Ready(result),
+                None          => panic!("PendingCommand polled after completion"),
+            }
+        } else {
+            state.waker = Some(context.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Slot {
+    fn complete(&self, result: Result<String, String>) {
+        let mut state = self.state.lock().unwrap();
+        if !state.done {
+            state.result = Some(result);
+            state.done = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+            self.condvar.notify_all();
+        }
+    }
+}
+
+impl Drop for Request {
+    fn drop(&mut self) {
+        // does nothing if the request was already run
+        self.slot.complete(Err(String::from("The command queue was dropped before running the command")));
+    }
+}
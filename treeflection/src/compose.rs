@@ -0,0 +1,51 @@
+//! Builds get and set out of the get and set of each child.
+//!
+//! Used by the code generated for `#[node(no_serde)]` types, which don't implement Serialize and Deserialize.
+
+use serde_json::Value;
+
+use crate::node::Node;
+use crate::node_runner::NodeRunner;
+use crate::node_token::NodeToken;
+
+/// Run get on `child` and read the result as JSON.
+/// Results that aren't JSON, such as the contents of a String, are read as a JSON string.
+pub fn get<T: Node + ?Sized>(child: &mut T) -> Value {
+    let result = child.node_step(NodeRunner { tokens: vec!(NodeToken::Get) });
+    serde_json::from_str(&result).unwrap_or(Value::String(result))
+}
+
+/// Run set on `child` with `value`.
+/// JSON strings are given without quotes, matching the result of get on a String.
+pub fn set<T: Node + ?Sized>(child: &mut T, value: &Value) -> Result<(), String> {
+    let value = match value {
+        Value::String (string) => string.clone(),
+        value                  => value.to_string(),
+    };
+    let result = child.node_step(NodeRunner { tokens: vec!(NodeToken::Set(value)) });
+    if result.is_empty() {
+        Ok(())
+    } else {
+        Err(result)
+    }
+}
+
+/// Format `value` the same way as get on a type that implements Serialize
+pub fn to_string(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap()
+}
+
+/// Read the value given to set as JSON, an error is returned if it isn't valid JSON
+pub fn parse(name: &str, value: &str) -> Result<Value, String> {
+    serde_json::from_str(value).map_err(|err| format!("{} set error: {}", name, err))
+}
+
+/// Returns an error if `object` has a field that isn't in `keys`
+pub fn check_keys(name: &str, object: &serde_json::Map<String, Value>, keys: &[&str]) -> Result<(), String> {
+    for key in object.keys() {
+        if !keys.contains(&key.as_str()) {
+            return Err(format!("{} set error: {} does not have a property '{}'", name, name, key));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! Runs a command depending on the result of another command: `if COMMAND OPERATOR VALUE then COMMAND [else COMMAND]`
+//! e.g. `if fighter.hp:get > 0 then fighter.state:variant Alive`.
+//!
+//! OPERATOR is one of `==`, `!=`, `<`, `<=`, `>` or `>=`.
+//! The result of the condition command and VALUE are compared as numbers when both are numbers, otherwise as text.
+//! The condition command must not modify anything and its path must be reachable, otherwise the whole line fails.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::node::Node;
+use crate::node_runner::NodeRunner;
+use crate::node_token::NodeToken;
+
+/// The comparison operators, operators that start with another operator come first
+const OPERATORS: [&str; 6] = ["==", "!=", "<=", ">=", "<", ">"];
+
+const USAGE: &str = "if COMMAND OPERATOR VALUE then COMMAND [else COMMAND]";
+
+/// A parsed `if` line, the commands are kept as written so they can be run relative to a `Session` path
+#[derive(Debug, PartialEq)]
+pub struct Condition<'a> {
+    /// The command whose result is compared
+    pub command:   &'a str,
+    pub operator:  &'a str,
+    pub value:     &'a str,
+    /// The line run when the comparison is true
+    pub then_line: &'a str,
+    /// The line run when the comparison is false
+    pub else_line: Option<&'a str>,
+}
+
+impl<'a> Condition<'a> {
+    /// Parse `line`, returns None if `line` is not an `if` line
+    pub fn parse(line: &'a str) -> Option<Result<Condition<'a>, String>> {
+        let line = line.trim_start();
+        let argument = line.strip_prefix("if")?;
+        if !argument.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(Condition::parse_argument(argument))
+    }
+
+    fn parse_argument(argument: &'a str) -> Result<Condition<'a>, String> {
+        let (condition, branches) = match find_word(argument, "then") {
+            Some (i) => (argument[..i].trim(), &argument[i + 4..]),
+            None     => return Err(format!("Missing then (needs to be: {})", USAGE)),
+        };
+        let (then_line, else_line) = match find_word(branches, "else") {
+            Some (i) => (branches[..i].trim(), Some (branches[i + 4..].trim())),
+            None     => (branches.trim(), None),
+        };
+        if then_line.is_empty() || else_line == Some ("") {
+            return Err(format!("Missing command (needs to be: {})", USAGE));
+        }
+
+        let (command, operator, value) = match OPERATORS.iter().filter_map(|x| find_word(condition, x).map(|i| (i, *x))).min() {
+            Some ((i, operator)) => (condition[..i].trim(), operator, condition[i + operator.len()..].trim()),
+            None => return Err(format!("Missing operator (needs to be one of: {})", OPERATORS.join(", "))),
+        };
+        if command.is_empty() || value.is_empty() {
+            return Err(format!("Missing command or value (needs to be: {})", USAGE));
+        }
+
+        Ok(Condition { command, operator, value, then_line, else_line })
+    }
+
+    /// The line to run for the `result` of the condition command, None if there is nothing to run
+    pub fn branch(&self, result: &str) -> Option<&'a str> {
+        let ordering = match (result.trim().parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok (a), Ok (b)) => a.partial_cmp(&b),
+            _                => Some(unquote(result).cmp(&unquote(self.value))),
+        };
+        let passed = match ordering {
+            Some (ordering) => match self.operator {
+                "==" => ordering == Ordering::Equal,
+                "!=" => ordering != Ordering::Equal,
+                "<"  => ordering == Ordering::Less,
+                "<=" => ordering != Ordering::Greater,
+                ">"  => ordering == Ordering::Greater,
+                _    => ordering != Ordering::Less,
+            }
+            None => self.operator == "!=", // NaN is not equal to anything
+        };
+
+        if passed {
+            Some(self.then_line)
+        } else {
+            self.else_line
+        }
+    }
+}
+
+/// Run the condition command `runner` on `root`, `command` is the command as written for error messages.
+/// Returns Err if the command is not a valid condition or its path can't be reached.
+pub fn evaluate<T: Node>(root: &mut T, command: &str, runner: NodeRunner) -> Result<String, String> {
+    check_runner(command, &runner)?;
+
+    // an exists condition is the one command whose result is meaningful when the path can't be reached
+    let reachable = runner.tokens[0] == NodeToken::Exists || {
+        let mut exists = runner.clone();
+        exists.tokens[0] = NodeToken::Exists;
+        root.node_step(exists) == "true"
+    };
+    let result = root.node_step(runner);
+    if reachable {
+        Ok(result)
+    } else {
+        Err(format!("The condition '{}' failed: {}", command, result))
+    }
+}
+
+/// Run `line` on `root`, which is either an `if` line or a regular command.
+/// Returns Err if any of the commands could not be parsed.
+/// Like the errors of any other command, a condition that failed is returned as Ok.
+pub fn run<T: Node>(root: &mut T, line: &str) -> Result<String, String> {
+    match Condition::parse(line) {
+        Some (condition) => {
+            let condition = condition?;
+            let result = match evaluate(root, condition.command, NodeRunner::new(condition.command)?) {
+                Ok (result) => result,
+                Err (err) => return Ok(err)
+            };
+            match condition.branch(&result) {
+                Some (line) => run(root, line),
+                None        => Ok(String::new()),
+            }
+        }
+        None => NodeRunner::new(line).map(|runner| root.node_step(runner))
+    }
+}
+
+/// Check that every command in `line` can be parsed and the condition does not modify anything, without running them
+pub fn check(line: &str) -> Result<(), String> {
+    match Condition::parse(line) {
+        Some (condition) => {
+            let condition = condition?;
+            check_runner(condition.command, &NodeRunner::new(condition.command)?)?;
+            check(condition.then_line)?;
+            match condition.else_line {
+                Some (line) => check(line),
+                None        => Ok(())
+            }
+        }
+        None => NodeRunner::new(line).map(|_| ())
+    }
+}
+
+/// A condition must not modify anything and must have a single result to compare
+fn check_runner(command: &str, runner: &NodeRunner) -> Result<(), String> {
+    if !runner.is_read_only() {
+        Err(format!("The condition '{}' must not modify anything (try an action like get)", command))
+    } else if runner.tokens.iter().any(|x| matches!(x, NodeToken::ChainAll | NodeToken::ChainContext)) {
+        Err(format!("The condition '{}' cannot use [*] or [?]", command))
+    } else {
+        Ok(())
+    }
+}
+
+/// The byte index of the first occurrence of `word` in `line` that is surrounded by whitespace or the ends of `line`
+fn find_word(line: &str, word: &str) -> Option<usize> {
+    line.match_indices(word).map(|(i, _)| i).find(|&i| {
+        let (before, after) = (&line[..i], &line[i + word.len()..]);
+        (before.is_empty() || before.ends_with(char::is_whitespace)) && (after.is_empty() || after.starts_with(char::is_whitespace))
+    })
+}
+
+/// Strings from get are JSON, so remove the quotes to compare them with plain text
+fn unquote(value: &str) -> String {
+    match serde_json::from_str::<Value>(value.trim()) {
+        Ok (Value::String (value)) => value,
+        _ => value.trim().to_string()
+    }
+}
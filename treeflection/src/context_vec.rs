@@ -4,9 +4,14 @@ use serde::ser::{Serialize, Serializer};
 use serde::de::{Deserialize, DeserializeOwned, Deserializer};
 use serde_json;
 
+use crate::edit;
+use crate::fan_out::FanOut;
+use crate::format::{self, Format};
+use crate::node;
 use crate::node::Node;
 use crate::node_runner::NodeRunner;
 use crate::node_token::NodeToken;
+use crate::reorder;
 
 #[derive(Clone, Default)]
 pub struct ContextVec<T> {
@@ -205,52 +210,45 @@ impl<T> Node for ContextVec<T> where T: Node + Serialize + DeserializeOwned + De
                 match self.vector.get_mut(index) {
                     Some (item) => item.node_step(runner),
                     None => {
-                        return match length {
+                        let error = match length {
                              0 => format!("Used index {} on an empty vector", index),
                              1 => format!("Used index {} on a vector of size 1 (try 0)", index),
                              _ => format!("Used index {} on a vector of size {} (try a value between 0-{})", index, length, length-1)
-                        }
+                        };
+                        node::path_error(&runner, error)
                     }
                 }
             }
             NodeToken::ChainContext => {
-                let mut combined = String::from("|");
+                let mut fan_out = FanOut::new(&mut runner);
                 for i in self.context.iter() {
-                    match self.vector.get_mut(*i) {
-                        Some(ref mut node) => {
-                            let result = node.node_step(runner.clone());
-                            combined.push_str(result.as_str());
-                        }
-                        None => {
-                            combined.push_str("Context out of range. This should never happen.");
-                        }
-                    }
-                    combined.push('|');
+                    let result = match self.vector.get_mut(*i) {
+                        Some(ref mut node) => node.node_step(runner.clone()),
+                        None => node::path_error(&runner, String::from("Context out of range. This should never happen."))
+                    };
+                    fan_out.push(NodeToken::ChainIndex (*i), result);
                 }
-                combined
+                fan_out.finish()
             }
             NodeToken::ChainAll => {
-                let mut combined = String::from("|");
-                for item in self.vector.iter_mut() {
-                    combined.push_str(item.node_step(runner.clone()).as_ref());
-                    combined.push('|');
+                let mut fan_out = FanOut::new(&mut runner);
+                for (i, item) in self.vector.iter_mut().enumerate() {
+                    fan_out.push(NodeToken::ChainIndex (i), item.node_step(runner.clone()));
                 }
-                combined
+                fan_out.finish()
             }
             NodeToken::ChainProperty (ref s) if s == "length" => { self.vector.len().node_step(runner) }
             NodeToken::Get => {
                 serde_json::to_string_pretty(&mut self.vector).unwrap()
             }
-            NodeToken::Set(value) => {
-                match serde_json::from_str(&value) {
-                    Ok(result) => {
-                        self.vector = result;
-                        String::from("")
-                    }
-                    Err(err) => {
-                        format!("vector set error: {}", err)
-                    }
-                }
+            NodeToken::Set (value) => {
+                set_vector(self, &value, Format::Json)
+            }
+            NodeToken::GetWith (options) => {
+                format::get(&self.vector, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                set_vector(self, &value, format)
             }
             NodeToken::Insert => {
                 self.push(T::default());
@@ -273,6 +271,31 @@ impl<T> Node for ContextVec<T> where T: Node + Serialize + DeserializeOwned + De
                     String::new()
                 }
             }
+            // a single argument after an index is parsed as a key, vectors have no keys so it is the value
+            NodeToken::InsertIndexValue (index, value) | NodeToken::InsertIndexKey (index, value) => {
+                let max_index = self.len();
+                if index > max_index {
+                    format!("Tried to insert at index {} on a vector of size {} (try a value between 0-{})", index, max_index, max_index)
+                }
+                else {
+                    match serde_json::from_str(&value) {
+                        Ok (value) => {
+                            self.insert(index, value);
+                            String::new()
+                        }
+                        Err (err) => format!("vector insert error: {}", err)
+                    }
+                }
+            }
+            NodeToken::Push (value) => {
+                match serde_json::from_str(&value) {
+                    Ok (value) => {
+                        self.push(value);
+                        String::new()
+                    }
+                    Err (err) => format!("vector push error: {}", err)
+                }
+            }
             NodeToken::RemoveIndex (index) => {
                 let max_index = self.len() - 1;
                 if index > max_index {
@@ -283,31 +306,69 @@ impl<T> Node for ContextVec<T> where T: Node + Serialize + DeserializeOwned + De
                     String::new()
                 }
             }
+            NodeToken::CheckRemoveIndex (index) if index < self.len() => { String::new() }
             NodeToken::SetDefault => {
-                self.vector = vec!();
+                self.clear();
                 String::new()
             }
+            NodeToken::Custom (action, args) => {
+                match reorder::run(&mut self.vector, &action, &args) {
+                    Some (Ok (moved)) => {
+                        // the context follows its elements, removed elements are removed from the context
+                        self.context = self.context.iter().filter_map(|i| moved[*i]).collect();
+                        String::new()
+                    }
+                    Some (Err (err)) => err,
+                    None             => format!("vector cannot '{}'", action)
+                }
+            }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
             NodeToken::Help => {
                 String::from(r#"
 Context Vector Help
 
 Commands:
-*   help          - display this help
-*   get           - display JSON
-*   set           - set to JSON
-*   insert        - create a new element at the end of the vector
-*   insert $INDEX - create a new element at $INDEX
-*   remove        - remove the element at the end of the vector
-*   remove $INDEX - remove the element at $INDEX
-*   reset         - reset to empty vector
+*   help                 - display this help
+*   get                  - display JSON
+*   set                  - set to JSON
+*   edit                 - edit JSON in your editor
+*   type                 - display the Rust type
+*   insert               - create a new element at the end of the vector
+*   insert $INDEX        - create a new element at $INDEX
+*   insert $INDEX $JSON  - insert $JSON at $INDEX
+*   push $JSON           - insert $JSON at the end of the vector
+*   remove               - remove the element at the end of the vector
+*   remove $INDEX        - remove the element at $INDEX
+*   reset                - reset to empty vector
+*   swap $INDEX $INDEX   - swap the elements at the two indexes
+*   move $FROM $TO       - move the element at $FROM to $TO
+*   reverse              - reverse the order of the elements
+*   duplicate $INDEX     - insert a copy of the element at $INDEX after it
+*   truncate $LENGTH     - remove the elements from $LENGTH onwards
+*   resize $LENGTH       - remove or create elements until there are $LENGTH
+*   sortby $PATH [ORDER] - sort by the value at $PATH in each element, ORDER is ascending or descending
 
 Accessors:
 *   [INDEX] - access item at INDEX
 *   [?]     - access items at current context
 *   .length - display number of items"#)
             }
-            action => { format!("vector cannot '{:?}'", action) }
+            action => { node::path_error(&runner, format!("vector cannot '{:?}'", action)) }
+        }
+    }
+}
+
+/// Replace the values of `vec` with `value`, the context is cleared as it could point past the end of the new values
+fn set_vector<T: DeserializeOwned>(vec: &mut ContextVec<T>, value: &str, format: Format) -> String {
+    match format::from_str(value, format) {
+        Ok (vector) => {
+            vec.set_vec(vector);
+            String::new()
         }
+        Err (err) => format!("vector set error: {}", err)
     }
 }
 
@@ -0,0 +1,156 @@
+//! The `edit` action: opens the value of a node in the user's editor and sets the node to the saved result.
+//!
+//! The editor command is taken from `$VISUAL`, falling back to `$EDITOR`, and is given the path of a temp file holding the output of `get`.
+//! If setting the saved contents fails, the errors are added to the top of the file as `//` comments and the editor is opened again.
+//! Saving without changes after an error cancels the edit and returns the error.
+//!
+//! The temp file is created with a name that is hard to guess and only if nothing exists at that path yet,
+//! on unix it can only be accessed by the current user.
+
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::node::Node;
+use crate::node_runner::NodeRunner;
+use crate::node_token::NodeToken;
+
+static FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+const ERROR_PREFIX: &str = "// ";
+
+/// How many names to try before giving up, each name is only used when nothing exists at its path
+const CREATE_ATTEMPTS: usize = 100;
+
+/// Runs the edit action on `node`, returning an empty string on success or the error otherwise
+pub fn edit<T: Node + ?Sized>(node: &mut T) -> String {
+    let editor = match editor() {
+        Some (editor) => editor,
+        None => return String::from("Cannot edit: Set $VISUAL or $EDITOR to the command that runs your editor")
+    };
+
+    let path = match create_temp_file() {
+        Ok (path) => path,
+        Err (err) => return format!("Cannot edit: Failed to create a temp file: {}", err)
+    };
+    let result = edit_loop(node, &editor, &path);
+    fs::remove_file(&path).ok();
+    result
+}
+
+fn edit_loop<T: Node + ?Sized>(node: &mut T, editor: &str, path: &Path) -> String {
+    let mut contents = node.node_step(NodeRunner { tokens: vec!(NodeToken::Get) });
+    let mut error: Option<String> = None;
+    loop {
+        let mut file = String::new();
+        if let Some (ref error) = error {
+            for line in error.lines() {
+                file.push_str(ERROR_PREFIX);
+                file.push_str(line);
+                file.push('\n');
+            }
+            file.push_str("// Fix the errors and save to try again, or save without changes to cancel\n");
+        }
+        file.push_str(&contents);
+        file.push('\n');
+        if let Err (err) = overwrite(path, &file) {
+            return format!("Cannot edit: Failed to write {}: {}", path.display(), err);
+        }
+
+        if let Err (err) = run_editor(editor, path) {
+            return err;
+        }
+
+        let edited = match fs::read_to_string(path) {
+            Ok (edited) => strip_errors(&edited),
+            Err (err) => return format!("Cannot edit: Failed to read {}: {}", path.display(), err)
+        };
+
+        if edited == contents {
+            return error.unwrap_or_default();
+        }
+
+        let result = node.node_step(NodeRunner { tokens: vec!(NodeToken::Set (edited.clone())) });
+        if result.is_empty() {
+            return result;
+        }
+        contents = edited;
+        error = Some(result);
+    }
+}
+
+/// The editor command, $VISUAL takes priority as it is expected to be a full screen editor
+fn editor() -> Option<String> {
+    for var in &["VISUAL", "EDITOR"] {
+        if let Ok (editor) = env::var(var) {
+            if !editor.trim().is_empty() {
+                return Some(editor);
+            }
+        }
+    }
+    None
+}
+
+/// The editor command may include arguments e.g. `code --wait`
+fn run_editor(editor: &str, path: &Path) -> Result<(), String> {
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap();
+    match Command::new(program).args(words).arg(path).status() {
+        Ok (status) if status.success() => Ok(()),
+        Ok (status) => Err(format!("Edit cancelled: The editor exited with {}", status)),
+        Err (err) => Err(format!("Cannot edit: Failed to run '{}': {}", editor, err)),
+    }
+}
+
+/// Create an empty temp file, an existing file or symlink is never opened as another name is tried instead
+fn create_temp_file() -> io::Result<PathBuf> {
+    let mut attempts = 1;
+    loop {
+        let path = temp_path();
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        match options.open(&path) {
+            Ok (_) => return Ok(path),
+            Err (ref err) if err.kind() == io::ErrorKind::AlreadyExists && attempts < CREATE_ATTEMPTS => attempts += 1,
+            Err (err) => return Err(err)
+        }
+    }
+}
+
+/// Write to the temp file without creating it, so a file that was removed is not replaced by whatever is at the path
+fn overwrite(path: &Path, contents: &str) -> io::Result<()> {
+    OpenOptions::new().write(true).truncate(true).open(path)?.write_all(contents.as_bytes())
+}
+
+fn temp_path() -> PathBuf {
+    let count = FILE_COUNT.fetch_add(1, Ordering::SeqCst);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_usize(count);
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_nanos()).unwrap_or(0));
+    env::temp_dir().join(format!("treeflection-edit-{}-{:016x}.json", std::process::id(), hasher.finish()))
+}
+
+/// Removes the error comments added to the top of the file and the newline added to the end.
+/// Any other lines are left alone as they may be part of the value.
+fn strip_errors(file: &str) -> String {
+    let file = file.strip_suffix('\n').map(|x| x.strip_suffix('\r').unwrap_or(x)).unwrap_or(file);
+    let mut rest = file;
+    while rest.starts_with(ERROR_PREFIX) {
+        rest = match rest.find('\n') {
+            Some (end) => &rest[end + 1..],
+            None => ""
+        };
+    }
+    String::from(rest)
+}
@@ -0,0 +1,221 @@
+//! Combines the results of the children accessed by `[*]` and `[?]`.
+//!
+//! The output is a JSON array in the order the children were accessed, with the path of each child and the result of the command on it e.g.
+//! `[{"path": "[0].name", "result": "Fox"}, {"path": "[1].name", "result": "Falco"}]`
+//!
+//! Paths are relative to the node the fan-out was used on and continue up to the next fan-out or the action.
+//! When there is another fan-out later in the command, the result of each child is the array of the nested fan-out,
+//! with paths that continue from the path of the child.
+//!
+//! When the action is an aggregate (`sum`, `min`, `max`, `mean` or `count`) the children are displayed as compact JSON instead
+//! and the output is the aggregate of every result, including those of nested fan-outs.
+//! A child that can't be reached fails the aggregate, except for `count` which only counts the children that were reached.
+//!
+//! `NodeToken::CheckPath` results in an empty string when every child can be reached, otherwise the path and reason of the first that can't.
+
+use serde_json::{Map, Number, Value};
+
+use crate::format::{Format, GetOptions};
+use crate::node_runner::NodeRunner;
+use crate::node_token::NodeToken;
+
+#[derive(Debug, Clone, Copy)]
+enum Aggregate {
+    Sum,
+    Min,
+    Max,
+    Mean,
+    Count,
+}
+
+impl Aggregate {
+    fn from_action(token: &NodeToken) -> Option<Aggregate> {
+        match token {
+            NodeToken::Sum   => Some(Aggregate::Sum),
+            NodeToken::Min   => Some(Aggregate::Min),
+            NodeToken::Max   => Some(Aggregate::Max),
+            NodeToken::Mean  => Some(Aggregate::Mean),
+            NodeToken::Count => Some(Aggregate::Count),
+            _                => None
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Aggregate::Sum   => "sum",
+            Aggregate::Min   => "min",
+            Aggregate::Max   => "max",
+            Aggregate::Mean  => "mean",
+            Aggregate::Count => "count",
+        }
+    }
+}
+
+pub struct FanOut {
+    results:   Vec<Value>,
+    /// The accessors between the fan-out and the next fan-out or action
+    rest:      Vec<NodeToken>,
+    nested:    bool,
+    aggregate: Option<Aggregate>,
+    check_path: bool,
+    /// The path and reason of the first child that can't be reached when checking the path
+    unreachable: Option<String>,
+}
+
+impl FanOut {
+    /// Start combining the results of running `runner` on each child, `runner` has already stepped past the fan-out token.
+    /// An aggregate action in `runner` is replaced by a compact JSON get, so the children display their values.
+    pub fn new(runner: &mut NodeRunner) -> FanOut {
+        // the action is the first token as the tokens are stored in reverse
+        let aggregate = runner.tokens.first().and_then(Aggregate::from_action);
+        if aggregate.is_some() {
+            let options = GetOptions { format: Some(Format::Json), compact: true, .. GetOptions::default() };
+            runner.tokens[0] = NodeToken::GetWith (options);
+        }
+
+        // the tokens of the runner are stored in reverse
+        let rest = runner.tokens.iter().rev()
+            .take_while(|x| is_accessor(x))
+            .cloned()
+            .collect();
+        let nested = runner.tokens.iter().any(|x| matches!(x, NodeToken::ChainAll | NodeToken::ChainContext));
+        let check_path = runner.tokens.first() == Some(&NodeToken::CheckPath);
+        FanOut { results: vec!(), rest, nested, aggregate, check_path, unreachable: None }
+    }
+
+    /// Add the result of the child accessed by `accessor`
+    pub fn push(&mut self, accessor: NodeToken, result: String) {
+        let mut path = String::new();
+        accessor.append_to_path(&mut path);
+        for token in &self.rest {
+            token.append_to_path(&mut path);
+        }
+
+        if self.check_path {
+            if self.unreachable.is_none() && !result.is_empty() {
+                self.unreachable = Some(format!("{}: {}", path, result));
+            }
+            return;
+        }
+
+        let result = match serde_json::from_str::<Value>(&result) {
+            // errors before reaching the nested fan-out are regular strings
+            Ok (Value::Array (mut nested)) if self.nested => {
+                prefix_paths(&mut nested, &path);
+                Value::Array(nested)
+            }
+            _ => Value::String(result)
+        };
+
+        let mut object = Map::new();
+        object.insert(String::from("path"), Value::String(path));
+        object.insert(String::from("result"), result);
+        self.results.push(Value::Object(object));
+    }
+
+    pub fn finish(self) -> String {
+        if self.check_path {
+            return self.unreachable.unwrap_or_default();
+        }
+        match self.aggregate {
+            Some (aggregate) => {
+                let mut pairs = vec!();
+                flatten(&self.results, &mut pairs);
+                match aggregate_pairs(aggregate, &pairs) {
+                    Ok (result) => result,
+                    Err (err) => err
+                }
+            }
+            None => serde_json::to_string_pretty(&self.results).unwrap()
+        }
+    }
+}
+
+/// Each result must be JSON, otherwise it is an error from reaching that child.
+/// Every number in the results is used, including those nested in arrays and objects, other values are skipped.
+/// Count is the number of children that were reached, so it skips errors instead of failing.
+fn aggregate_pairs(aggregate: Aggregate, pairs: &[(String, String)]) -> Result<String, String> {
+    if let Aggregate::Count = aggregate {
+        let count = pairs.iter().filter(|(_, result)| serde_json::from_str::<Value>(result).is_ok()).count();
+        return Ok(count.to_string());
+    }
+
+    let mut numbers = vec!();
+    for (path, result) in pairs {
+        match serde_json::from_str::<Value>(result) {
+            Ok (value) => collect_numbers(&value, &mut numbers),
+            Err (_) => return Err(format!("Cannot {} as '{}' failed: {}", aggregate.name(), path, result))
+        }
+    }
+
+    if numbers.is_empty() {
+        return match aggregate {
+            Aggregate::Sum => Ok(String::from("0")),
+            _ => Err(format!("Cannot {} as there are no numbers", aggregate.name()))
+        };
+    }
+
+    // integers stay integers unless the result needs a fraction
+    let integers: Option<Vec<i128>> = numbers.iter().map(|x| x.as_i64().map(i128::from).or_else(|| x.as_u64().map(i128::from))).collect();
+    let floats: Vec<f64> = numbers.iter().map(|x| x.as_f64().unwrap()).collect();
+    let result = match (aggregate, integers) {
+        (Aggregate::Sum, Some (integers)) => integers.iter().sum::<i128>().to_string(),
+        (Aggregate::Min, Some (integers)) => integers.iter().min().unwrap().to_string(),
+        (Aggregate::Max, Some (integers)) => integers.iter().max().unwrap().to_string(),
+        (Aggregate::Sum, None) => float_to_string(floats.iter().sum()),
+        (Aggregate::Min, None) => float_to_string(floats.iter().cloned().fold(f64::INFINITY, f64::min)),
+        (Aggregate::Max, None) => float_to_string(floats.iter().cloned().fold(f64::NEG_INFINITY, f64::max)),
+        (Aggregate::Mean, _)   => float_to_string(floats.iter().sum::<f64>() / floats.len() as f64),
+        (Aggregate::Count, _)  => unreachable!(),
+    };
+    Ok(result)
+}
+
+fn collect_numbers(value: &Value, numbers: &mut Vec<Number>) {
+    match value {
+        Value::Number (number) => numbers.push(number.clone()),
+        Value::Array (array) => {
+            for item in array {
+                collect_numbers(item, numbers);
+            }
+        }
+        Value::Object (map) => {
+            for item in map.values() {
+                collect_numbers(item, numbers);
+            }
+        }
+        _ => { }
+    }
+}
+
+fn float_to_string(value: f64) -> String {
+    serde_json::to_string(&value).unwrap()
+}
+
+/// Collect the (path, result) pairs of `results`, nested fan-outs are flattened into the pairs of their children
+fn flatten(results: &[Value], pairs: &mut Vec<(String, String)>) -> Option<()> {
+    for result in results {
+        let path = result.get("path")?.as_str()?;
+        match result.get("result")? {
+            Value::String (result) => pairs.push((path.to_string(), result.clone())),
+            Value::Array (nested)  => flatten(nested, pairs)?,
+            _ => return None
+        }
+    }
+    Some(())
+}
+
+fn prefix_paths(results: &mut [Value], prefix: &str) {
+    for result in results {
+        if let Some (Value::String (path)) = result.get_mut("path") {
+            *path = format!("{}{}", prefix, path);
+        }
+        if let Some (Value::Array (nested)) = result.get_mut("result") {
+            prefix_paths(nested, prefix);
+        }
+    }
+}
+
+fn is_accessor(token: &NodeToken) -> bool {
+    matches!(token, NodeToken::ChainProperty (_) | NodeToken::ChainIndex (_) | NodeToken::ChainKey (_))
+}
@@ -0,0 +1,258 @@
+//! The formats `get` and `set` can use instead of pretty printed JSON, and the other options of `get`.
+//!
+//! A format is chosen per command e.g. `:get ron` and `:set ron (x: 1)`, or for every get and set run through a `Session` with `format ron`.
+//! RON, YAML and TOML are only available with the `ron`, `yaml` and `toml` features.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    /// Pretty printed JSON, the format used by a plain `get` and `set`
+    Json,
+    /// JSON on a single line
+    JsonCompact,
+    Ron,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    /// The format with the name used in commands, or None if there is no such format
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "json"    => Some(Format::Json),
+            "compact" => Some(Format::JsonCompact),
+            "ron"     => Some(Format::Ron),
+            "yaml"    => Some(Format::Yaml),
+            "toml"    => Some(Format::Toml),
+            _         => None
+        }
+    }
+
+    /// The name used in commands
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Json        => "json",
+            Format::JsonCompact => "compact",
+            Format::Ron         => "ron",
+            Format::Yaml        => "yaml",
+            Format::Toml        => "toml",
+        }
+    }
+}
+
+pub const FORMAT_NAMES: &str = "json, compact, ron, yaml, toml";
+
+/// How `get` displays a value, parsed from the arguments of `get` e.g. `:get ron --depth 2 --sorted`
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct GetOptions {
+    /// None uses the format of the session, or JSON outside of a session
+    pub format:  Option<Format>,
+    /// Display on a single line, JSON and RON support this
+    pub compact: bool,
+    /// Objects and arrays nested deeper than this many levels are replaced with `…`
+    pub depth:   Option<usize>,
+    /// Sort the keys of maps and structs, giving the same output for equal values
+    pub sorted:  bool,
+}
+
+impl GetOptions {
+    pub fn parse(args: &[&str]) -> Result<GetOptions, String> {
+        let mut options = GetOptions::default();
+        let mut args = args.iter();
+        while let Some (arg) = args.next() {
+            match *arg {
+                "--compact" => options.compact = true,
+                "--sorted"  => options.sorted = true,
+                "--depth"   => {
+                    match args.next().map(|x| x.parse()) {
+                        Some (Ok (depth)) => options.depth = Some(depth),
+                        _ => return Err(String::from("--depth needs to be followed by a number"))
+                    }
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown get option '{}' (try one of: --compact, --depth, --sorted)", arg)),
+                _ => {
+                    match Format::from_name(arg) {
+                        Some (format) if options.format.is_none() => options.format = Some(format),
+                        Some (_) => return Err(String::from("get can only use one format")),
+                        None => return Err(format!("Unknown format '{}' (try one of: {})", arg, FORMAT_NAMES))
+                    }
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Serialize `value` in `format`
+pub fn to_string<T: Serialize + ?Sized>(value: &T, format: Format) -> Result<String, String> {
+    match format {
+        Format::Json        => serde_json::to_string_pretty(value).map_err(|x| x.to_string()),
+        Format::JsonCompact => serde_json::to_string(value).map_err(|x| x.to_string()),
+        Format::Ron         => ron_to_string(value, false),
+        Format::Yaml        => yaml_to_string(value),
+        Format::Toml        => toml_to_string(value),
+    }
+}
+
+/// Deserialize `value` from `format`
+pub fn from_str<T: DeserializeOwned>(value: &str, format: Format) -> Result<T, String> {
+    match format {
+        Format::Json | Format::JsonCompact => serde_json::from_str(value).map_err(|x| x.to_string()),
+        Format::Ron  => ron_from_str(value),
+        Format::Yaml => yaml_from_str(value),
+        Format::Toml => toml_from_str(value),
+    }
+}
+
+/// The result of a `get` with `options`, which is the error if serializing fails
+pub fn get<T: Serialize + ?Sized>(value: &T, options: &GetOptions) -> String {
+    let format = options.format.unwrap_or(Format::Json);
+    let result = if options.depth.is_some() || options.sorted {
+        // the value is trimmed and sorted as JSON before converting it to the format
+        serde_json::to_value(value).map_err(|x| x.to_string()).and_then(|mut value| {
+            if let Some (depth) = options.depth {
+                elide(&mut value, depth);
+            }
+            if options.sorted {
+                sort_keys(&mut value);
+            }
+            to_string_compact(&value, format, options.compact)
+        })
+    } else {
+        to_string_compact(value, format, options.compact)
+    };
+
+    match result {
+        Ok (result) => result,
+        Err (err) => format!("Failed to get as {}: {}", format.name(), err)
+    }
+}
+
+/// YAML and TOML don't have a single line form so compact has no effect on them
+fn to_string_compact<T: Serialize + ?Sized>(value: &T, format: Format, compact: bool) -> Result<String, String> {
+    match format {
+        Format::Json if compact => to_string(value, Format::JsonCompact),
+        Format::Ron => ron_to_string(value, compact),
+        _ => to_string(value, format)
+    }
+}
+
+/// Replace non-empty objects and arrays nested deeper than `depth` with `…`
+fn elide(value: &mut Value, depth: usize) {
+    match value {
+        Value::Array (array) if !array.is_empty() && depth == 0 => *value = Value::String(String::from("…")),
+        Value::Object (map) if !map.is_empty() && depth == 0 => *value = Value::String(String::from("…")),
+        Value::Array (array) => {
+            for item in array {
+                elide(item, depth - 1);
+            }
+        }
+        Value::Object (map) => {
+            for item in map.values_mut() {
+                elide(item, depth - 1);
+            }
+        }
+        _ => { }
+    }
+}
+
+/// Rebuilds every object with its keys in order.
+/// Without serde_json's preserve_order feature objects are already sorted, this ensures it when another crate enables it.
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Array (array) => {
+            for item in array {
+                sort_keys(item);
+            }
+        }
+        Value::Object (map) => {
+            let mut pairs: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            pairs.sort_by(|a, b| a.0.cmp(&b.0));
+            let mut sorted = Map::new();
+            for (key, mut item) in pairs {
+                sort_keys(&mut item);
+                sorted.insert(key, item);
+            }
+            *map = sorted;
+        }
+        _ => { }
+    }
+}
+
+/// Set `node` to `value` deserialized from `format`, `node` is unchanged if deserializing fails
+pub fn set<T: DeserializeOwned>(node: &mut T, value: &str, format: Format) -> Result<(), String> {
+    *node = from_str(value, format)?;
+    Ok(())
+}
+
+#[cfg(feature = "ron")]
+fn ron_to_string<T: Serialize + ?Sized>(value: &T, compact: bool) -> Result<String, String> {
+    if compact {
+        ron::ser::to_string(value).map_err(|x| x.to_string())
+    } else {
+        ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).map_err(|x| x.to_string())
+    }
+}
+
+#[cfg(feature = "ron")]
+fn ron_from_str<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    ron::from_str(value).map_err(|x| x.to_string())
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, String> {
+    serde_yaml::to_string(value).map(|x| x.trim_end().to_string()).map_err(|x| x.to_string())
+}
+
+#[cfg(feature = "yaml")]
+fn yaml_from_str<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_yaml::from_str(value).map_err(|x| x.to_string())
+}
+
+#[cfg(feature = "toml")]
+fn toml_to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, String> {
+    toml::to_string_pretty(value).map(|x| x.trim_end().to_string()).map_err(|x| x.to_string())
+}
+
+#[cfg(feature = "toml")]
+fn toml_from_str<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    toml::from_str(value).map_err(|x: toml::de::Error| x.to_string().trim_end().to_string())
+}
+
+#[cfg(not(feature = "ron"))]
+fn ron_to_string<T: Serialize + ?Sized>(_value: &T, _compact: bool) -> Result<String, String> {
+    Err(not_enabled(Format::Ron))
+}
+
+#[cfg(not(feature = "ron"))]
+fn ron_from_str<T: DeserializeOwned>(_value: &str) -> Result<T, String> {
+    Err(not_enabled(Format::Ron))
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_to_string<T: Serialize + ?Sized>(_value: &T) -> Result<String, String> {
+    Err(not_enabled(Format::Yaml))
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_from_str<T: DeserializeOwned>(_value: &str) -> Result<T, String> {
+    Err(not_enabled(Format::Yaml))
+}
+
+#[cfg(not(feature = "toml"))]
+fn toml_to_string<T: Serialize + ?Sized>(_value: &T) -> Result<String, String> {
+    Err(not_enabled(Format::Toml))
+}
+
+#[cfg(not(feature = "toml"))]
+fn toml_from_str<T: DeserializeOwned>(_value: &str) -> Result<T, String> {
+    Err(not_enabled(Format::Toml))
+}
+
+#[cfg(not(all(feature = "ron", feature = "yaml", feature = "toml")))]
+fn not_enabled(format: Format) -> String {
+    format!("The {} format requires the {} feature of treeflection", format.name(), format.name())
+}
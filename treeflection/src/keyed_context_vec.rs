@@ -8,6 +8,10 @@ use serde::de::DeserializeOwned;
 use serde::ser::Serialize;
 use serde_json;
 
+use crate::edit;
+use crate::fan_out::FanOut;
+use crate::format::{self, Format};
+use crate::node;
 use crate::node::Node;
 use crate::node_runner::NodeRunner;
 use crate::node_token::NodeToken;
@@ -236,6 +240,48 @@ impl<T> KeyedContextVec<T> {
         }
     }
 
+    /// Change the key of a value, the value keeps its index.
+    /// Panics if `from` is not used or `to` is already used.
+    pub fn rename_key(&mut self, from: &str, to: String) {
+        if self.keys.contains(&to) {
+            panic!("Key is already used.");
+        }
+        match self.key_to_index(from) {
+            Some (index) => self.keys[index] = to,
+            None => panic!("Key is not used.")
+        }
+    }
+
+    /// Move the value with `key` to `index`, shifting the values in between.
+    /// Context indices continue to point to the same values.
+    /// Panics if `key` is not used or `index` is out of bounds.
+    pub fn move_key(&mut self, key: &str, index: usize) {
+        let from = match self.key_to_index(key) {
+            Some (from) => from,
+            None => panic!("Key is not used.")
+        };
+        if index >= self.vector.len() {
+            panic!("Attempted to move to index {} on a KeyedContextVec of length {}", index, self.vector.len());
+        }
+
+        let value = self.vector.remove(from);
+        let key = self.keys.remove(from);
+        self.vector.insert(index, value);
+        self.keys.insert(index, key);
+
+        for i in self.context.iter_mut() {
+            if *i == from {
+                *i = index;
+            }
+            else if from < *i && *i <= index {
+                *i -= 1;
+            }
+            else if index <= *i && *i < from {
+                *i += 1;
+            }
+        }
+    }
+
     /// Retrieve the index corresponding to the given key.
     /// This operation is O(n)
     pub fn key_to_value(&self, key_search: &str) -> Option<&T> {
@@ -417,17 +463,23 @@ impl<T> DerefMut for KeyedContextVec<T> {
 
 impl<T> Node for KeyedContextVec<T> where T: Node + Serialize + DeserializeOwned + Default {
     fn node_step(&mut self, mut runner: NodeRunner) -> String {
-        match runner.step() {
+        let token = match runner.step() {
+            // a lone number is parsed as an index, but an index is only used along with a key so it is the key
+            NodeToken::InsertIndex (index) => NodeToken::InsertKey (index.to_string()),
+            token => token
+        };
+        match token {
             NodeToken::ChainIndex (index) => {
                 let length = self.vector.len();
                 match self.vector.get_mut(index) {
                     Some (item) => item.node_step(runner),
                     None => {
-                        return match length {
+                        let error = match length {
                              0 => format!("Used index {} on an empty keyed context vector", index),
                              1 => format!("Used index {} on a keyed context vector of size 1 (try 0)", index),
                              _ => format!("Used index {} on a keyed context vector of size {} (try a value between 0-{})", index, length, length-1)
-                        }
+                        };
+                        node::path_error(&runner, error)
                     }
                 }
             }
@@ -437,38 +489,37 @@ impl<T> Node for KeyedContextVec<T> where T: Node + Serialize + DeserializeOwned
                     Some (item) => { return item.node_step(runner) }
                     None => { }
                 }
-                match length {
+                let error = match length {
                      0 => {
                         format!("Used key '{}' on an empty keyed context vector.", key)
                      }
                      _ => {
                         format!("Used key '{}' on a keyed context vector that does not contain it. Try one of: {}", key, self.format_keys())
                     }
-                }
+                };
+                node::path_error(&runner, error)
             }
             NodeToken::ChainContext => {
-                let mut combined = String::from("|");
+                let mut fan_out = FanOut::new(&mut runner);
                 for i in self.context.iter() {
-                    match self.vector.get_mut(*i) {
-                        Some(ref mut node) => {
-                            let result = node.node_step(runner.clone());
-                            combined.push_str(result.as_str());
-                        }
-                        None => {
-                            combined.push_str("Context out of range. This should never happen.");
-                        }
-                    }
-                    combined.push('|');
+                    let result = match self.vector.get_mut(*i) {
+                        Some(ref mut node) => node.node_step(runner.clone()),
+                        None => node::path_error(&runner, String::from("Context out of range. This should never happen."))
+                    };
+                    let path = match self.keys.get(*i) {
+                        Some (key) => NodeToken::ChainKey (key.clone()),
+                        None       => NodeToken::ChainIndex (*i),
+                    };
+                    fan_out.push(path, result);
                 }
-                combined
+                fan_out.finish()
             }
             NodeToken::ChainAll => {
-                let mut combined = String::from("|");
-                for item in self.vector.iter_mut() {
-                    combined.push_str(item.node_step(runner.clone()).as_ref());
-                    combined.push('|');
+                let mut fan_out = FanOut::new(&mut runner);
+                for (key, item) in self.keys.iter().zip(self.vector.iter_mut()) {
+                    fan_out.push(NodeToken::ChainKey (key.clone()), item.node_step(runner.clone()));
                 }
-                combined
+                fan_out.finish()
             }
             NodeToken::ChainProperty (ref s) if s == "length" => { self.vector.len().node_step(runner) }
             NodeToken::GetKeys => {
@@ -478,15 +529,13 @@ impl<T> Node for KeyedContextVec<T> where T: Node + Serialize + DeserializeOwned
                 serde_json::to_string_pretty(&mut self.vector).unwrap()
             }
             NodeToken::Set (value) => {
-                match serde_json::from_str(&value) {
-                    Ok(result) => {
-                        self.vector = result;
-                        String::from("")
-                    }
-                    Err(err) => {
-                        format!("keyed context vector set error: {}", err)
-                    }
-                }
+                set_vector(self, &value, Format::Json)
+            }
+            NodeToken::GetWith (options) => {
+                format::get(&self.vector, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                set_vector(self, &value, format)
             }
             NodeToken::InsertKey (key) => {
                 if self.contains_key(&key) {
@@ -507,6 +556,19 @@ impl<T> Node for KeyedContextVec<T> where T: Node + Serialize + DeserializeOwned
                     String::new()
                 }
             }
+            NodeToken::InsertKeyValue (key, value) => {
+                if self.contains_key(&key) {
+                    format!("Tried to insert with key '{}' on a keyed context vector that already contains it. Current keys: {}", key, self.format_keys())
+                } else {
+                    match serde_json::from_str(&value) {
+                        Ok (value) => {
+                            self.push(key, value);
+                            String::new()
+                        }
+                        Err (err) => format!("keyed context vector insert error: {}", err)
+                    }
+                }
+            }
             NodeToken::Remove => {
                 if self.len() == 0 {
                     String::from("Tried to remove from an empty keyed context vector.")
@@ -532,32 +594,103 @@ impl<T> Node for KeyedContextVec<T> where T: Node + Serialize + DeserializeOwned
                     format!("Tried to remove the value with key '{}' on a keyed context vector that doesnt contain it. Current keys: {}", key, self.format_keys())
                 }
             }
+            NodeToken::CheckRemoveIndex (index) if index < self.len() => { String::new() }
+            NodeToken::CheckRemoveKey (key) if self.contains_key(&key) => { String::new() }
             NodeToken::SetDefault => {
-                self.vector = vec!();
+                self.clear();
                 String::new()
             }
+            NodeToken::Custom (action, args) => {
+                match action.as_ref() {
+                    "rename" | "copykey" => {
+                        let (from, to) = match (args.first(), args.get(1)) {
+                            (Some (from), Some (to)) => (from, to.clone()),
+                            _ => return String::from("Missing value (needs to be: a key followed by a new key)")
+                        };
+                        let verb = if action == "rename" { "rename" } else { "copy" };
+                        if !self.contains_key(from) {
+                            format!("Tried to {} key '{}' on a keyed context vector that doesnt contain it. Current keys: {}", verb, from, self.format_keys())
+                        } else if from == &to && action == "rename" {
+                            String::new()
+                        } else if self.contains_key(&to) {
+                            format!("Tried to {} key '{}' to '{}' on a keyed context vector that already contains it. Current keys: {}", verb, from, to, self.format_keys())
+                        } else if action == "rename" {
+                            self.rename_key(from, to);
+                            String::new()
+                        } else {
+                            match node::copy_value(self.key_to_value(from).unwrap()) {
+                                Ok (copy) => {
+                                    self.push(to, copy);
+                                    String::new()
+                                }
+                                Err (err) => format!("Failed to copy key '{}': {}", from, err)
+                            }
+                        }
+                    }
+                    "movekey" => {
+                        let (key, index) = match (args.first(), args.get(1).map(|x| x.parse::<usize>())) {
+                            (Some (key), Some (Ok (index))) => (key, index),
+                            _ => return String::from("Missing value (needs to be: a key followed by an index)")
+                        };
+                        let length = self.len();
+                        if !self.contains_key(key) {
+                            format!("Tried to move key '{}' on a keyed context vector that doesnt contain it. Current keys: {}", key, self.format_keys())
+                        } else if index >= length {
+                            format!("Tried to move key '{}' to index {} on a keyed context vector of size {} (try a value between 0-{})", key, index, length, length - 1)
+                        } else {
+                            self.move_key(key, index);
+                            String::new()
+                        }
+                    }
+                    _ => format!("keyed context vector cannot '{}'", action)
+                }
+            }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
             NodeToken::Help => {
                 String::from(r#"
 Keyed Context Vector Help
 
 Commands:
-*   help               - display this help
-*   get                - display JSON
-*   getkeys            - display keys
-*   set                - set to JSON
-*   insert $KEY        - create a new element at the end of the vector with $KEY
-*   insert $INDEX $KEY - create a new element at $INDEX with $KEY
-*   remove             - remove the element
-*   remove $KEY        - remove the element with $KEY
-*   remove $INDEX      - remove the element at $INDEX
-*   reset              - reset to empty vector
+*   help                  - display this help
+*   get                   - display JSON
+*   getkeys               - display keys
+*   set                   - set to JSON
+*   edit                  - edit JSON in your editor
+*   type                  - display the Rust type
+*   insert $KEY           - create a new element at the end of the vector with $KEY
+*   insert $INDEX $KEY    - create a new element at $INDEX with $KEY
+*   insert $KEY $JSON     - insert $JSON at the end of the vector with $KEY
+*   rename $KEY $NEW_KEY  - change $KEY to $NEW_KEY
+*   copykey $KEY $NEW_KEY - insert a copy of the element with $KEY at the end of the vector with $NEW_KEY
+*   movekey $KEY $INDEX   - move the element with $KEY to $INDEX
+*   remove                - remove the element
+*   remove $KEY           - remove the element with $KEY
+*   remove $INDEX         - remove the element at $INDEX
+*   reset                 - reset to empty vector
 
 Accessors:
 *   [INDEX] - access item at INDEX
 *   [?]     - access items at current context
 *   .length - display number of items"#)
             }
-            action => { format!("keyed context vector cannot '{:?}'", action) }
+            action => { node::path_error(&runner, format!("keyed context vector cannot '{:?}'", action)) }
+        }
+    }
+}
+
+/// Replace the values of `vec` with `value`, which must have a value for each key as the values are displayed without their keys.
+/// The context is cleared as it may no longer point to the values it was set to.
+fn set_vector<T: DeserializeOwned>(vec: &mut KeyedContextVec<T>, value: &str, format: Format) -> String {
+    match format::from_str::<Vec<T>>(value, format) {
+        Ok (vector) if vector.len() == vec.keys.len() => {
+            vec.context.clear();
+            vec.vector = vector;
+            String::new()
         }
+        Ok (vector) => format!("keyed context vector set error: Expected {} values, one for each key, but got {} (use insert and remove to change the keys)", vec.keys.len(), vector.len()),
+        Err (err) => format!("keyed context vector set error: {}", err)
     }
 }
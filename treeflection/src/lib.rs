@@ -2,12 +2,33 @@
 
 pub use node_runner::NodeRunner;
 pub use node::Node;
+pub use node_actions::NodeActions;
 pub use context_vec::ContextVec;
 pub use keyed_context_vec::KeyedContextVec;
 pub use node_token::NodeToken;
+pub use format::{Format, GetOptions};
+pub use session::Session;
+pub use command_queue::{CommandQueue, CommandHandle, PendingCommand, Budget};
+
+// used by the code generated by treeflection_derive, so users don't need to depend on them directly
+pub use serde;
+pub use serde_json;
 
 pub mod node;
+pub mod node_actions;
 pub mod node_runner;
 pub mod context_vec;
 pub mod keyed_context_vec;
 pub mod node_token;
+pub mod session;
+pub mod command_queue;
+pub mod clipboard;
+pub mod args;
+pub mod compose;
+pub mod condition;
+pub mod edit;
+pub mod fan_out;
+pub mod format;
+pub mod reorder;
+#[cfg(feature = "server")]
+pub mod server;
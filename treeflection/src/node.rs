@@ -3,12 +3,23 @@ use std::collections::HashMap;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde_json;
+use serde_json::{Number, Value};
 
+use crate::edit;
+use crate::fan_out::FanOut;
+use crate::format;
 use crate::node_runner::NodeRunner;
 use crate::node_token::NodeToken;
+use crate::reorder;
 
 pub trait Node {
     fn node_step(&mut self, runner: NodeRunner) -> String;
+
+    /// Returns true if `property` can be accessed on this node.
+    /// Used to pass properties through to fields marked with `#[serde(flatten)]`.
+    fn node_has_property(&self, _property: &str) -> bool {
+        false
+    }
 }
 
 impl<T> Node for Vec<T> where T: Node + Serialize + DeserializeOwned + Default {
@@ -19,21 +30,21 @@ impl<T> Node for Vec<T> where T: Node + Serialize + DeserializeOwned + Default {
                 match self.get_mut(index) {
                     Some (item) => item.node_step(runner),
                     None => {
-                        return match length {
+                        let error = match length {
                              0 => format!("Used index {} on an empty vector", index),
                              1 => format!("Used index {} on a vector of size 1 (try 0)", index),
                              _ => format!("Used index {} on a vector of size {} (try a value between 0-{})", index, length, length-1)
-                        }
+                        };
+                        path_error(&runner, error)
                     }
                 }
             }
             NodeToken::ChainAll => {
-                let mut combined = String::from("|");
-                for item in self {
-                    combined.push_str(item.node_step(runner.clone()).as_ref());
-                    combined.push('|');
+                let mut fan_out = FanOut::new(&mut runner);
+                for (i, item) in self.iter_mut().enumerate() {
+                    fan_out.push(NodeToken::ChainIndex (i), item.node_step(runner.clone()));
                 }
-                combined
+                fan_out.finish()
             }
             NodeToken::ChainProperty (ref s) if s == "length" => { self.len().node_step(runner) } // TODO: yeah this should really be a command not a property
             NodeToken::Get => {
@@ -50,6 +61,15 @@ impl<T> Node for Vec<T> where T: Node + Serialize + DeserializeOwned + Default {
                     }
                 }
             }
+            NodeToken::GetWith (options) => {
+                format::get(self, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                match format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("vector set error: {}", err)
+                }
+            }
             NodeToken::InsertIndex (index) => {
                 let max_index = self.len();
                 if index > max_index {
@@ -60,6 +80,31 @@ impl<T> Node for Vec<T> where T: Node + Serialize + DeserializeOwned + Default {
                     String::new()
                 }
             }
+            // a single argument after an index is parsed as a key, vectors have no keys so it is the value
+            NodeToken::InsertIndexValue (index, value) | NodeToken::InsertIndexKey (index, value) => {
+                let max_index = self.len();
+                if index > max_index {
+                    format!("Tried to insert at index {} on a vector of size {} (try a value between 0-{})", index, max_index, max_index)
+                }
+                else {
+                    match serde_json::from_str(&value) {
+                        Ok (value) => {
+                            self.insert(index, value);
+                            String::new()
+                        }
+                        Err (err) => format!("vector insert error: {}", err)
+                    }
+                }
+            }
+            NodeToken::Push (value) => {
+                match serde_json::from_str(&value) {
+                    Ok (value) => {
+                        self.push(value);
+                        String::new()
+                    }
+                    Err (err) => format!("vector push error: {}", err)
+                }
+            }
             NodeToken::RemoveIndex (index) => {
                 let max_index = self.len() - 1;
                 if index > max_index {
@@ -70,58 +115,81 @@ impl<T> Node for Vec<T> where T: Node + Serialize + DeserializeOwned + Default {
                     String::new()
                 }
             }
+            NodeToken::CheckRemoveIndex (index) if index < self.len() => { String::new() }
             NodeToken::SetDefault => {
                 *self = vec!();
                 String::new()
             }
+            NodeToken::Custom (action, args) => {
+                match reorder::run(self, &action, &args) {
+                    Some (Ok (_))    => String::new(),
+                    Some (Err (err)) => err,
+                    None             => format!("vector cannot '{}'", action)
+                }
+            }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
             NodeToken::Help => {
                 String::from(r#"
 Vector Help
 
 Commands:
-*   help    - display this help
-*   get     - display JSON
-*   set     - set to JSON
-*   insert  - create a new element
-*   remove  - remove an element
-*   reset   - reset to empty vector
+*   help                 - display this help
+*   get                  - display JSON
+*   set                  - set to JSON
+*   edit                 - edit JSON in your editor
+*   type                 - display the Rust type
+*   insert               - create a new element
+*   insert $INDEX $JSON  - insert $JSON at $INDEX
+*   push $JSON           - insert $JSON at the end
+*   remove               - remove an element
+*   reset                - reset to empty vector
+*   swap $INDEX $INDEX   - swap the elements at the two indexes
+*   move $FROM $TO       - move the element at $FROM to $TO
+*   reverse              - reverse the order of the elements
+*   duplicate $INDEX     - insert a copy of the element at $INDEX after it
+*   truncate $LENGTH     - remove the elements from $LENGTH onwards
+*   resize $LENGTH       - remove or create elements until there are $LENGTH
+*   sortby $PATH [ORDER] - sort by the value at $PATH in each element, ORDER is ascending or descending
 
 Accessors:
 *   [index] - access item at index
 *   .length - display number of items"#)
             }
-            action => { format!("vector cannot '{:?}'", action) }
+            action => { path_error(&runner, format!("vector cannot '{:?}'", action)) }
         }
     }
 }
 
 impl<T> Node for HashMap<String, T> where T: Node + Serialize + DeserializeOwned + Default {
     fn node_step(&mut self, mut runner: NodeRunner) -> String {
-        match runner.step() {
+        match index_as_key(runner.step()) {
             NodeToken::ChainKey (key) => {
                 let length = self.len();
                 match self.get_mut(&key) {
                     Some (item) => { return item.node_step(runner) }
                     None        => { }
                 }
-                match length {
+                let error = match length {
                      0 => {
                         format!("Used key '{}' on an empty map.", key)
                      }
                      _ => {
                         format!("Used key '{}' on a map that does not contain it. Try one of: {}", key, format_keys(self))
                     }
-                }
+                };
+                path_error(&runner, error)
             }
             NodeToken::ChainAll => {
-                let mut combined = String::from("|");
+                let mut fan_out = FanOut::new(&mut runner);
                 let mut pairs: Vec<_> = self.iter_mut().collect();
                 pairs.sort_by_key(|x| x.0);
-                for (_, item) in pairs {
-                    combined.push_str(item.node_step(runner.clone()).as_ref());
-                    combined.push('|');
+                for (key, item) in pairs {
+                    fan_out.push(NodeToken::ChainKey (key.clone()), item.node_step(runner.clone()));
                 }
-                combined
+                fan_out.finish()
             }
             NodeToken::GetKeys => {
                 format_keys(self)
@@ -141,6 +209,15 @@ impl<T> Node for HashMap<String, T> where T: Node + Serialize + DeserializeOwned
                     }
                 }
             }
+            NodeToken::GetWith (options) => {
+                format::get(self, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                match format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("map set error: {}", err)
+                }
+            }
             NodeToken::InsertKey (key) => {
                 if self.contains_key(&key) {
                     format!("Tried to insert key '{}' on a map that already contains it. Current keys: {}", key, format_keys(self))
@@ -150,6 +227,18 @@ impl<T> Node for HashMap<String, T> where T: Node + Serialize + DeserializeOwned
                     String::new()
                 }
             }
+            NodeToken::InsertKeyValue (key, value) => {
+                if self.contains_key(&key) {
+                    return format!("Tried to insert key '{}' on a map that already contains it. Current keys: {}", key, format_keys(self));
+                }
+                match serde_json::from_str(&value) {
+                    Ok (value) => {
+                        self.insert(key, value);
+                        String::new()
+                    }
+                    Err (err) => format!("map insert error: {}", err)
+                }
+            }
             NodeToken::RemoveKey (key) => {
                 if let None = self.remove(&key) {
                     format!("Tried to remove key '{}' on a map that doesnt contain it. Current keys: {}", key, format_keys(self))
@@ -158,32 +247,97 @@ impl<T> Node for HashMap<String, T> where T: Node + Serialize + DeserializeOwned
                     String::new()
                 }
             }
+            NodeToken::CheckRemoveKey (key) if self.contains_key(&key) => { String::new() }
             NodeToken::SetDefault => {
                 *self = HashMap::new();
                 String::new()
             }
+            NodeToken::Custom (action, args) => {
+                match action.as_ref() {
+                    "rename" | "copykey" => {
+                        let (from, to) = match (args.first(), args.get(1)) {
+                            (Some (from), Some (to)) => (from, to.clone()),
+                            _ => return String::from("Missing value (needs to be: a key followed by a new key)")
+                        };
+                        let verb = if action == "rename" { "rename" } else { "copy" };
+                        if !self.contains_key(from) {
+                            return format!("Tried to {} key '{}' on a map that doesnt contain it. Current keys: {}", verb, from, format_keys(self));
+                        }
+                        if from == &to && action == "rename" {
+                            return String::new();
+                        }
+                        if self.contains_key(&to) {
+                            return format!("Tried to {} key '{}' to '{}' on a map that already contains it. Current keys: {}", verb, from, to, format_keys(self));
+                        }
+
+                        let value = if action == "rename" {
+                            self.remove(from).unwrap()
+                        } else {
+                            match copy_value(&self[from]) {
+                                Ok (copy) => copy,
+                                Err (err) => return format!("Failed to copy key '{}': {}", from, err)
+                            }
+                        };
+                        self.insert(to, value);
+                        String::new()
+                    }
+                    _ => format!("map cannot '{}'", action)
+                }
+            }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
             NodeToken::Help => {
                 String::from(r#"
 Map Help
 
 Commands:
-*   help    - display this help
-*   get     - display JSON
-*   getkeys - display keys
-*   set     - set to JSON
-*   insert  - create a new element
-*   remove  - remove an element
-*   reset   - reset to empty map
+*   help                  - display this help
+*   get                   - display JSON
+*   getkeys               - display keys
+*   set                   - set to JSON
+*   edit                  - edit JSON in your editor
+*   type                  - display the Rust type
+*   insert                - create a new element
+*   insert $KEY $JSON     - insert $JSON with $KEY
+*   rename $KEY $NEW_KEY  - change $KEY to $NEW_KEY
+*   copykey $KEY $NEW_KEY - insert a copy of the element with $KEY with $NEW_KEY
+*   remove                - remove an element
+*   reset                 - reset to empty map
 
 Accessors:
 *   [key]   - access item at the string key
 *   .length - display number of items"#)
             }
-            action => { format!("map cannot '{:?}'", action) }
+            action => { path_error(&runner, format!("map cannot '{:?}'", action)) }
         }
     }
 }
 
+/// Copy a value that is only required to be serializable, by converting it to JSON and back
+pub fn copy_value<T: Serialize + DeserializeOwned>(value: &T) -> Result<T, String> {
+    serde_json::to_value(value).and_then(serde_json::from_value).map_err(|x| x.to_string())
+}
+
+/// The result of a command that can't continue past this node, `error` explains why.
+/// `:exists` displays false instead of the error, the remaining tokens of `runner` decide which.
+pub fn path_error(runner: &NodeRunner, error: String) -> String {
+    match runner.tokens.first() {
+        Some (NodeToken::Exists) => String::from("false"),
+        _                        => error
+    }
+}
+
+/// A number given to insert is parsed as an index, nodes that have keys but no indexes use this to treat the number as a key
+fn index_as_key(token: NodeToken) -> NodeToken {
+    match token {
+        NodeToken::InsertIndex (index) => NodeToken::InsertKey (index.to_string()),
+        NodeToken::InsertIndexKey (index, value) | NodeToken::InsertIndexValue (index, value) => NodeToken::InsertKeyValue (index.to_string(), value),
+        token => token
+    }
+}
+
 fn format_keys<T>(map: &HashMap<String, T>) -> String {
     let mut key_list: Vec<String> = map.keys().map(|x| format!("'{}'", x)).collect();
     key_list.sort();
@@ -201,16 +355,15 @@ macro_rules! tuple_node {
                             $(
                                 $indexes => self.$indexes.node_step(runner),
                             )*
-                            _ => format!("Used index {} on a {}", index, name)
+                            _ => path_error(&runner, format!("Used index {} on a {}", index, name))
                         }
                     }
                     NodeToken::ChainAll => {
-                        let mut combined = String::from("|");
+                        let mut fan_out = FanOut::new(&mut runner);
                         $(
-                            combined.push_str(self.$indexes.node_step(runner.clone()).as_ref());
-                            combined.push('|');
+                            fan_out.push(NodeToken::ChainIndex ($indexes), self.$indexes.node_step(runner.clone()));
                         )*
-                        combined
+                        fan_out.finish()
                     }
                     NodeToken::Get => {
                         serde_json::to_string_pretty(self).unwrap()
@@ -226,6 +379,19 @@ macro_rules! tuple_node {
                             }
                         }
                     }
+                    NodeToken::GetWith (options) => {
+                        format::get(self, &options)
+                    }
+                    NodeToken::SetWith (format, value) => {
+                        match format::set(self, &value, format) {
+                            Ok (()) => String::new(),
+                            Err (err) => format!("{} set error: {}", name, err)
+                        }
+                    }
+                    NodeToken::Edit => { edit::edit(self) }
+                    NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+                    NodeToken::Exists  => { String::from("true") }
+                    NodeToken::CheckPath => { String::new() }
                     NodeToken::Help => {
                         String::from(r#"
 Tuple Help
@@ -234,11 +400,13 @@ Commands:
 *   help - display this help
 *   get  - display JSON
 *   set  - set to JSON
+*   edit - edit JSON in your editor
+*   type - display the Rust type
 
 Accessors:
 *   [index] - access item at index"#)
                     }
-                    action => { format!("{} cannot '{:?}'", name, action) }
+                    action => { path_error(&runner, format!("{} cannot '{:?}'", name, action)) }
                 }
             }
         }
@@ -273,16 +441,15 @@ macro_rules! array_node {
                         if index < $length {
                             self[index].node_step(runner)
                         } else {
-                            format!("Used index {} on an array of length {}", index, length)
+                            path_error(&runner, format!("Used index {} on an array of length {}", index, length))
                         }
                     }
                     NodeToken::ChainAll => {
-                        let mut combined = String::from("|");
+                        let mut fan_out = FanOut::new(&mut runner);
                         for i in 0..$length {
-                            combined.push_str(self[i].node_step(runner.clone()).as_ref());
-                            combined.push('|');
+                            fan_out.push(NodeToken::ChainIndex (i), self[i].node_step(runner.clone()));
                         }
-                        combined
+                        fan_out.finish()
                     }
                     NodeToken::Get => {
                         serde_json::to_string_pretty(self).unwrap()
@@ -298,6 +465,19 @@ macro_rules! array_node {
                             }
                         }
                     }
+                    NodeToken::GetWith (options) => {
+                        format::get(self, &options)
+                    }
+                    NodeToken::SetWith (format, value) => {
+                        match format::set(self, &value, format) {
+                            Ok (()) => String::new(),
+                            Err (err) => format!("array set error: {}", err)
+                        }
+                    }
+                    NodeToken::Edit => { edit::edit(self) }
+                    NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+                    NodeToken::Exists  => { String::from("true") }
+                    NodeToken::CheckPath => { String::new() }
                     NodeToken::Help => {
                         String::from(r#"
 Array Help
@@ -306,11 +486,13 @@ Commands:
 *   help - display this help
 *   get  - display JSON
 *   set  - set to JSON
+*   edit - edit JSON in your editor
+*   type - display the Rust type
 
 Accessors:
 *   [index] - access item at index"#)
                     }
-                    action => { format!("array cannot '{:?}'", action) }
+                    action => { path_error(&runner, format!("array cannot '{:?}'", action)) }
                 }
             }
         }
@@ -340,6 +522,19 @@ impl Node for bool {
         match runner.step() {
             NodeToken::Get         => { if *self { String::from("true") } else { String::from("false") } }
             NodeToken::Set (value) => { *self = value.as_str() == "true"; String::from("") }
+            NodeToken::GetWith (options) => {
+                format::get(self, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                match format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("bool set error: {}", err)
+                }
+            }
+            NodeToken::Edit        => { edit::edit(self) }
+            NodeToken::GetType     => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists      => { String::from("true") }
+            NodeToken::CheckPath   => { String::new() }
             NodeToken::Help        => {
                 String::from(r#"
 Bool Help
@@ -349,9 +544,11 @@ Valid values: true or false
 Commands:
 *   help - display this help
 *   get  - display value
-*   set  - set to value"#)
+*   set  - set to value
+*   edit - edit value in your editor
+*   type - display the Rust type"#)
             }
-            action => { format!("bool cannot '{:?}'", action) }
+            action => { path_error(&runner, format!("bool cannot '{:?}'", action)) }
         }
     }
 }
@@ -361,6 +558,15 @@ impl Node for String {
         match runner.step() {
             NodeToken::Get => { (*self).clone() }
             NodeToken::Set (value) => { *self = value; String::from("") }
+            NodeToken::GetWith (options) => {
+                format::get(self, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                match format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("String set error: {}", err)
+                }
+            }
             NodeToken::CopyFrom => {
                 let copy = Some (self.clone());
                 unsafe {
@@ -380,6 +586,10 @@ impl Node for String {
                     }
                 }
             }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
             NodeToken::Help => {
                 String::from(r#"
 String Help
@@ -391,9 +601,11 @@ Commands:
 *   copy  - copy this value
 *   paste - paste the copied value here
 *   get   - display value
-*   set   - set to value"#)
+*   set   - set to value
+*   edit  - edit value in your editor
+*   type  - display the Rust type"#)
             }
-            action => { format!("String cannot '{:?}'", action) }
+            action => { path_error(&runner, format!("String cannot '{:?}'", action)) }
         }
     }
 }
@@ -408,7 +620,7 @@ impl<T> Node for Option<T> where T: Node + Serialize + DeserializeOwned + Defaul
                     value.node_step(runner)
                 }
                 else {
-                    String::from("Option contains no value")
+                    path_error(&runner, String::from("Option contains no value"))
                 }
             }
             NodeToken::Get => {
@@ -425,10 +637,39 @@ impl<T> Node for Option<T> where T: Node + Serialize + DeserializeOwned + Defaul
                     }
                 }
             }
+            NodeToken::GetWith (options) => {
+                format::get(self, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                match format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("Option set error: {}", err)
+                }
+            }
             NodeToken::Insert => {
                 *self = Some(T::default());
                 String::new()
             }
+            // a lone argument is parsed as a key, Option has no keys so it is the value
+            NodeToken::InsertValue (value) | NodeToken::InsertKey (value) => {
+                match serde_json::from_str(&value) {
+                    Ok (value) => {
+                        *self = Some(value);
+                        String::new()
+                    }
+                    Err (err) => format!("Option insert error: {}", err)
+                }
+            }
+            NodeToken::InsertIndex (value) => {
+                // a lone number is parsed as an index, Option has no indexes so it is the value
+                match serde_json::from_str(&value.to_string()) {
+                    Ok (value) => {
+                        *self = Some(value);
+                        String::new()
+                    }
+                    Err (err) => format!("Option insert error: {}", err)
+                }
+            }
             NodeToken::Remove => {
                 *self = None;
                 String::new()
@@ -437,26 +678,419 @@ impl<T> Node for Option<T> where T: Node + Serialize + DeserializeOwned + Defaul
                 *self = None;
                 String::new()
             }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
             NodeToken::Help => {
                 String::from(r#"
 Option Help
 
 Commands:
-*   help    - display this help
-*   get     - display JSON
-*   set     - set to JSON
-*   insert  - set to a value
-*   remove  - remove value
-*   reset   - remove value
+*   help         - display this help
+*   get          - display JSON
+*   set          - set to JSON
+*   edit         - edit JSON in your editor
+*   type         - display the Rust type
+*   insert       - set to a value
+*   insert $JSON - set to $JSON
+*   remove       - remove value
+*   reset        - remove value
 
 Accessors:
 *   .value - the stored value"#)
             }
-            action => { format!("Option cannot '{:?}'", action) }
+            action => { path_error(&runner, format!("Option cannot '{:?}'", action)) }
         }
     }
 }
 
+impl Node for Value {
+    fn node_step(&mut self, mut runner: NodeRunner) -> String {
+        let name = json_type_name(self);
+        let token = match self {
+            Value::Object (_) => index_as_key(runner.step()),
+            _                 => runner.step()
+        };
+        match token {
+            NodeToken::ChainProperty (property) => {
+                match self {
+                    Value::Object (map) => {
+                        if let Some(item) = map.get_mut(&property) {
+                            return item.node_step(runner);
+                        }
+                        if property == "length" {
+                            map.len().node_step(runner)
+                        } else {
+                            path_error(&runner, format!("{} does not have a property '{}'", name, property))
+                        }
+                    }
+                    Value::Array (vector) if property == "length" => { vector.len().node_step(runner) }
+                    _ => path_error(&runner, format!("{} does not have a property '{}'", name, property))
+                }
+            }
+            NodeToken::ChainIndex (index) => {
+                match self {
+                    Value::Array (vector) => {
+                        let length = vector.len();
+                        match vector.get_mut(index) {
+                            Some (item) => item.node_step(runner),
+                            None => {
+                                let error = match length {
+                                     0 => format!("Used index {} on an empty {}", index, name),
+                                     1 => format!("Used index {} on a {} of size 1 (try 0)", index, name),
+                                     _ => format!("Used index {} on a {} of size {} (try a value between 0-{})", index, name, length, length-1)
+                                };
+                                path_error(&runner, error)
+                            }
+                        }
+                    }
+                    _ => path_error(&runner, format!("Cannot index {}", name))
+                }
+            }
+            NodeToken::ChainKey (key) => {
+                match self {
+                    Value::Object (map) => {
+                        if map.is_empty() {
+                            return path_error(&runner, format!("Used key '{}' on an empty {}.", key, name));
+                        }
+                        let keys = format_json_keys(map);
+                        match map.get_mut(&key) {
+                            Some (item) => item.node_step(runner),
+                            None        => path_error(&runner, format!("Used key '{}' on a {} that does not contain it. Try one of: {}", key, name, keys))
+                        }
+                    }
+                    _ => path_error(&runner, format!("Cannot use a key on {}", name))
+                }
+            }
+            NodeToken::ChainAll => {
+                let mut fan_out = FanOut::new(&mut runner);
+                match self {
+                    Value::Array (vector) => {
+                        for (i, item) in vector.iter_mut().enumerate() {
+                            fan_out.push(NodeToken::ChainIndex (i), item.node_step(runner.clone()));
+                        }
+                    }
+                    Value::Object (map) => {
+                        for (key, item) in map.iter_mut() {
+                            fan_out.push(NodeToken::ChainKey (key.clone()), item.node_step(runner.clone()));
+                        }
+                    }
+                    _ => return path_error(&runner, format!("{} cannot 'ChainAll'", name))
+                }
+                fan_out.finish()
+            }
+            NodeToken::GetKeys => {
+                match self {
+                    Value::Object (map) => format_json_keys(map),
+                    _ => format!("{} cannot 'GetKeys'", name)
+                }
+            }
+            NodeToken::Get => {
+                serde_json::to_string_pretty(self).unwrap()
+            }
+            NodeToken::Set (value) => {
+                match serde_json::from_str(&value) {
+                    Ok(result) => {
+                        *self = result;
+                        String::new()
+                    }
+                    Err(_) if self.is_string() => {
+                        // Allow strings to be set without surrounding them in quotes, like a regular String node
+                        *self = Value::String(value);
+                        String::new()
+                    }
+                    Err(err) => {
+                        format!("{} set error: {}", name, err)
+                    }
+                }
+            }
+            NodeToken::GetWith (options) => {
+                format::get(self, &options)
+            }
+            NodeToken::SetWith (format, value) => {
+                match format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("{} set error: {}", name, err)
+                }
+            }
+            NodeToken::Insert => {
+                match self {
+                    Value::Array (vector) => {
+                        vector.push(Value::Null);
+                        String::new()
+                    }
+                    _ => format!("{} cannot 'Insert'", name)
+                }
+            }
+            NodeToken::InsertIndex (index) => {
+                match self {
+                    Value::Array (vector) => {
+                        let max_index = vector.len();
+                        if index > max_index {
+                            format!("Tried to insert at index {} on a {} of size {} (try a value between 0-{})", index, name, max_index, max_index)
+                        }
+                        else {
+                            vector.insert(index, Value::Null);
+                            String::new()
+                        }
+                    }
+                    _ => format!("{} cannot 'InsertIndex({})'", name, index)
+                }
+            }
+            NodeToken::InsertKey (key) => {
+                match self {
+                    Value::Object (map) => {
+                        if map.contains_key(&key) {
+                            format!("Tried to insert key '{}' on a {} that already contains it. Current keys: {}", key, name, format_json_keys(map))
+                        }
+                        else {
+                            map.insert(key, Value::Null);
+                            String::new()
+                        }
+                    }
+                    _ => format!("{} cannot 'InsertKey({:?})'", name, key)
+                }
+            }
+            // a single argument after an index is parsed as a key, arrays have no keys so it is the value
+            NodeToken::InsertIndexValue (index, value) | NodeToken::InsertIndexKey (index, value) => {
+                match self {
+                    Value::Array (vector) => {
+                        let max_index = vector.len();
+                        if index > max_index {
+                            format!("Tried to insert at index {} on a {} of size {} (try a value between 0-{})", index, name, max_index, max_index)
+                        }
+                        else {
+                            match serde_json::from_str(&value) {
+                                Ok (value) => {
+                                    vector.insert(index, value);
+                                    String::new()
+                                }
+                                Err (err) => format!("{} insert error: {}", name, err)
+                            }
+                        }
+                    }
+                    _ => format!("{} cannot 'InsertIndexValue({}, {:?})'", name, index, value)
+                }
+            }
+            NodeToken::InsertKeyValue (key, value) => {
+                match self {
+                    Value::Object (map) => {
+                        if map.contains_key(&key) {
+                            format!("Tried to insert key '{}' on a {} that already contains it. Current keys: {}", key, name, format_json_keys(map))
+                        }
+                        else {
+                            match serde_json::from_str(&value) {
+                                Ok (value) => {
+                                    map.insert(key, value);
+                                    String::new()
+                                }
+                                Err (err) => format!("{} insert error: {}", name, err)
+                            }
+                        }
+                    }
+                    _ => format!("{} cannot 'InsertKeyValue({:?}, {:?})'", name, key, value)
+                }
+            }
+            NodeToken::Push (value) => {
+                match self {
+                    Value::Array (vector) => {
+                        match serde_json::from_str(&value) {
+                            Ok (value) => {
+                                vector.push(value);
+                                String::new()
+                            }
+                            Err (err) => format!("{} push error: {}", name, err)
+                        }
+                    }
+                    _ => format!("{} cannot 'Push({:?})'", name, value)
+                }
+            }
+            NodeToken::Remove => {
+                match self {
+                    Value::Array (vector) => {
+                        if vector.pop().is_some() {
+                            String::new()
+                        } else {
+                            format!("Tried to remove from an empty {}.", name)
+                        }
+                    }
+                    _ => format!("{} cannot 'Remove'", name)
+                }
+            }
+            NodeToken::RemoveIndex (index) => {
+                match self {
+                    Value::Array (vector) => {
+                        let length = vector.len();
+                        if index >= length {
+                            match length {
+                                0 => format!("Tried to remove the value at index {} on an empty {}", index, name),
+                                _ => format!("Tried to remove the value at index {} on a {} of size {} (try a value between 0-{})", index, name, length, length-1)
+                            }
+                        }
+                        else {
+                            vector.remove(index);
+                            String::new()
+                        }
+                    }
+                    _ => format!("{} cannot 'RemoveIndex({})'", name, index)
+                }
+            }
+            NodeToken::RemoveKey (key) => {
+                match self {
+                    Value::Object (map) => {
+                        if map.remove(&key).is_none() {
+                            format!("Tried to remove key '{}' on a {} that doesnt contain it. Current keys: {}", key, name, format_json_keys(map))
+                        }
+                        else {
+                            String::new()
+                        }
+                    }
+                    _ => format!("{} cannot 'RemoveKey({:?})'", name, key)
+                }
+            }
+            NodeToken::CheckRemoveIndex (index) => {
+                match self {
+                    Value::Array (vector) if index < vector.len() => String::new(),
+                    _ => format!("{} cannot 'RemoveIndex({})'", name, index)
+                }
+            }
+            NodeToken::CheckRemoveKey (key) => {
+                match self {
+                    Value::Object (map) if map.contains_key(&key) => String::new(),
+                    _ => format!("{} cannot 'RemoveKey({:?})'", name, key)
+                }
+            }
+            NodeToken::SetDefault => {
+                *self = Value::Null;
+                String::new()
+            }
+            NodeToken::Custom (action, args) => {
+                match action.as_ref() {
+                    "kind" if args.is_empty() => String::from(json_type(self)),
+                    "add" | "subtract" | "multiply" | "divide" => {
+                        match self {
+                            Value::Number (number) => {
+                                match json_arithmetic(number, action.as_ref(), args.first()) {
+                                    Ok (result) => {
+                                        *number = result;
+                                        String::new()
+                                    }
+                                    Err (err) => err
+                                }
+                            }
+                            _ => format!("{} cannot '{}'", name, action)
+                        }
+                    }
+                    _ => format!("{} cannot '{}'", name, action)
+                }
+            }
+            NodeToken::Edit => { edit::edit(self) }
+            NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+            NodeToken::Exists  => { String::from("true") }
+            NodeToken::CheckPath => { String::new() }
+            NodeToken::Help => {
+                String::from(r#"
+JSON Value Help
+
+The available commands and accessors depend on the type of the value (null, bool, number, string, array or object)
+
+Commands:
+*   help                - display this help
+*   get                 - display JSON
+*   set                 - set to JSON
+*   edit                - edit JSON in your editor
+*   type                - display the Rust type
+*   kind                - display the JSON type of the value (null, bool, number, string, array or object)
+*   reset               - reset to null
+*   getkeys             - display keys (object)
+*   insert              - create a new null element at the end (array)
+*   insert $INDEX       - create a new null element at $INDEX (array)
+*   insert $KEY         - create a new null element with $KEY (object)
+*   insert $INDEX $JSON - insert $JSON at $INDEX (array)
+*   insert $KEY $JSON   - insert $JSON with $KEY (object)
+*   push $JSON          - insert $JSON at the end (array)
+*   remove              - remove the element at the end (array)
+*   remove $INDEX       - remove the element at $INDEX (array)
+*   remove $KEY         - remove the element with $KEY (object)
+*   add      $NUMBER    - adds $NUMBER to this number (number)
+*   subtract $NUMBER    - subtracts $NUMBER from this number (number)
+*   multiply $NUMBER    - multiply this number with $NUMBER (number)
+*   divide   $NUMBER    - divide this number by $NUMBER (number)
+
+Accessors:
+*   [index]   - access item at index (array)
+*   ["key"]   - access item at the string key (object)
+*   .property - access item at the property (object)
+*   [*]       - access all items (array, object)
+*   .length   - display number of items (array, object)"#)
+            }
+            action => { path_error(&runner, format!("{} cannot '{:?}'", name, action)) }
+        }
+    }
+
+    fn node_has_property(&self, property: &str) -> bool {
+        match self {
+            Value::Object (map) => map.contains_key(property),
+            _ => false
+        }
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null       => "null",
+        Value::Bool (_)   => "bool",
+        Value::Number (_) => "number",
+        Value::String (_) => "string",
+        Value::Array (_)  => "array",
+        Value::Object (_) => "object",
+    }
+}
+
+fn json_type_name(value: &Value) -> String {
+    format!("json {}", json_type(value))
+}
+
+fn format_json_keys(map: &serde_json::Map<String, Value>) -> String {
+    let key_list: Vec<String> = map.keys().map(|x| format!("'{}'", x)).collect();
+    key_list.join(", ")
+}
+
+/// Integers stay integers when both operands are integers, otherwise the operation is performed on floats
+fn json_arithmetic(number: &Number, action: &str, arg: Option<&String>) -> Result<Number, String> {
+    let arg = match arg {
+        Some (arg) => arg,
+        None       => return Err(String::from("No value for json number (needs to be: A number)"))
+    };
+
+    if let (Some(lhs), Ok(rhs)) = (number.as_i64(), arg.parse::<i64>()) {
+        let result = match action {
+            "add"      => Some(lhs.saturating_add(rhs)),
+            "subtract" => Some(lhs.saturating_sub(rhs)),
+            "multiply" => Some(lhs.saturating_mul(rhs)),
+            _          => lhs.checked_div(rhs),
+        };
+        return match result {
+            Some (result) => Ok(Number::from(result)),
+            None          => Err(String::from("Invalid value for json number (needs to be: A number, excluding 0)"))
+        };
+    }
+
+    let lhs = number.as_f64().unwrap_or(0.0);
+    let rhs = match arg.parse::<f64>() {
+        Ok (rhs) => rhs,
+        Err (_)  => return Err(String::from("Invalid value for json number (needs to be: A number)"))
+    };
+    let result = match action {
+        "add"      => lhs + rhs,
+        "subtract" => lhs - rhs,
+        "multiply" => lhs * rhs,
+        _          => lhs / rhs,
+    };
+    Number::from_f64(result).ok_or_else(|| format!("json number {} {} {} does not result in a valid json number", lhs, action, rhs))
+}
+
 macro_rules! int_node {
     ($e:ty, $valid_values:tt) => {
         impl Node for $e {
@@ -474,6 +1108,19 @@ macro_rules! int_node {
                             }
                         }
                     }
+                    NodeToken::GetWith (options) => {
+                        format::get(self, &options)
+                    }
+                    NodeToken::SetWith (format, value) => {
+                        match format::set(self, &value, format) {
+                            Ok (()) => String::new(),
+                            Err (err) => format!("{} set error: {}", stringify! { $e }, err)
+                        }
+                    }
+                    NodeToken::Edit => { edit::edit(self) }
+                    NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+                    NodeToken::Exists  => { String::from("true") }
+                    NodeToken::CheckPath => { String::new() }
                     NodeToken::Help => {
                         format!(r#"
 {} Help
@@ -486,6 +1133,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -579,7 +1228,7 @@ Commands:
                             }
                         }
                     }
-                    action => { format!("{} cannot '{:?}'", stringify! { $e }, action) }
+                    action => { path_error(&runner, format!("{} cannot '{:?}'", stringify! { $e }, action)) }
                 }
             }
         }
@@ -603,6 +1252,19 @@ macro_rules! float_node {
                             }
                         }
                     }
+                    NodeToken::GetWith (options) => {
+                        format::get(self, &options)
+                    }
+                    NodeToken::SetWith (format, value) => {
+                        match format::set(self, &value, format) {
+                            Ok (()) => String::new(),
+                            Err (err) => format!("{} set error: {}", stringify! { $e }, err)
+                        }
+                    }
+                    NodeToken::Edit => { edit::edit(self) }
+                    NodeToken::GetType => { String::from(std::any::type_name::<Self>()) }
+                    NodeToken::Exists  => { String::from("true") }
+                    NodeToken::CheckPath => { String::new() }
                     NodeToken::Help => {
                         format!(r#"
 {} Help
@@ -615,6 +1277,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -704,7 +1368,7 @@ Commands:
                             }
                         }
                     }
-                    action => { format!("{} cannot '{:?}'", stringify! { $e }, action) }
+                    action => { path_error(&runner, format!("{} cannot '{:?}'", stringify! { $e }, action)) }
                 }
             }
         }
@@ -0,0 +1,11 @@
+/// Custom actions of a type, usually implemented by placing `#[node_actions]` from treeflection_derive on an impl block.
+///
+/// Types that `#[derive(Node)]` with `#[node(actions)]` run these for any custom action they don't handle themselves.
+pub trait NodeActions {
+    /// Run the custom action named `action`.
+    /// Returns None if there is no action by that name.
+    fn node_action(&mut self, action: &str, args: &[String]) -> Option<String>;
+
+    /// The lines describing each action for the output of help e.g. "*   explode - set the radius to 0\n"
+    fn node_actions_help() -> String;
+}
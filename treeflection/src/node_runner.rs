@@ -1,3 +1,4 @@
+use crate::format::{Format, GetOptions};
 use crate::node_token::NodeToken;
 use std::slice::Iter;
 
@@ -128,8 +129,13 @@ impl NodeRunner {
                 }
             }
             else if chars[i] == ':' {
-                let tokenized = NodeRunner::tokenize_action(&chars[i+1..])?;
-                tokens.push(NodeRunner::get_action(tokenized.iter())?);
+                match NodeRunner::get_transfer(&chars[i+1..])? {
+                    Some (transfer) => tokens.push(transfer),
+                    None => {
+                        let tokenized = NodeRunner::tokenize_action(&chars[i+1..])?;
+                        tokens.push(NodeRunner::get_action(tokenized.iter())?);
+                    }
+                }
 
                 tokens.reverse();
 
@@ -196,45 +202,87 @@ impl NodeRunner {
         }
     }
 
+    // The transfers take a path rather than values, so the path is kept as written including the quotes of its keys
+    fn get_transfer(action: &[char]) -> Result<Option<NodeToken>, String> {
+        let action: String = action.iter().collect();
+        let action = action.trim();
+        let (name, path) = match action.find(char::is_whitespace) {
+            Some (i) => (&action[..i], action[i..].trim()),
+            None     => (action, ""),
+        };
+        let transfer = match name {
+            "copyto"   => NodeToken::CopyTo (path.to_string()),
+            "moveto"   => NodeToken::MoveTo (path.to_string()),
+            "swapwith" => NodeToken::SwapWith (path.to_string()),
+            _ => return Ok(None)
+        };
+        if path.is_empty() {
+            Err(format!("Missing path for {}", name))
+        } else {
+            Ok(Some(transfer))
+        }
+    }
+
     fn get_action(mut action: Iter<String>) -> Result<NodeToken, String> {
         match action.next().map(|x| x.as_ref()) {
             Some("help")    => Ok(NodeToken::Help),
             Some("reset")   => Ok(NodeToken::SetDefault),
             Some("edit")    => Ok(NodeToken::Edit),
+            Some("type")    => Ok(NodeToken::GetType),
+            Some("exists")  => Ok(NodeToken::Exists),
             Some("copy")    => Ok(NodeToken::CopyFrom),
             Some("paste")   => Ok(NodeToken::PasteTo),
             Some("getkeys") => Ok(NodeToken::GetKeys),
-            Some("get")     => Ok(NodeToken::Get),
+            Some("sum")     => Ok(NodeToken::Sum),
+            Some("min")     => Ok(NodeToken::Min),
+            Some("max")     => Ok(NodeToken::Max),
+            Some("mean")    => Ok(NodeToken::Mean),
+            Some("count")   => Ok(NodeToken::Count),
+            Some("get") => {
+                let args: Vec<&str> = action.map(|x| x.as_ref()).collect();
+                if args.is_empty() {
+                    Ok(NodeToken::Get)
+                } else {
+                    Ok(NodeToken::GetWith (GetOptions::parse(&args)?))
+                }
+            }
             Some("set") => {
                 let mut set_value: Vec<&str> = vec!();
                 for token in action {
                     set_value.push(token);
                 }
+                // a leading format name is only a format when followed by a value, so a string can still be set to e.g. "ron"
+                if set_value.len() > 1 {
+                    if let Some(format) = Format::from_name(set_value[0]) {
+                        return Ok(NodeToken::SetWith (format, set_value[1..].join(" ")));
+                    }
+                }
                 Ok(NodeToken::Set(set_value.join(" ")))
             }
             Some("insert") => {
-                match action.next() {
-                    Some(arg0) => {
-                        match action.next() {
-                            Some(arg1) => {
-                                match arg0.parse() {
-                                    Ok(index) => {
-                                        Ok(NodeToken::InsertIndexKey (index, arg1.to_string()))
-                                    }
-                                    Err(_) => Err(String::from("When two arguments are used, first must be a valid index."))
-                                }
-                            }
-                            None => {
-                                match arg0.parse() {
-                                    Ok(index) => Ok(NodeToken::InsertIndex (index)),
-                                    Err(_)    => Ok(NodeToken::InsertKey (arg0.to_string())),
-                                }
-                            }
-                        }
-                    }
-                    None => {
-                        Ok(NodeToken::Insert)
-                    }
+                // The arguments are disambiguated by how many there are, a node decides whether a key is really a value:
+                // *   a lone number is an index, otherwise a lone argument is a key
+                // *   a number followed by a single argument is an index and a key
+                // *   a number followed by more arguments is an index and a value
+                // *   more arguments that are JSON as a whole are a value, otherwise the first is a key followed by a value
+                let args: Vec<&str> = action.map(|x| x.as_ref()).collect();
+                let index = args.first().and_then(|x| x.parse::<usize>().ok());
+                match (args.as_slice(), index) {
+                    ([], _) => Ok(NodeToken::Insert),
+                    ([_], Some (index)) => Ok(NodeToken::InsertIndex (index)),
+                    ([key], None) => Ok(NodeToken::InsertKey (key.to_string())),
+                    ([_, key], Some (index)) => Ok(NodeToken::InsertIndexKey (index, key.to_string())),
+                    ([_, value @ ..], Some (index)) => Ok(NodeToken::InsertIndexValue (index, value.join(" "))),
+                    (args, None) if is_json(&args.join(" ")) => Ok(NodeToken::InsertValue (args.join(" "))),
+                    ([key, value @ ..], None) => Ok(NodeToken::InsertKeyValue (key.to_string(), value.join(" "))),
+                }
+            }
+            Some("push") => {
+                let value: Vec<&str> = action.map(|x| x.as_ref()).collect();
+                if value.is_empty() {
+                    Err(String::from("Missing value to push"))
+                } else {
+                    Ok(NodeToken::Push (value.join(" ")))
                 }
             }
             Some("remove") => {
@@ -269,4 +317,14 @@ impl NodeRunner {
     pub fn step(&mut self) -> NodeToken {
         self.tokens.pop().unwrap()
     }
+
+    /// Returns true if the action at the end of the runner does not modify anything
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.tokens.first(), Some (NodeToken::Get) | Some (NodeToken::GetWith (_)) | Some (NodeToken::Help) | Some (NodeToken::GetKeys) | Some (NodeToken::GetType) | Some (NodeToken::Exists) | Some (NodeToken::CopyFrom)
+            | Some (NodeToken::Sum) | Some (NodeToken::Min) | Some (NodeToken::Max) | Some (NodeToken::Mean) | Some (NodeToken::Count))
+    }
+}
+
+fn is_json(value: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(value).is_ok()
 }
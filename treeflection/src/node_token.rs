@@ -1,3 +1,5 @@
+use crate::format::{Format, GetOptions};
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum NodeToken {
     ChainProperty (String),
@@ -7,9 +9,20 @@ pub enum NodeToken {
     ChainAll,
     Help,
     Edit,
+    GetType,
+    Exists,
+    /// Used by `Session::cd` rather than written in commands, results in an empty string if the path can be reached, otherwise the reason it can't
+    CheckPath,
+    Sum,
+    Min,
+    Max,
+    Mean,
+    Count,
     GetKeys,
     Get,
+    GetWith (GetOptions),
     Set (String),
+    SetWith (Format, String),
     SetDefault,
     SetVariant (String),
     CopyFrom,
@@ -18,8 +31,42 @@ pub enum NodeToken {
     InsertIndex (usize),
     InsertKey (String),
     InsertIndexKey (usize, String),
+    InsertValue (String),
+    InsertIndexValue (usize, String),
+    InsertKeyValue (String, String),
+    Push (String),
     Remove,
     RemoveIndex (usize),
     RemoveKey (String),
+    /// Used by `Session` before a move, results in an empty string if the node can remove the value at the index, otherwise the reason it can't
+    CheckRemoveIndex (usize),
+    /// Used by `Session` before a move, results in an empty string if the node can remove the value at the key, otherwise the reason it can't
+    CheckRemoveKey (String),
+    /// The transfers are run by `Session` as they take a second path, the path is kept as written
+    CopyTo (String),
+    MoveTo (String),
+    SwapWith (String),
     Custom (String, Vec<String>),
 }
+
+impl NodeToken {
+    /// Append this accessor to `path` as it would be written in a command e.g. `.foo`, `[0]` or `["key"]`.
+    /// A property at the start of the path is written without the leading dot.
+    /// Returns false without changing `path` when the token is an action.
+    pub fn append_to_path(&self, path: &mut String) -> bool {
+        match self {
+            NodeToken::ChainProperty (property) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(property);
+            }
+            NodeToken::ChainIndex (index) => path.push_str(&format!("[{}]", index)),
+            NodeToken::ChainKey (key)     => path.push_str(&format!("[\"{}\"]", key)),
+            NodeToken::ChainContext       => path.push_str("[?]"),
+            NodeToken::ChainAll           => path.push_str("[*]"),
+            _ => return false
+        }
+        true
+    }
+}
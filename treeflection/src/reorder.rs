@@ -0,0 +1,178 @@
+//! The actions that rearrange the elements of `Vec` and `ContextVec`: swap, move, reverse, duplicate, truncate, resize and sortby.
+//!
+//! Each action returns where every element ended up, so `ContextVec` can keep its context pointing at the same elements.
+
+use std::cmp::Ordering;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::node;
+use crate::node::Node;
+use crate::node_runner::NodeRunner;
+
+/// For each element before the action, its index after the action or None if it was removed
+pub type Moved = Vec<Option<usize>>;
+
+/// Run the reorder `action` on `vector`, returns None if `action` is not a reorder action
+pub fn run<T>(vector: &mut Vec<T>, action: &str, args: &[String]) -> Option<Result<Moved, String>>
+    where T: Node + Serialize + DeserializeOwned + Default
+{
+    let result = match action {
+        "swap"      => swap(vector, args),
+        "move"      => move_element(vector, args),
+        "reverse"   => {
+            let order = (0..vector.len()).rev().collect();
+            Ok(apply_order(vector, order))
+        }
+        "duplicate" => duplicate(vector, args),
+        "truncate"  => {
+            parse_arg(args, 0, "a length").map(|length| {
+                let old_length = vector.len();
+                vector.truncate(length);
+                kept(old_length, length)
+            })
+        }
+        "resize"    => {
+            parse_arg(args, 0, "a length").map(|length| {
+                let old_length = vector.len();
+                vector.resize_with(length, T::default);
+                kept(old_length, length)
+            })
+        }
+        "sortby"    => sort_by(vector, args),
+        _           => return None
+    };
+    Some(result)
+}
+
+fn swap<T>(vector: &mut [T], args: &[String]) -> Result<Moved, String> {
+    let a = parse_index(args, 0, vector.len())?;
+    let b = parse_index(args, 1, vector.len())?;
+    vector.swap(a, b);
+
+    let mut moved: Moved = (0..vector.len()).map(Some).collect();
+    moved.swap(a, b);
+    Ok(moved)
+}
+
+fn move_element<T>(vector: &mut Vec<T>, args: &[String]) -> Result<Moved, String> {
+    let from = parse_index(args, 0, vector.len())?;
+    let to = parse_index(args, 1, vector.len())?;
+
+    let mut order: Vec<usize> = (0..vector.len()).collect();
+    let index = order.remove(from);
+    order.insert(to, index);
+    Ok(apply_order(vector, order))
+}
+
+fn duplicate<T: Serialize + DeserializeOwned>(vector: &mut Vec<T>, args: &[String]) -> Result<Moved, String> {
+    let index = parse_index(args, 0, vector.len())?;
+    let copy = node::copy_value(&vector[index]).map_err(|x| format!("Failed to duplicate index {}: {}", index, x))?;
+    vector.insert(index + 1, copy);
+
+    Ok((0..vector.len() - 1).map(|i| Some(if i > index { i + 1 } else { i })).collect())
+}
+
+/// The elements are sorted by the result of `<path>:get`, the sort is stable so equal elements keep their order
+fn sort_by<T: Node>(vector: &mut Vec<T>, args: &[String]) -> Result<Moved, String> {
+    let mut path = "";
+    let mut descending = false;
+    for arg in args {
+        match arg.as_ref() {
+            "ascending"  => descending = false,
+            "descending" => descending = true,
+            _ if path.is_empty() => path = arg,
+            _ => return Err(format!("Unknown sortby option '{}' (try one of: ascending, descending)", arg))
+        }
+    }
+
+    let command = format!("{}:get compact", path);
+    let mut keys = vec!();
+    for (i, item) in vector.iter_mut().enumerate() {
+        let result = item.node_step(NodeRunner::new(&command)?);
+        match serde_json::from_str::<Value>(&result) {
+            Ok (key) => keys.push(key),
+            Err (_) => return Err(format!("Cannot sort by '{}' as it failed on index {}: {}", path, i, result))
+        }
+    }
+
+    let mut order: Vec<usize> = (0..vector.len()).collect();
+    order.sort_by(|a, b| {
+        let ordering = compare_values(&keys[*a], &keys[*b]);
+        if descending { ordering.reverse() } else { ordering }
+    });
+    Ok(apply_order(vector, order))
+}
+
+/// Values of different types are ordered: null, bool, number, string, array, object
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool (a), Value::Bool (b)) => a.cmp(b),
+        (Value::Number (a), Value::Number (b)) => {
+            match (a.as_i64(), b.as_i64()) {
+                (Some (a), Some (b)) => a.cmp(&b),
+                _ => a.as_f64().unwrap_or(0.0).partial_cmp(&b.as_f64().unwrap_or(0.0)).unwrap_or(Ordering::Equal)
+            }
+        }
+        (Value::String (a), Value::String (b)) => a.cmp(b),
+        (Value::Array (a), Value::Array (b)) => {
+            for (a, b) in a.iter().zip(b.iter()) {
+                let ordering = compare_values(a, b);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        _ => type_rank(a).cmp(&type_rank(b))
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null       => 0,
+        Value::Bool (_)   => 1,
+        Value::Number (_) => 2,
+        Value::String (_) => 3,
+        Value::Array (_)  => 4,
+        Value::Object (_) => 5,
+    }
+}
+
+/// Rearrange `vector` so that the element at index `i` is the element previously at `order[i]`
+fn apply_order<T>(vector: &mut Vec<T>, order: Vec<usize>) -> Moved {
+    let mut old: Vec<Option<T>> = vector.drain(..).map(Some).collect();
+    let mut moved = vec!(None; old.len());
+    for (new, index) in order.into_iter().enumerate() {
+        vector.push(old[index].take().unwrap());
+        moved[index] = Some(new);
+    }
+    moved
+}
+
+/// The elements before `length` stay where they are and the rest are removed
+fn kept(old_length: usize, length: usize) -> Moved {
+    (0..old_length).map(|i| if i < length { Some(i) } else { None }).collect()
+}
+
+fn parse_arg(args: &[String], i: usize, needs: &str) -> Result<usize, String> {
+    match args.get(i) {
+        Some (arg) => arg.parse().map_err(|_| format!("Invalid value '{}' (needs to be: {})", arg, needs)),
+        None => Err(format!("Missing value (needs to be: {})", needs))
+    }
+}
+
+fn parse_index(args: &[String], i: usize, length: usize) -> Result<usize, String> {
+    let index = parse_arg(args, i, "an index")?;
+    if index < length {
+        Ok(index)
+    } else {
+        match length {
+            0 => Err(format!("Used index {} on an empty vector", index)),
+            1 => Err(format!("Used index {} on a vector of size 1 (try 0)", index)),
+            _ => Err(format!("Used index {} on a vector of size {} (try a value between 0-{})", index, length, length-1))
+        }
+    }
+}
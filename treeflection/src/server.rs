@@ -0,0 +1,183 @@
+//! A line based TCP server for running commands from another process, e.g. a level editor.
+//!
+//! Requires the `server` feature.
+//!
+//! The server accepts connections on its own threads and submits their commands to a `CommandQueue`.
+//! Commands are only run when the thread that owns the root node calls `CommandQueue::process`,
+//! so the root node never needs to be shared between threads.
+//!
+//! ```no_run
+//! use treeflection::CommandQueue;
+//! use treeflection::server::Server;
+//!
+//! let mut root = vec!(1, 2, 3);
+//! let mut queue = CommandQueue::new();
+//! let server = Server::bind("127.0.0.1:8123", queue.handle()).unwrap();
+//! loop {
+//!     queue.process(&mut root);
+//!     // run the rest of the frame
+//! }
+//! ```
+//!
+//! # Protocol
+//!
+//! Each request is a single line, which is either a plain command or a JSON-RPC 2.0 request.
+//!
+//! A plain command such as `foo.bar:get` is answered with a header line followed by the result:
+//!
+//! ```text
+//! OK <length>
+//! <result>
+//! ```
+//!
+//! `<length>` is the length of the result in bytes, the result is followed by a newline that is not included in `<length>`.
+//! If the command could not be parsed the header is `ERR <length>` and the result is the error message.
+//!
+//! A line starting with `{` is a JSON-RPC request calling the method `run` with the command as its only parameter:
+//!
+//! ```text
+//! {"jsonrpc": "2.0", "id": 1, "method": "run", "params": ["foo.bar:get"]}
+//! ```
+//!
+//! It is answered with a single line JSON-RPC response.
+//! `params` may also be an object of the form `{"command": "foo.bar:get"}`.
+//! A command that could not be parsed is answered with the error code -32602 (invalid params),
+//! and a command that could not be run because the `CommandQueue` was dropped with -32603 (internal error).
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use crate::command_queue::CommandHandle;
+use crate::condition;
+
+/// Accepts connections and submits their commands to a `CommandQueue`.
+/// Dropping the server stops accepting new connections, existing connections keep running until they are closed.
+/// Commands are answered with an error once the `CommandQueue` has been dropped.
+pub struct Server {
+    local_addr: SocketAddr,
+    shutdown:   Arc<AtomicBool>,
+}
+
+impl Server {
+    /// Start listening on `addr`.
+    /// Use port 0 to let the operating system pick a free port, the chosen port can be retrieved with `local_addr`.
+    /// Commands are submitted through `handle`.
+    pub fn bind<A: ToSocketAddrs>(addr: A, handle: CommandHandle) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let accept_shutdown = shutdown.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Ok(stream) = stream {
+                    let handle = handle.clone();
+                    thread::spawn(move || handle_connection(stream, handle));
+                }
+                if accept_shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+            }
+        });
+
+        Ok(Server { local_addr, shutdown })
+    }
+
+    /// The address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // wake up the accept thread so it notices the shutdown
+        TcpStream::connect(self.local_addr).ok();
+    }
+}
+
+fn handle_connection(stream: TcpStream, handle: CommandHandle) {
+    let mut writer = match stream.try_clone() {
+        Ok (writer) => writer,
+        Err (_)     => return,
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok (line) => line,
+            Err (_)   => return,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let response = if line.starts_with('{') {
+            json_rpc(line, &handle).map(|x| format!("{}\n", x))
+        } else {
+            let (header, result) = match handle.submit(line).wait() {
+                Ok (result) => ("OK", result),
+                Err (err)   => ("ERR", err),
+            };
+            Some(format!("{} {}\n{}\n", header, result.len(), result))
+        };
+
+        if let Some(response) = response {
+            if writer.write_all(response.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Returns None for notifications, which do not get a response
+fn json_rpc(line: &str, handle: &CommandHandle) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok (request) => request,
+        Err (err)    => return Some(json_rpc_error(Value::Null, -32700, &format!("Parse error: {}", err))),
+    };
+
+    let id = request.get("id").cloned();
+    let response_id = id.clone().unwrap_or(Value::Null);
+    if request.get("jsonrpc") != Some(&json!("2.0")) {
+        return Some(json_rpc_error(response_id, -32600, "Invalid request: jsonrpc must be \"2.0\""));
+    }
+
+    let method = match request.get("method") {
+        Some (Value::String (method)) => method,
+        _ => return Some(json_rpc_error(response_id, -32600, "Invalid request: method must be a string")),
+    };
+    if method != "run" {
+        return Some(json_rpc_error(response_id, -32601, &format!("Method not found: {}", method)));
+    }
+
+    let command = match request.get("params") {
+        Some (Value::Array (params)) if params.len() == 1 => params[0].as_str(),
+        Some (Value::Object (params)) => params.get("command").and_then(|x| x.as_str()),
+        _ => None
+    };
+    let command = match command {
+        Some (command) => command,
+        None => return Some(json_rpc_error(response_id, -32602, "Invalid params: expected [command] or {\"command\": command}")),
+    };
+
+    // parse the command here so an invalid command can be told apart from the queue failing to run it
+    if let Err (err) = condition::check(command) {
+        return id.map(|id| json_rpc_error(id, -32602, &err));
+    }
+    let result = handle.submit(command).wait();
+    id.map(|id| match result {
+        Ok (result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err (err)   => json_rpc_error(id, -32603, &err),
+    })
+}
+
+fn json_rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
@@ -0,0 +1,390 @@
+use crate::condition::{self, Condition};
+use crate::format::{Format, GetOptions, FORMAT_NAMES};
+use crate::node::Node;
+use crate::node_runner::NodeRunner;
+use crate::node_token::NodeToken;
+
+/// A Session runs commands relative to a current path, like a shell runs commands relative to a working directory.
+/// It is independent of any front end, so it can be embedded in a game console as easily as in a REPL.
+///
+/// The root node is not owned by the session, it is passed in to every call of `run`.
+///
+/// # Commands
+///
+/// *   `cd PATH`  - change the current path if PATH can be reached, `cd` on its own returns to the root
+/// *   `ls [PATH]` - display the help of the node at the current path or PATH
+/// *   `pwd`      - display the current path
+/// *   `history`  - display previously run lines
+/// *   `format [FORMAT]` - display the format used by get and set, or change it to FORMAT e.g. `format ron`
+/// *   `!!`, `!N` - rerun the previous line or line N of the history
+/// *   `help`     - display the session commands
+/// *   `if COMMAND OPERATOR VALUE then COMMAND [else COMMAND]` - run a command depending on the result of another command,
+///     see the `condition` module. The commands are relative to the current path and a condition that fails is returned as Err.
+///
+/// Anything else is a regular command run relative to the current path.
+///
+/// # Transfers
+///
+/// Commands can also use actions that take a second path, the value is copied through JSON and both paths must exist:
+///
+/// *   `PATH:copyto TARGET`   - set TARGET to the value at PATH
+/// *   `PATH:moveto TARGET`   - set TARGET to the value at PATH then remove PATH from its parent, which must be able to remove it
+/// *   `PATH:swapwith TARGET` - exchange the values at PATH and TARGET
+///
+/// If any step fails the values are restored, so either the whole transfer happens or nothing changes.
+///
+/// # Paths
+///
+/// *   Paths starting with `/` are relative to the root
+/// *   Each leading `..` moves up to the parent, optionally separated with `/` e.g. `../..:get` or `../foo:get`
+pub struct Session {
+    path:    Vec<NodeToken>,
+    history: Vec<String>,
+    format:  Format,
+}
+
+impl Session {
+    /// Create a new Session at the root
+    pub fn new() -> Session {
+        Session {
+            path:    vec!(),
+            history: vec!(),
+            format:  Format::Json,
+        }
+    }
+
+    /// Run a line on the root node.
+    /// Returns Err if the line is not a valid command.
+    pub fn run<T: Node>(&mut self, root: &mut T, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let line = match line {
+            "" => return Ok(String::new()),
+            "!!" => {
+                match self.history.last() {
+                    Some (line) => line.clone(),
+                    None        => return Err(String::from("History is empty")),
+                }
+            }
+            _ if line.starts_with('!') => {
+                match line[1..].parse::<usize>().ok().and_then(|i| self.history.get(i)) {
+                    Some (line) => line.clone(),
+                    None        => return Err(format!("{} is not in the history", line)),
+                }
+            }
+            _ => line.to_string()
+        };
+        self.history.push(line.clone());
+        self.run_line(root, &line)
+    }
+
+    /// Run a line that has already been added to the history
+    fn run_line<T: Node>(&mut self, root: &mut T, line: &str) -> Result<String, String> {
+        if let Some (condition) = Condition::parse(line) {
+            return self.run_if(root, condition?);
+        }
+
+        let (name, argument) = match line.find(char::is_whitespace) {
+            Some (i) => (&line[..i], line[i..].trim()),
+            None     => (line, ""),
+        };
+
+        match name {
+            "cd" => {
+                self.cd(root, argument)?;
+                Ok(String::new())
+            }
+            "ls" => {
+                let runner = self.resolve_runner(argument, NodeToken::Help)?;
+                Ok(root.node_step(runner))
+            }
+            "pwd" if argument.is_empty() => Ok(self.pwd()),
+            "history" if argument.is_empty() => Ok(self.format_history()),
+            "format" if argument.is_empty() => Ok(String::from(self.format.name())),
+            "format" => {
+                match Format::from_name(argument) {
+                    Some (format) => {
+                        self.format = format;
+                        Ok(String::new())
+                    }
+                    None => Err(format!("Unknown format '{}' (try one of: {})", argument, FORMAT_NAMES)),
+                }
+            }
+            "help" if argument.is_empty() => Ok(String::from(HELP)),
+            _ => {
+                let runner = self.resolve(line)?;
+                match runner.tokens.first() {
+                    Some (NodeToken::CopyTo (target)) | Some (NodeToken::MoveTo (target)) | Some (NodeToken::SwapWith (target)) => {
+                        let target = self.resolve_path(target)?;
+                        let mut source = runner.tokens;
+                        let action = source.remove(0);
+                        source.reverse();
+                        self.transfer(root, action, source, target)
+                    }
+                    _ => Ok(root.node_step(runner))
+                }
+            }
+        }
+    }
+
+    /// Change the current path.
+    /// An empty path returns to the root.
+    /// Returns Err without changing the current path if the new path can't be reached in `root`.
+    pub fn cd<T: Node>(&mut self, root: &mut T, path: &str) -> Result<(), String> {
+        if path.is_empty() {
+            self.path = vec!();
+            return Ok(());
+        }
+
+        let path = self.resolve_path(path)?;
+        let unreachable = step(root, &path, NodeToken::CheckPath);
+        if !unreachable.is_empty() {
+            return Err(format!("Cannot cd to {}: {}", format_path(&path), unreachable));
+        }
+        self.path = path;
+        Ok(())
+    }
+
+    /// The current path as a list of chain tokens, starting from the root
+    pub fn path(&self) -> &[NodeToken] {
+        &self.path
+    }
+
+    /// The current path formatted as a string
+    pub fn pwd(&self) -> String {
+        format_path(&self.path)
+    }
+
+    /// The format used by get and set commands that don't specify a format
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Change the format used by get and set commands that don't specify a format
+    pub fn set_format(&mut self, format: Format) {
+        self.format = format;
+    }
+
+    /// All lines that have been run, oldest first
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Create a NodeRunner for a command relative to the current path.
+    /// Get and set without a format use the format of the session.
+    pub fn resolve(&self, command: &str) -> Result<NodeRunner, String> {
+        let (mut base, command) = self.split_base(command)?;
+        let mut runner = NodeRunner::new(command)?;
+        if self.format != Format::Json {
+            let action = runner.tokens.remove(0);
+            runner.tokens.insert(0, match action {
+                NodeToken::Get => NodeToken::GetWith (GetOptions { format: Some(self.format), ..GetOptions::default() }),
+                NodeToken::GetWith (options) => NodeToken::GetWith (GetOptions { format: options.format.or(Some(self.format)), ..options }),
+                NodeToken::Set (value) => NodeToken::SetWith (self.format, value),
+                action                 => action,
+            });
+        }
+        base.reverse();
+        runner.tokens.extend(base);
+        Ok(runner)
+    }
+
+    /// Like resolve but for a path without an action, the action is provided separately
+    fn resolve_runner(&self, path: &str, action: NodeToken) -> Result<NodeRunner, String> {
+        let mut tokens = self.resolve_path(path)?;
+        tokens.push(action);
+        tokens.reverse();
+        Ok(NodeRunner { tokens })
+    }
+
+    /// Resolve a path without an action into chain tokens starting from the root
+    fn resolve_path(&self, path: &str) -> Result<Vec<NodeToken>, String> {
+        let (mut base, path) = self.split_base(path)?;
+        if !path.is_empty() {
+            // reuse the command parser by giving the path a throwaway action
+            let runner = NodeRunner::new(&format!("{}:help", path))?;
+            base.extend(runner.tokens.into_iter().skip(1).rev());
+        }
+        Ok(base)
+    }
+
+    /// Split off the part of a command that refers to the root or parents.
+    /// Returns the path the rest of the command is relative to, along with the rest of the command.
+    fn split_base<'a>(&self, command: &'a str) -> Result<(Vec<NodeToken>, &'a str), String> {
+        let (mut base, mut command) = match command.strip_prefix('/') {
+            Some (command) => (vec!(), command),
+            None           => (self.path.clone(), command),
+        };
+
+        while let Some(rest) = command.strip_prefix("..") {
+            if base.pop().is_none() {
+                return Err(String::from("Cannot go above the root"));
+            }
+            command = rest.strip_prefix('/').unwrap_or(rest);
+        }
+        Ok((base, command))
+    }
+
+    /// Run the transfer `action` from the `source` path to the `target` path, both starting from the root.
+    /// Errors from the nodes are returned as Ok like any other command.
+    fn transfer<T: Node>(&self, root: &mut T, action: NodeToken, source: Vec<NodeToken>, target: Vec<NodeToken>) -> Result<String, String> {
+        let name = match action {
+            NodeToken::CopyTo (_) => "copyto",
+            NodeToken::MoveTo (_) => "moveto",
+            _                     => "swapwith",
+        };
+        for token in source.iter().chain(target.iter()) {
+            if let NodeToken::ChainAll | NodeToken::ChainContext = token {
+                return Err(format!("{} cannot be used with [*] or [?]", name));
+            }
+        }
+
+        let (source_name, target_name) = (format_path(&source), format_path(&target));
+        let source_value = match get_json(root, &source) {
+            Ok (value) => value,
+            Err (err) => return Ok(format!("Cannot get {}: {}", source_name, err))
+        };
+        let target_value = match get_json(root, &target) {
+            Ok (value) => value,
+            Err (err) => return Ok(format!("Cannot get {}: {}", target_name, err))
+        };
+
+        match action {
+            NodeToken::CopyTo (_) => {
+                match set_json(root, &target, &source_value) {
+                    Ok (()) => Ok(String::new()),
+                    Err (err) => Ok(format!("Cannot copy {} to {}: {}", source_name, target_name, err))
+                }
+            }
+            NodeToken::MoveTo (_) => {
+                if target.starts_with(&source) || source.starts_with(&target) {
+                    return Ok(format!("Cannot move {} to {} as one contains the other", source_name, target_name));
+                }
+                let (check, remove) = match source.last() {
+                    Some (NodeToken::ChainIndex (index))       => (NodeToken::CheckRemoveIndex (*index), NodeToken::RemoveIndex (*index)),
+                    Some (NodeToken::ChainKey (key))           => (NodeToken::CheckRemoveKey (key.clone()), NodeToken::RemoveKey (key.clone())),
+                    Some (NodeToken::ChainProperty (property)) => (NodeToken::CheckRemoveKey (property.clone()), NodeToken::RemoveKey (property.clone())),
+                    _ => return Ok(String::from("Cannot move the root"))
+                };
+                // check before writing the target, as not every parent can remove its values e.g. the fields of a struct
+                let parent = &source[..source.len() - 1];
+                let result = step(root, parent, check);
+                if !result.is_empty() {
+                    return Ok(format!("Cannot move {} as it can't be removed: {}", source_name, result));
+                }
+                if let Err (err) = set_json(root, &target, &source_value) {
+                    return Ok(format!("Cannot move {} to {}: {}", source_name, target_name, err));
+                }
+                let result = step(root, parent, remove);
+                if result.is_empty() {
+                    Ok(String::new())
+                } else {
+                    Ok(restore(root, &target, &target_value, format!("Cannot move {} as removing it failed: {}", source_name, result)))
+                }
+            }
+            _ => {
+                if target.starts_with(&source) || source.starts_with(&target) {
+                    return Ok(format!("Cannot swap {} with {} as one contains the other", source_name, target_name));
+                }
+                if let Err (err) = set_json(root, &target, &source_value) {
+                    return Ok(format!("Cannot swap {} with {}: {}", source_name, target_name, err));
+                }
+                match set_json(root, &source, &target_value) {
+                    Ok (()) => Ok(String::new()),
+                    Err (err) => Ok(restore(root, &target, &target_value, format!("Cannot swap {} with {}: {}", source_name, target_name, err)))
+                }
+            }
+        }
+    }
+
+    /// Run an `if` line, the condition is run relative to the current path and the branch is run as a line of its own
+    fn run_if<T: Node>(&mut self, root: &mut T, condition: Condition) -> Result<String, String> {
+        let runner = self.resolve(condition.command)?;
+        let result = condition::evaluate(root, condition.command, runner)?;
+        match condition.branch(&result) {
+            Some (line) => self.run_line(root, line),
+            None        => Ok(String::new()),
+        }
+    }
+
+    fn format_history(&self) -> String {
+        let lines: Vec<String> = self.history.iter().enumerate().map(|(i, line)| format!("{:>4}  {}", i, line)).collect();
+        lines.join("\n")
+    }
+}
+
+impl Default for Session {
+    fn default() -> Session {
+        Session::new()
+    }
+}
+
+/// Format a path starting from the root
+fn format_path(path: &[NodeToken]) -> String {
+    let mut result = String::new();
+    for token in path {
+        let appended = token.append_to_path(&mut result);
+        debug_assert!(appended, "Paths only contain chain tokens");
+    }
+    format!("/{}", result)
+}
+
+/// Run `action` on the node at `path`
+fn step<T: Node>(root: &mut T, path: &[NodeToken], action: NodeToken) -> String {
+    let mut tokens = path.to_vec();
+    tokens.push(action);
+    tokens.reverse();
+    root.node_step(NodeRunner { tokens })
+}
+
+/// The value at `path` as JSON, or the error from getting it
+fn get_json<T: Node>(root: &mut T, path: &[NodeToken]) -> Result<String, String> {
+    let options = GetOptions { format: Some(Format::Json), compact: true, ..GetOptions::default() };
+    let result = step(root, path, NodeToken::GetWith (options));
+    // errors are plain text, so anything that is not JSON is an error
+    match serde_json::from_str::<serde_json::Value>(&result) {
+        Ok (_) => Ok(result),
+        Err (_) => Err(result)
+    }
+}
+
+fn set_json<T: Node>(root: &mut T, path: &[NodeToken], value: &str) -> Result<(), String> {
+    let result = step(root, path, NodeToken::Set (value.to_string()));
+    if result.is_empty() {
+        Ok(())
+    } else {
+        Err(result)
+    }
+}
+
+/// Set `path` back to `value` after a transfer failed with `err`
+fn restore<T: Node>(root: &mut T, path: &[NodeToken], value: &str, err: String) -> String {
+    match set_json(root, path, value) {
+        Ok (()) => err,
+        Err (restore_err) => format!("{}\nRestoring {} also failed: {}", err, format_path(path), restore_err)
+    }
+}
+
+const HELP: &str = r#"
+Session Help
+
+Commands:
+*   cd $PATH       - change the current path, cd on its own returns to the root
+*   ls             - display help for the current path
+*   ls $PATH       - display help for $PATH
+*   pwd            - display the current path
+*   history        - display previously run lines
+*   format         - display the format used by get and set
+*   format $FORMAT - change the format used by get and set (json, compact, ron, yaml or toml)
+*   !!             - rerun the previous line
+*   !$N            - rerun line $N of the history
+*   help           - display this help
+*   if $COMMAND $OPERATOR $VALUE then $COMMAND else $COMMAND
+                   - run the command after then if the result of the first command compares true with $VALUE,
+                     otherwise run the optional command after else. $OPERATOR is one of ==, !=, <, <=, > or >=
+
+Any other command is run relative to the current path, and can use these actions that take a second path:
+*   :copyto $PATH   - set $PATH to the value
+*   :moveto $PATH   - set $PATH to the value then remove the value from its parent
+*   :swapwith $PATH - exchange the value with the value at $PATH
+
+Paths starting with / are relative to the root and each leading .. moves up to the parent e.g. ..:help"#;
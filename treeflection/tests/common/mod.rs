@@ -0,0 +1,32 @@
+//! Helpers shared by the unit tests
+
+use serde_json::Value;
+
+/// Parse the output of a fan-out into (path, result) pairs, nested fan-outs are flattened into the pairs of their children.
+/// Returns None if `output` is not the output of a fan-out.
+pub fn fan_out_pairs(output: &str) -> Option<Vec<(String, String)>> {
+    let results = match serde_json::from_str::<Value>(output) {
+        Ok (Value::Array (results)) => results,
+        _ => return None
+    };
+    let mut pairs = vec!();
+    flatten(&results, &mut pairs)?;
+    Some(pairs)
+}
+
+fn flatten(results: &[Value], pairs: &mut Vec<(String, String)>) -> Option<()> {
+    for result in results {
+        let path = result.get("path")?.as_str()?;
+        match result.get("result")? {
+            Value::String (result) => pairs.push((path.to_string(), result.clone())),
+            Value::Array (nested)  => flatten(nested, pairs)?,
+            _ => return None
+        }
+    }
+    Some(())
+}
+
+pub fn assert_fan_out(expected: &[(&str, &str)], output: &str) {
+    let expected: Vec<(String, String)> = expected.iter().map(|(path, result)| (path.to_string(), result.to_string())).collect();
+    assert_eq!(Some(expected), fan_out_pairs(output));
+}
@@ -0,0 +1,45 @@
+extern crate treeflection;
+
+use std::collections::HashMap;
+
+use treeflection::args::parse_arg;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|x| x.to_string()).collect()
+}
+
+#[test]
+fn parse_arg_json() {
+    let args = args(&["42", "-1.5", "true", "[1,2]", "{\"a\":1}"]);
+    assert_eq!(parse_arg::<u32>(&args, 0), Ok(42));
+    assert_eq!(parse_arg::<f64>(&args, 1), Ok(-1.5));
+    assert_eq!(parse_arg::<bool>(&args, 2), Ok(true));
+    assert_eq!(parse_arg::<Vec<u8>>(&args, 3), Ok(vec!(1, 2)));
+    let mut map = HashMap::new();
+    map.insert(String::from("a"), 1);
+    assert_eq!(parse_arg::<HashMap<String, i32>>(&args, 4), Ok(map));
+}
+
+#[test]
+fn parse_arg_string() {
+    let args = args(&["foo", "42", "true", "\"quoted\""]);
+    assert_eq!(parse_arg::<String>(&args, 0), Ok(String::from("foo")));
+    assert_eq!(parse_arg::<String>(&args, 1), Ok(String::from("42")));
+    assert_eq!(parse_arg::<String>(&args, 2), Ok(String::from("true")));
+    assert_eq!(parse_arg::<String>(&args, 3), Ok(String::from("quoted")));
+}
+
+#[test]
+fn parse_arg_optional() {
+    let args = args(&["1"]);
+    assert_eq!(parse_arg::<Option<u8>>(&args, 0), Ok(Some(1)));
+    assert_eq!(parse_arg::<Option<u8>>(&args, 1), Ok(None));
+    assert_eq!(parse_arg::<u8>(&args, 1), Err(String::from("Missing argument 2 (needs to be: u8)")));
+}
+
+#[test]
+fn parse_arg_invalid() {
+    let args = args(&["foo", "-1"]);
+    assert_eq!(parse_arg::<u8>(&args, 0), Err(String::from("Invalid value 'foo' for argument 1 (needs to be: u8): invalid type: string \"foo\", expected u8")));
+    assert_eq!(parse_arg::<Vec<u8>>(&args, 1), Err(String::from("Invalid value '-1' for argument 2 (needs to be: Vec<u8>): invalid type: integer `-1`, expected a sequence at line 1 column 2")));
+}
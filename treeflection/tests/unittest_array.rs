@@ -1,5 +1,8 @@
 extern crate treeflection;
 
+mod common;
+
+use common::assert_fan_out;
 use treeflection::{Node, NodeRunner, NodeToken};
 
 fn test_array2() -> [bool; 2] {
@@ -37,7 +40,7 @@ fn array_chain_all() {
         NodeToken::Get,
         NodeToken::ChainAll,
     )};
-    assert_eq!("|false|true|", test_array2().node_step(runner));
+    assert_fan_out(&[("[0]", "false"), ("[1]", "true")], &test_array2().node_step(runner));
 }
 
 #[test]
@@ -98,6 +101,8 @@ Commands:
 *   help - display this help
 *   get  - display JSON
 *   set  - set to JSON
+*   edit - edit JSON in your editor
+*   type - display the Rust type
 
 Accessors:
 *   [index] - access item at index"#;
@@ -1,5 +1,8 @@
 extern crate treeflection;
 
+mod common;
+
+use common::assert_fan_out;
 use treeflection::{Node, NodeRunner, NodeToken};
 
 fn test_vec4() -> Vec<i32> {
@@ -73,7 +76,7 @@ fn vec_chain_all() {
         NodeToken::Get,
         NodeToken::ChainAll,
     )};
-    assert_eq!("|100000|13|-358|42|", test_vec4().node_step(runner));
+    assert_fan_out(&[("[0]", "100000"), ("[1]", "13"), ("[2]", "-358"), ("[3]", "42")], &test_vec4().node_step(runner));
 }
 
 #[test]
@@ -114,6 +117,36 @@ fn vec_insert() {
     assert_eq!(some_vec[5], 42);
 }
 
+#[test]
+fn vec_insert_value() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":insert 1 7"), "");
+    assert_eq!(some_vec, vec!(100000, 7, 13, -358, 42));
+    assert_eq!(run(&mut some_vec, ":insert 5 -1"), "");
+    assert_eq!(some_vec, vec!(100000, 7, 13, -358, 42, -1));
+    assert_eq!(run(&mut some_vec, ":insert 7 1"), "Tried to insert at index 7 on a vector of size 6 (try a value between 0-6)");
+    assert_eq!(run(&mut some_vec, ":insert 0 1.5"), "vector insert error: invalid type: floating point `1.5`, expected i32 at line 1 column 3");
+    assert_eq!(some_vec, vec!(100000, 7, 13, -358, 42, -1));
+
+    let mut nested: Vec<Vec<u32>> = vec!();
+    assert_eq!(nested.node_step(NodeRunner::new(":insert 0 [1, 2, 3]").unwrap()), "");
+    assert_eq!(nested, vec!(vec!(1, 2, 3)));
+}
+
+#[test]
+fn vec_push() {
+    let mut some_vec = test_vec0();
+    assert_eq!(run(&mut some_vec, ":push 3"), "");
+    assert_eq!(run(&mut some_vec, ":push 4"), "");
+    assert_eq!(some_vec, vec!(3, 4));
+    assert_eq!(run(&mut some_vec, ":push four"), "vector push error: expected ident at line 1 column 2");
+    assert_eq!(some_vec, vec!(3, 4));
+
+    let mut fighters: Vec<(String, u32)> = vec!();
+    assert_eq!(fighters.node_step(NodeRunner::new(r#":push [\"Fox\", 75]"#).unwrap()), "");
+    assert_eq!(fighters, vec!((String::from("Fox"), 75)));
+}
+
 #[test]
 fn vec_remove() {
     let mut some_vec = test_vec4();
@@ -180,18 +213,126 @@ fn vec_set_fail() {
     assert_eq!(some_vec.node_step(runner), String::from("vector set error: expected value at line 1 column 5"));
 }
 
+fn run(vec: &mut Vec<i32>, command: &str) -> String {
+    vec.node_step(NodeRunner::new(command).unwrap())
+}
+
+#[test]
+fn vec_swap() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":swap 0 3"), "");
+    assert_eq!(some_vec, vec!(42, 13, -358, 100000));
+    assert_eq!(run(&mut some_vec, ":swap 1 4"), "Used index 4 on a vector of size 4 (try a value between 0-3)");
+    assert_eq!(run(&mut some_vec, ":swap 1"), "Missing value (needs to be: an index)");
+    assert_eq!(run(&mut some_vec, ":swap 1 two"), "Invalid value 'two' (needs to be: an index)");
+    assert_eq!(some_vec, vec!(42, 13, -358, 100000));
+}
+
+#[test]
+fn vec_move() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":move 0 2"), "");
+    assert_eq!(some_vec, vec!(13, -358, 100000, 42));
+    assert_eq!(run(&mut some_vec, ":move 3 0"), "");
+    assert_eq!(some_vec, vec!(42, 13, -358, 100000));
+    assert_eq!(run(&mut some_vec, ":move 0 4"), "Used index 4 on a vector of size 4 (try a value between 0-3)");
+}
+
+#[test]
+fn vec_reverse() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":reverse"), "");
+    assert_eq!(some_vec, vec!(42, -358, 13, 100000));
+
+    let mut some_vec = test_vec0();
+    assert_eq!(run(&mut some_vec, ":reverse"), "");
+    assert!(some_vec.is_empty());
+}
+
+#[test]
+fn vec_duplicate() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":duplicate 1"), "");
+    assert_eq!(some_vec, vec!(100000, 13, 13, -358, 42));
+    assert_eq!(run(&mut some_vec, ":duplicate 5"), "Used index 5 on a vector of size 5 (try a value between 0-4)");
+
+    let mut some_vec = test_vec0();
+    assert_eq!(run(&mut some_vec, ":duplicate 0"), "Used index 0 on an empty vector");
+}
+
+#[test]
+fn vec_truncate() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":truncate 6"), "");
+    assert_eq!(some_vec, vec!(100000, 13, -358, 42));
+    assert_eq!(run(&mut some_vec, ":truncate 2"), "");
+    assert_eq!(some_vec, vec!(100000, 13));
+    assert_eq!(run(&mut some_vec, ":truncate"), "Missing value (needs to be: a length)");
+}
+
+#[test]
+fn vec_resize() {
+    let mut some_vec = test_vec1();
+    assert_eq!(run(&mut some_vec, ":resize 3"), "");
+    assert_eq!(some_vec, vec!(13, 0, 0));
+    assert_eq!(run(&mut some_vec, ":resize 0"), "");
+    assert!(some_vec.is_empty());
+    assert_eq!(run(&mut some_vec, ":resize -1"), "Invalid value '-1' (needs to be: a length)");
+}
+
+#[test]
+fn vec_sortby() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":sortby"), "");
+    assert_eq!(some_vec, vec!(-358, 13, 42, 100000));
+    assert_eq!(run(&mut some_vec, ":sortby descending"), "");
+    assert_eq!(some_vec, vec!(100000, 42, 13, -358));
+
+    let mut fighters: Vec<(String, u32)> = vec!((String::from("Fox"), 75), (String::from("Marth"), 87), (String::from("Falco"), 80));
+    assert_eq!(fighters.node_step(NodeRunner::new(":sortby [1] descending").unwrap()), "");
+    assert_eq!(fighters.iter().map(|x| x.0.as_str()).collect::<Vec<_>>(), vec!("Marth", "Falco", "Fox"));
+    assert_eq!(fighters.node_step(NodeRunner::new(":sortby [0] ascending").unwrap()), "");
+    assert_eq!(fighters.iter().map(|x| x.0.as_str()).collect::<Vec<_>>(), vec!("Falco", "Fox", "Marth"));
+
+    assert_eq!(fighters.node_step(NodeRunner::new(":sortby [0] upwards").unwrap()), "Unknown sortby option 'upwards' (try one of: ascending, descending)");
+
+    let mut nested: Vec<Vec<u32>> = vec!(vec!(2), vec!(), vec!(1));
+    assert_eq!(nested.node_step(NodeRunner::new(":sortby [0]").unwrap()), "Cannot sort by '[0]' as it failed on index 1: Used index 0 on an empty vector");
+    assert_eq!(nested, vec!(vec!(2), vec!(), vec!(1)));
+}
+
+#[test]
+fn vec_sortby_stable() {
+    let mut pairs: Vec<(u32, u32)> = vec!((2, 0), (1, 1), (2, 2), (1, 3));
+    assert_eq!(pairs.node_step(NodeRunner::new(":sortby [0]").unwrap()), "");
+    assert_eq!(pairs, vec!((1, 1), (1, 3), (2, 0), (2, 2)));
+    assert_eq!(pairs.node_step(NodeRunner::new(":sortby [0] descending").unwrap()), "");
+    assert_eq!(pairs, vec!((2, 0), (2, 2), (1, 1), (1, 3)));
+}
+
 #[test]
 fn vec_help() {
     let output = r#"
 Vector Help
 
 Commands:
-*   help    - display this help
-*   get     - display JSON
-*   set     - set to JSON
-*   insert  - create a new element
-*   remove  - remove an element
-*   reset   - reset to empty vector
+*   help                 - display this help
+*   get                  - display JSON
+*   set                  - set to JSON
+*   edit                 - edit JSON in your editor
+*   type                 - display the Rust type
+*   insert               - create a new element
+*   insert $INDEX $JSON  - insert $JSON at $INDEX
+*   push $JSON           - insert $JSON at the end
+*   remove               - remove an element
+*   reset                - reset to empty vector
+*   swap $INDEX $INDEX   - swap the elements at the two indexes
+*   move $FROM $TO       - move the element at $FROM to $TO
+*   reverse              - reverse the order of the elements
+*   duplicate $INDEX     - insert a copy of the element at $INDEX after it
+*   truncate $LENGTH     - remove the elements from $LENGTH onwards
+*   resize $LENGTH       - remove or create elements until there are $LENGTH
+*   sortby $PATH [ORDER] - sort by the value at $PATH in each element, ORDER is ascending or descending
 
 Accessors:
 *   [index] - access item at index
@@ -229,7 +370,7 @@ fn tuple_chain_all() {
         NodeToken::Get,
         NodeToken::ChainAll,
     )};
-    assert_eq!("|42|true|", test_tuple().node_step(runner));
+    assert_fan_out(&[("[0]", "42"), ("[1]", "true")], &test_tuple().node_step(runner));
 }
 
 #[test]
@@ -290,6 +431,8 @@ Commands:
 *   help - display this help
 *   get  - display JSON
 *   set  - set to JSON
+*   edit - edit JSON in your editor
+*   type - display the Rust type
 
 Accessors:
 *   [index] - access item at index"#;
@@ -304,12 +447,15 @@ fn option_help() {
 Option Help
 
 Commands:
-*   help    - display this help
-*   get     - display JSON
-*   set     - set to JSON
-*   insert  - set to a value
-*   remove  - remove value
-*   reset   - remove value
+*   help         - display this help
+*   get          - display JSON
+*   set          - set to JSON
+*   edit         - edit JSON in your editor
+*   type         - display the Rust type
+*   insert       - set to a value
+*   insert $JSON - set to $JSON
+*   remove       - remove value
+*   reset        - remove value
 
 Accessors:
 *   .value - the stored value"#;
@@ -368,6 +514,23 @@ fn option_insert() {
     }
 }
 
+#[test]
+fn option_insert_value() {
+    let mut some_option: Option<Vec<u32>> = None;
+    assert_eq!(some_option.node_step(NodeRunner::new(":insert [1, 2]").unwrap()), "");
+    assert_eq!(some_option, Some(vec!(1, 2)));
+    assert_eq!(some_option.node_step(NodeRunner::new(":insert [3,").unwrap()), "Option insert error: EOF while parsing a value at line 1 column 3");
+    assert_eq!(some_option, Some(vec!(1, 2)));
+
+    let mut some_option: Option<usize> = None;
+    assert_eq!(some_option.node_step(NodeRunner::new(":insert 5").unwrap()), "");
+    assert_eq!(some_option, Some(5));
+
+    let mut some_option: Option<String> = None;
+    assert_eq!(some_option.node_step(NodeRunner::new(r#":insert \"Fox\""#).unwrap()), "");
+    assert_eq!(some_option, Some(String::from("Fox")));
+}
+
 #[test]
 fn option_remove() {
     let mut some_option: Option<usize> = Some(358);
@@ -400,3 +563,29 @@ fn option_value() {
     )};
     assert_eq!("Option contains no value", some_option.node_step(runner));
 }
+
+#[test]
+fn vec_exists() {
+    let mut some_vec = test_vec4();
+    assert_eq!(run(&mut some_vec, ":exists"), "true");
+    assert_eq!(run(&mut some_vec, "[3]:exists"), "true");
+    assert_eq!(run(&mut some_vec, "[4]:exists"), "false");
+    assert_eq!(run(&mut some_vec, "[0].foo:exists"), "false");
+    assert_eq!(run(&mut some_vec, ".length:exists"), "true");
+}
+
+#[test]
+fn tuple_exists() {
+    let mut tuple = test_tuple();
+    assert_eq!(tuple.node_step(NodeRunner::new("[1]:exists").unwrap()), "true");
+    assert_eq!(tuple.node_step(NodeRunner::new("[2]:exists").unwrap()), "false");
+}
+
+#[test]
+fn option_exists() {
+    let mut some_option: Option<usize> = Some(42);
+    assert_eq!(some_option.node_step(NodeRunner::new("value:exists").unwrap()), "true");
+    some_option = None;
+    assert_eq!(some_option.node_step(NodeRunner::new("value:exists").unwrap()), "false");
+    assert_eq!(some_option.node_step(NodeRunner::new(":exists").unwrap()), "true");
+}
@@ -2,7 +2,7 @@
 
 extern crate treeflection;
 
-use treeflection::{NodeRunner, NodeToken};
+use treeflection::{Format, GetOptions, NodeRunner, NodeToken};
 
 fn assert_command(expected: Vec<NodeToken>, command: &str) {
     let runner = NodeRunner::new(command).unwrap();
@@ -53,6 +53,72 @@ fn insert_with_index_key_stringed() {
     assert_command(expected, ":insert \"2\" \"bar\"");
 }
 
+#[test]
+fn insert_with_index_single_argument() {
+    // the node decides whether the argument is a key or a value
+    let expected = vec!(
+        NodeToken::InsertIndexKey(2, String::from("5")),
+    );
+    assert_command(expected, ":insert 2 5");
+
+    let expected = vec!(
+        NodeToken::InsertIndexKey(0, String::from("true")),
+    );
+    assert_command(expected, ":insert 0 true");
+}
+
+#[test]
+fn insert_with_index_value_multiple_words() {
+    let expected = vec!(
+        NodeToken::InsertIndexValue(0, String::from("{\"name\": \"Fox\", \"weight\": 75}")),
+    );
+    assert_command(expected, r#":insert 0 {\"name\": \"Fox\", \"weight\": 75}"#);
+}
+
+#[test]
+fn insert_with_key_value() {
+    let expected = vec!(
+        NodeToken::InsertKeyValue(String::from("fox"), String::from("[1, 2]")),
+    );
+    assert_command(expected, ":insert fox [1, 2]");
+}
+
+#[test]
+fn insert_with_value() {
+    let expected = vec!(
+        NodeToken::InsertValue(String::from("[1, 2]")),
+    );
+    assert_command(expected, ":insert [1, 2]");
+}
+
+#[test]
+fn insert_with_single_argument() {
+    // the node decides whether the argument is a key or a value
+    let expected = vec!(
+        NodeToken::InsertKey(String::from("\"Fox\"")),
+    );
+    assert_command(expected, r#":insert \"Fox\""#);
+
+    let expected = vec!(
+        NodeToken::InsertKey(String::from("true")),
+    );
+    assert_command(expected, ":insert true");
+
+    let expected = vec!(
+        NodeToken::InsertKey(String::from("null")),
+    );
+    assert_command(expected, ":insert null");
+}
+
+#[test]
+fn push() {
+    let expected = vec!(
+        NodeToken::Push(String::from("{\"a\": 1}")),
+    );
+    assert_command(expected, r#":push {\"a\": 1}"#);
+    assert!(matches!(NodeRunner::new(":push"), Err (ref err) if err == "Missing value to push"));
+}
+
 #[test]
 fn remove() {
     let expected = vec!(
@@ -126,6 +192,38 @@ fn set_string_escape_quote() {
     assert_command(expected, r#":set foo \" bar"#);
 }
 
+#[test]
+fn get_with_format() {
+    let expected = vec!(
+        NodeToken::GetWith(GetOptions { format: Some(Format::Ron), ..GetOptions::default() }),
+    );
+    assert_command(expected, ":get ron");
+}
+
+#[test]
+fn get_with_options() {
+    let expected = vec!(
+        NodeToken::GetWith(GetOptions { format: Some(Format::Yaml), compact: true, depth: Some(2), sorted: true }),
+    );
+    assert_command(expected, ":get --compact --depth 2 yaml --sorted");
+}
+
+#[test]
+fn set_with_format() {
+    let expected = vec!(
+        NodeToken::SetWith(Format::Yaml, String::from("[1, 2]")),
+    );
+    assert_command(expected, ":set yaml [1, 2]");
+}
+
+#[test]
+fn set_format_name() {
+    let expected = vec!(
+        NodeToken::Set(String::from("ron")),
+    );
+    assert_command(expected, ":set ron");
+}
+
 #[test]
 fn copy() {
     let expected = vec!(
@@ -150,6 +248,42 @@ fn edit() {
     assert_command(expected, ":edit");
 }
 
+#[test]
+fn get_type() {
+    let expected = vec!(
+        NodeToken::GetType,
+        NodeToken::ChainIndex(0),
+    );
+    assert_command(expected, "[0]:type");
+}
+
+#[test]
+fn exists() {
+    let expected = vec!(
+        NodeToken::Exists,
+        NodeToken::ChainProperty(String::from("foo")),
+    );
+    assert_command(expected, "foo:exists");
+}
+
+#[test]
+fn transfer_path() {
+    // the path is kept as written rather than tokenized like the arguments of other actions
+    assert_command(vec!(NodeToken::CopyTo (String::from("bar[\"a b\"].baz")), NodeToken::ChainProperty (String::from("foo"))), "foo:copyto  bar[\"a b\"].baz ");
+    assert_command(vec!(NodeToken::MoveTo (String::from("/bar")), NodeToken::ChainKey (String::from("a:moveto b"))), "[\"a:moveto b\"]:moveto /bar");
+    assert_command(vec!(NodeToken::SwapWith (String::from("..")), NodeToken::ChainIndex (0)), "[0]:swapwith ..");
+    assert_command_fail("Missing path for copyto", "foo:copyto");
+}
+
+#[test]
+fn aggregates() {
+    assert_command(vec!(NodeToken::Sum, NodeToken::ChainAll), "[*]:sum");
+    assert_command(vec!(NodeToken::Min, NodeToken::ChainAll), "[*]:min");
+    assert_command(vec!(NodeToken::Max, NodeToken::ChainAll), "[*]:max");
+    assert_command(vec!(NodeToken::Mean, NodeToken::ChainAll), "[*]:mean");
+    assert_command(vec!(NodeToken::Count, NodeToken::ChainContext), "[?]:count");
+}
+
 #[test]
 fn getkeys() {
     let expected = vec!(
@@ -343,4 +477,9 @@ fn invalid_inputs() {
     assert_command_fail("Missing action", "[?]");
     assert_command_fail("Empty command", "");
     assert_command_fail("Missing action", r#"foo:"#);
+    assert_command_fail("Unknown format 'xml' (try one of: json, compact, ron, yaml, toml)", "foo:get xml");
+    assert_command_fail("Unknown get option '--pretty' (try one of: --compact, --depth, --sorted)", "foo:get --pretty");
+    assert_command_fail("--depth needs to be followed by a number", "foo:get --depth");
+    assert_command_fail("--depth needs to be followed by a number", "foo:get --depth two");
+    assert_command_fail("get can only use one format", "foo:get ron yaml");
 }
@@ -0,0 +1,143 @@
+extern crate treeflection;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+use treeflection::{Budget, CommandQueue};
+
+#[test]
+fn command_queue_process() {
+    let mut root: Vec<i32> = vec!(1, 2, 3);
+    let mut queue = CommandQueue::new();
+    let handle = queue.handle();
+
+    let set = handle.submit("[0]:set 5");
+    let get = handle.submit("[0]:get");
+    let invalid = handle.submit("[0]");
+    assert!(!set.is_done());
+
+    assert_eq!(queue.process(&mut root), 3);
+    assert!(set.is_done());
+    assert_eq!(set.wait(), Ok(String::new()));
+    assert_eq!(get.wait(), Ok(String::from("5")));
+    assert_eq!(invalid.wait(), Err(String::from("Missing action")));
+    assert_eq!(queue.process(&mut root), 0);
+}
+
+#[test]
+fn command_queue_if() {
+    let mut root: Vec<i32> = vec!(1, 2, 3);
+    let mut queue = CommandQueue::new();
+    let handle = queue.handle();
+
+    let then = handle.submit("if [0]:get < 2 then [0]:set 5 else [0]:set 6");
+    let failed = handle.submit("if [3]:get < 2 then [0]:set 7");
+    let invalid = handle.submit("if [0]:get < 2");
+    assert_eq!(queue.process(&mut root), 3);
+    assert_eq!(then.wait(), Ok(String::new()));
+    assert_eq!(failed.wait(), Ok(String::from("The condition '[3]:get' failed: Used index 3 on a vector of size 3 (try a value between 0-2)")));
+    assert_eq!(invalid.wait(), Err(String::from("Missing then (needs to be: if COMMAND OPERATOR VALUE then COMMAND [else COMMAND])")));
+    assert_eq!(root, vec!(5, 2, 3));
+}
+
+#[test]
+fn command_queue_threads() {
+    let mut root: Vec<i32> = vec!(0);
+    let mut queue = CommandQueue::new();
+    let threads: Vec<_> = (0..4).map(|_| {
+        let handle = queue.handle();
+        thread::spawn(move || {
+            for _ in 0..10 {
+                assert_eq!(handle.submit("[0]:set 1").wait(), Ok(String::new()));
+            }
+            handle.submit("[0]:get").wait().unwrap()
+        })
+    }).collect();
+
+    while !threads.iter().all(|x| x.is_finished()) {
+        queue.process(&mut root);
+        thread::yield_now();
+    }
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), "1");
+    }
+}
+
+#[test]
+fn command_queue_budget_commands() {
+    let mut root: Vec<i32> = vec!(1, 2, 3);
+    let mut queue = CommandQueue::new();
+    queue.set_budget(Budget { max_commands: Some(2), max_duration: None });
+    let handle = queue.handle();
+    let pending: Vec<_> = (0..5).map(|i| handle.submit(format!("[{}]:get", i % 3))).collect();
+
+    assert_eq!(queue.process(&mut root), 2);
+    assert_eq!(pending.iter().filter(|x| x.is_done()).count(), 2);
+    assert_eq!(queue.process(&mut root), 2);
+    assert_eq!(queue.process(&mut root), 1);
+    assert_eq!(queue.process(&mut root), 0);
+
+    let results: Vec<_> = pending.into_iter().map(|x| x.wait().unwrap()).collect();
+    assert_eq!(results, vec!("1", "2", "3", "1", "2"));
+}
+
+#[test]
+fn command_queue_budget_duration() {
+    let mut root: Vec<i32> = vec!(1);
+    let mut queue = CommandQueue::new();
+    queue.set_budget(Budget { max_commands: None, max_duration: Some(Duration::from_secs(0)) });
+    let pending = queue.handle().submit("[0]:get");
+
+    assert_eq!(queue.process(&mut root), 0);
+    assert!(!pending.is_done());
+
+    queue.set_budget(Budget::default());
+    assert_eq!(queue.process(&mut root), 1);
+    assert_eq!(pending.wait(), Ok(String::from("1")));
+}
+
+#[test]
+fn command_queue_wait_timeout() {
+    let mut root: Vec<i32> = vec!(1);
+    let mut queue = CommandQueue::new();
+    let pending = queue.handle().submit("[0]:get");
+
+    let pending = pending.wait_timeout(Duration::from_millis(1)).unwrap_err();
+    queue.process(&mut root);
+    assert_eq!(pending.wait_timeout(Duration::from_millis(1)).ok(), Some(Ok(String::from("1"))));
+}
+
+#[test]
+fn command_queue_dropped() {
+    let queue = CommandQueue::new();
+    let handle = queue.handle();
+    let pending = handle.submit("[0]:get");
+    drop(queue);
+
+    let error = Err(String::from("The command queue was dropped before running the command"));
+    assert_eq!(pending.wait(), error);
+    assert_eq!(handle.submit("[0]:get").wait(), error);
+}
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) { }
+}
+
+#[test]
+fn command_queue_future() {
+    let mut root: Vec<i32> = vec!(1, 2);
+    let mut queue = CommandQueue::new();
+    let mut pending = queue.handle().submit("[1]:get");
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    assert_eq!(Pin::new(&mut pending).poll(&mut context), Poll::Pending);
+    queue.process(&mut root);
+    assert_eq!(Pin::new(&mut pending).poll(&mut context), Poll::Ready(Ok(String::from("2"))));
+}
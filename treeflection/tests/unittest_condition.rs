@@ -0,0 +1,53 @@
+extern crate treeflection;
+
+use treeflection::condition::{self, Condition};
+
+#[test]
+fn condition_parse() {
+    assert_eq!(Condition::parse("if [0]:get >= 2 then [1]:set 3 else [1]:set 4"), Some(Ok(Condition {
+        command:   "[0]:get",
+        operator:  ">=",
+        value:     "2",
+        then_line: "[1]:set 3",
+        else_line: Some("[1]:set 4"),
+    })));
+    assert_eq!(Condition::parse("if name:get == \"Fox McCloud\" then name:set Fox"), Some(Ok(Condition {
+        command:   "name:get",
+        operator:  "==",
+        value:     "\"Fox McCloud\"",
+        then_line: "name:set Fox",
+        else_line: None,
+    })));
+    assert_eq!(Condition::parse("[0]:get"), None);
+    assert_eq!(Condition::parse("iffy:get"), None);
+}
+
+#[test]
+fn condition_branch() {
+    let condition = Condition::parse("if [0]:get < 10 then a else b").unwrap().unwrap();
+    assert_eq!(condition.branch("9"), Some("a"));
+    assert_eq!(condition.branch("10.5"), Some("b"));
+
+    let condition = Condition::parse("if [0]:get != Fox then a").unwrap().unwrap();
+    assert_eq!(condition.branch("\"Fox\""), None);
+    assert_eq!(condition.branch("\"Falco\""), Some("a"));
+}
+
+#[test]
+fn condition_run() {
+    let mut root: Vec<Vec<u32>> = vec!(vec!(1), vec!());
+    assert_eq!(condition::run(&mut root, "if [0][0]:get == 1 then [1]:push 2"), Ok(String::new()));
+    assert_eq!(condition::run(&mut root, "if [1][0]:exists == true then [1][0]:get else [0][0]:get"), Ok(String::from("2")));
+    assert_eq!(condition::run(&mut root, "[0]:get compact"), Ok(String::from("[1]")));
+    assert_eq!(condition::run(&mut root, "if [2]:get == 1 then [0]:reset"), Ok(String::from("The condition '[2]:get' failed: Used index 2 on a vector of size 2 (try a value between 0-1)")));
+    assert_eq!(condition::run(&mut root, "if [0][0]:get == 1 then [0]"), Err(String::from("Missing action")));
+    assert_eq!(root, vec!(vec!(1), vec!(2)));
+}
+
+#[test]
+fn condition_check() {
+    assert_eq!(condition::check("[0]:get"), Ok(()));
+    assert_eq!(condition::check("if [0]:get == 1 then [0]:set 2 else if [1]:get == 1 then [1]:set 2"), Ok(()));
+    assert_eq!(condition::check("if [0]:get == 1 then [0]:set 2 else if [1]:reset == 1 then [1]:set 2"), Err(String::from("The condition '[1]:reset' must not modify anything (try an action like get)")));
+    assert_eq!(condition::check("if [0]:get == 1 then [0]"), Err(String::from("Missing action")));
+}
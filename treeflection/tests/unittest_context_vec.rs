@@ -1,6 +1,9 @@
 extern crate treeflection;
 #[macro_use] extern crate matches;
 
+mod common;
+
+use common::assert_fan_out;
 use treeflection::{Node, NodeRunner, NodeToken, ContextVec};
 
 fn test_vec4() -> ContextVec<i32> {
@@ -305,14 +308,25 @@ fn help() {
 Context Vector Help
 
 Commands:
-*   help          - display this help
-*   get           - display JSON
-*   set           - set to JSON
-*   insert        - create a new element at the end of the vector
-*   insert $INDEX - create a new element at $INDEX
-*   remove        - remove the element at the end of the vector
-*   remove $INDEX - remove the element at $INDEX
-*   reset         - reset to empty vector
+*   help                 - display this help
+*   get                  - display JSON
+*   set                  - set to JSON
+*   edit                 - edit JSON in your editor
+*   type                 - display the Rust type
+*   insert               - create a new element at the end of the vector
+*   insert $INDEX        - create a new element at $INDEX
+*   insert $INDEX $JSON  - insert $JSON at $INDEX
+*   push $JSON           - insert $JSON at the end of the vector
+*   remove               - remove the element at the end of the vector
+*   remove $INDEX        - remove the element at $INDEX
+*   reset                - reset to empty vector
+*   swap $INDEX $INDEX   - swap the elements at the two indexes
+*   move $FROM $TO       - move the element at $FROM to $TO
+*   reverse              - reverse the order of the elements
+*   duplicate $INDEX     - insert a copy of the element at $INDEX after it
+*   truncate $LENGTH     - remove the elements from $LENGTH onwards
+*   resize $LENGTH       - remove or create elements until there are $LENGTH
+*   sortby $PATH [ORDER] - sort by the value at $PATH in each element, ORDER is ascending or descending
 
 Accessors:
 *   [INDEX] - access item at INDEX
@@ -329,22 +343,22 @@ fn node_step_chain_context() {
         NodeToken::ChainContext
     )};
 
-    assert_eq!("|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context(0);
-    assert_eq!("|10|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[0]", "10")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context(1);
-    assert_eq!("|1337|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[1]", "1337")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context(2);
-    assert_eq!("|42|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[2]", "42")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context_vec(vec!(2, 0));
-    assert_eq!("|42|10|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[2]", "42"), ("[0]", "10")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context_vec(vec!(0, 1, 2));
-    assert_eq!("|10|1337|42|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[0]", "10"), ("[1]", "1337"), ("[2]", "42")], &context_vec.node_step(runner.clone()));
 }
 
 #[test]
@@ -353,7 +367,7 @@ fn node_step_chain_all() {
         NodeToken::Get,
         NodeToken::ChainAll,
     )};
-    assert_eq!("|100000|13|-358|42|", test_vec4().node_step(runner));
+    assert_fan_out(&[("[0]", "100000"), ("[1]", "13"), ("[2]", "-358"), ("[3]", "42")], &test_vec4().node_step(runner));
 }
 
 #[test]
@@ -551,3 +565,116 @@ fn node_step_set_fail()
     let runner = NodeRunner { tokens: vec!(NodeToken::Set(String::from("[1, lol]"))) };
     assert_eq!(some_vec.node_step(runner), String::from("vector set error: expected value at line 1 column 5"));
 }
+
+#[test]
+fn node_step_set_context() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(3, 1));
+    assert_eq!(run(&mut some_vec, ":set compact [7]"), "");
+    assert_eq!(*some_vec, [7]);
+    assert!(some_vec.get_context().is_empty());
+    assert!(some_vec.selection().is_empty());
+
+    some_vec.set_context(0);
+    assert_eq!(run(&mut some_vec, ":reset"), "");
+    assert!(some_vec.get_context().is_empty());
+}
+
+fn run(vec: &mut ContextVec<i32>, command: &str) -> String {
+    vec.node_step(NodeRunner::new(command).unwrap())
+}
+
+#[test]
+fn node_step_swap() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(0, 1));
+    assert_eq!(run(&mut some_vec, ":swap 0 3"), "");
+    assert_eq!(*some_vec, [42, 13, -358, 100000]);
+    assert_eq!(some_vec.get_context(), &[3, 1]);
+    assert_eq!(some_vec.selection(), vec!(&100000, &13));
+
+    assert_eq!(run(&mut some_vec, ":swap 0 4"), "Used index 4 on a vector of size 4 (try a value between 0-3)");
+    assert_eq!(some_vec.get_context(), &[3, 1]);
+}
+
+#[test]
+fn node_step_move() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(0, 3));
+    assert_eq!(run(&mut some_vec, ":move 0 2"), "");
+    assert_eq!(*some_vec, [13, -358, 100000, 42]);
+    assert_eq!(some_vec.selection(), vec!(&100000, &42));
+}
+
+#[test]
+fn node_step_reverse() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(1, 3));
+    assert_eq!(run(&mut some_vec, ":reverse"), "");
+    assert_eq!(*some_vec, [42, -358, 13, 100000]);
+    assert_eq!(some_vec.get_context(), &[2, 0]);
+    assert_eq!(some_vec.selection(), vec!(&13, &42));
+}
+
+#[test]
+fn node_step_duplicate() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(1, 2));
+    assert_eq!(run(&mut some_vec, ":duplicate 1"), "");
+    assert_eq!(*some_vec, [100000, 13, 13, -358, 42]);
+    // the context stays on the original rather than the copy
+    assert_eq!(some_vec.get_context(), &[1, 3]);
+    assert_eq!(some_vec.selection(), vec!(&13, &-358));
+}
+
+#[test]
+fn node_step_truncate_resize() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(3, 0, 2));
+    assert_eq!(run(&mut some_vec, ":truncate 3"), "");
+    assert_eq!(*some_vec, [100000, 13, -358]);
+    assert_eq!(some_vec.get_context(), &[0, 2]);
+
+    assert_eq!(run(&mut some_vec, ":resize 4"), "");
+    assert_eq!(*some_vec, [100000, 13, -358, 0]);
+    assert_eq!(some_vec.get_context(), &[0, 2]);
+
+    assert_eq!(run(&mut some_vec, ":resize 1"), "");
+    assert_eq!(*some_vec, [100000]);
+    assert_eq!(some_vec.get_context(), &[0]);
+}
+
+#[test]
+fn node_step_sortby() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(2, 0));
+    assert_eq!(run(&mut some_vec, ":sortby"), "");
+    assert_eq!(*some_vec, [-358, 13, 42, 100000]);
+    assert_eq!(some_vec.get_context(), &[0, 3]);
+    assert_eq!(some_vec.selection(), vec!(&-358, &100000));
+
+    assert_eq!(run(&mut some_vec, ":sortby descending"), "");
+    assert_eq!(*some_vec, [100000, 42, 13, -358]);
+    assert_eq!(some_vec.selection(), vec!(&-358, &100000));
+}
+
+#[test]
+fn node_step_insert_value() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(1, 3));
+    assert_eq!(run(&mut some_vec, ":insert 2 7"), "");
+    assert_eq!(*some_vec, [100000, 13, 7, -358, 42]);
+    assert_eq!(some_vec.selection(), vec!(&13, &42));
+    assert_eq!(run(&mut some_vec, ":insert 6 7"), "Tried to insert at index 6 on a vector of size 5 (try a value between 0-5)");
+    assert_eq!(run(&mut some_vec, ":insert 0 seven"), "vector insert error: expected value at line 1 column 1");
+    assert_eq!(*some_vec, [100000, 13, 7, -358, 42]);
+}
+
+#[test]
+fn node_step_push() {
+    let mut some_vec = test_vec1();
+    some_vec.set_context(0);
+    assert_eq!(run(&mut some_vec, ":push 8"), "");
+    assert_eq!(*some_vec, [13, 8]);
+    assert_eq!(some_vec.selection(), vec!(&13));
+}
@@ -0,0 +1,130 @@
+#![cfg(unix)]
+
+extern crate treeflection;
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use treeflection::{Node, NodeRunner};
+
+// the editor is read from the environment, so tests changing it can't run at the same time
+static EDITOR_LOCK: Mutex<()> = Mutex::new(());
+
+/// Runs `:edit` on `node` with an editor that runs `script` on the file
+fn edit<T: Node>(node: &mut T, name: &str, script: &str) -> String {
+    edit_command(node, ":edit", name, script)
+}
+
+fn edit_command<T: Node>(node: &mut T, command: &str, name: &str, script: &str) -> String {
+    let _lock = EDITOR_LOCK.lock().unwrap_or_else(|x| x.into_inner());
+    let editor = editor_script(name, script);
+    env::remove_var("VISUAL");
+    env::set_var("EDITOR", &editor);
+    let result = node.node_step(NodeRunner::new(command).unwrap());
+    fs::remove_file(editor).unwrap();
+    result
+}
+
+fn editor_script(name: &str, script: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("treeflection-test-editor-{}-{}", std::process::id(), name));
+    fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn edit_vec() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    assert_eq!(edit(&mut vec, "vec", r#"sed -i 's/2/3, 4/' "$1""#), "");
+    assert_eq!(vec, vec!(1, 3, 4));
+}
+
+#[test]
+fn edit_string() {
+    let mut string = String::from("Fox");
+    // editors add a newline to the end of the file which shouldn't end up in the value
+    assert_eq!(edit(&mut string, "string", r#"printf 'Falco\n' > "$1""#), "");
+    assert_eq!(string, "Falco");
+}
+
+#[test]
+fn edit_nested() {
+    let mut vec: Vec<Vec<u32>> = vec!(vec!(1), vec!(2));
+    assert_eq!(edit_command(&mut vec, "[1]:edit", "nested", r#"printf '[5]' > "$1""#), "");
+    assert_eq!(vec, vec!(vec!(1), vec!(5)));
+}
+
+#[test]
+fn edit_unchanged() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    assert_eq!(edit(&mut vec, "unchanged", "true"), "");
+    assert_eq!(vec, vec!(1, 2));
+}
+
+#[test]
+fn edit_private_file() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    // the editor only saves when the file can't be accessed by other users
+    assert_eq!(edit(&mut vec, "private", r#"ls -l "$1" | grep -q '^-rw-------' && printf '[6]' > "$1""#), "");
+    assert_eq!(vec, vec!(6));
+}
+
+#[test]
+fn edit_error_then_fix() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    let log = env::temp_dir().join(format!("treeflection-test-editor-{}-log", std::process::id()));
+    // the first edit is invalid, the second edit sees the error and fixes it
+    let script = format!(r#"
+if grep -q '^// ' "$1"; then
+    cp "$1" '{}'
+    printf '[7]' > "$1"
+else
+    printf '[1, ' > "$1"
+fi"#, log.display());
+    assert_eq!(edit(&mut vec, "fix", &script), "");
+    assert_eq!(vec, vec!(7));
+
+    let logged = fs::read_to_string(&log).unwrap();
+    fs::remove_file(&log).unwrap();
+    assert_eq!(logged, "// vector set error: EOF while parsing a value at line 1 column 4\n// Fix the errors and save to try again, or save without changes to cancel\n[1, \n");
+}
+
+#[test]
+fn edit_error_cancelled() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    // keeps saving the same invalid JSON, which cancels after the first error
+    assert_eq!(edit(&mut vec, "cancel", r#"printf '[1, ' > "$1""#), "vector set error: EOF while parsing a value at line 1 column 4");
+    assert_eq!(vec, vec!(1, 2));
+}
+
+#[test]
+fn edit_editor_fails() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    assert_eq!(edit(&mut vec, "fails", r#"printf '[3]' > "$1"; exit 1"#), "Edit cancelled: The editor exited with exit status: 1");
+    assert_eq!(vec, vec!(1, 2));
+}
+
+#[test]
+fn edit_no_editor() {
+    let _lock = EDITOR_LOCK.lock().unwrap_or_else(|x| x.into_inner());
+    env::remove_var("VISUAL");
+    env::set_var("EDITOR", "");
+    let mut vec: Vec<u32> = vec!(1, 2);
+    assert_eq!(vec.node_step(NodeRunner::new(":edit").unwrap()), "Cannot edit: Set $VISUAL or $EDITOR to the command that runs your editor");
+}
+
+#[test]
+fn edit_visual_first() {
+    let _lock = EDITOR_LOCK.lock().unwrap_or_else(|x| x.into_inner());
+    let visual = editor_script("visual", r#"printf '[8]' > "$1""#);
+    env::set_var("VISUAL", &visual);
+    env::set_var("EDITOR", "false");
+    let mut vec: Vec<u32> = vec!(1, 2);
+    assert_eq!(vec.node_step(NodeRunner::new(":edit").unwrap()), "");
+    env::remove_var("VISUAL");
+    fs::remove_file(visual).unwrap();
+    assert_eq!(vec, vec!(8));
+}
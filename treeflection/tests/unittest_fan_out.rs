@@ -0,0 +1,157 @@
+extern crate treeflection;
+
+mod common;
+
+use common::assert_fan_out;
+use treeflection::{Node, NodeRunner, ContextVec};
+
+#[test]
+fn fan_out_output() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    let expected = r#"[
+  {
+    "path": "[0]",
+    "result": "1"
+  },
+  {
+    "path": "[1]",
+    "result": "2"
+  }
+]"#;
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:get").unwrap()), expected);
+}
+
+#[test]
+fn fan_out_empty() {
+    let mut vec: Vec<u32> = vec!();
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:get").unwrap()), "[]");
+    assert_fan_out(&[], "[]");
+}
+
+#[test]
+fn fan_out_results_containing_separators() {
+    // results are JSON strings so they can contain anything
+    let mut vec: Vec<String> = vec!(String::from("a|b"), String::from("c\"d"));
+    let output = vec.node_step(NodeRunner::new("[*]:get").unwrap());
+    assert_fan_out(&[("[0]", "a|b"), ("[1]", "c\"d")], &output);
+}
+
+#[test]
+fn fan_out_nested() {
+    let mut vec: Vec<Vec<u32>> = vec!(vec!(1, 2), vec!(), vec!(3));
+    let output = vec.node_step(NodeRunner::new("[*][*]:get").unwrap());
+    let expected = r#"[
+  {
+    "path": "[0]",
+    "result": [
+      {
+        "path": "[0][0]",
+        "result": "1"
+      },
+      {
+        "path": "[0][1]",
+        "result": "2"
+      }
+    ]
+  },
+  {
+    "path": "[1]",
+    "result": []
+  },
+  {
+    "path": "[2]",
+    "result": [
+      {
+        "path": "[2][0]",
+        "result": "3"
+      }
+    ]
+  }
+]"#;
+    assert_eq!(output, expected);
+    assert_fan_out(&[("[0][0]", "1"), ("[0][1]", "2"), ("[2][0]", "3")], &output);
+}
+
+#[test]
+fn fan_out_nested_error() {
+    // an error before reaching the nested fan-out is the result of that child
+    let mut vec: Vec<Vec<Vec<u32>>> = vec!(vec!(vec!(1)), vec!());
+    let output = vec.node_step(NodeRunner::new("[*][0][*]:get").unwrap());
+    assert_fan_out(&[("[0][0][0]", "1"), ("[1][0]", "Used index 0 on an empty vector")], &output);
+}
+
+#[test]
+fn fan_out_context() {
+    let mut vec: ContextVec<u32> = ContextVec::from_vec(vec!(4, 5, 6));
+    vec.set_context_vec(vec!(2, 0));
+    let output = vec.node_step(NodeRunner::new("[?]:get").unwrap());
+    assert_fan_out(&[("[2]", "6"), ("[0]", "4")], &output);
+}
+
+#[test]
+fn aggregate_integers() {
+    let mut vec: Vec<i32> = vec!(4, -2, 12);
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:sum").unwrap()), "14");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:min").unwrap()), "-2");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:max").unwrap()), "12");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:mean").unwrap()), "4.666666666666667");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:count").unwrap()), "3");
+    assert_eq!(vec, vec!(4, -2, 12));
+}
+
+#[test]
+fn aggregate_floats() {
+    let mut vec: Vec<f64> = vec!(1.5, 2.5, -1.0);
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:sum").unwrap()), "3.0");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:min").unwrap()), "-1.0");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:max").unwrap()), "2.5");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:mean").unwrap()), "1.0");
+}
+
+#[test]
+fn aggregate_empty() {
+    let mut vec: Vec<u32> = vec!();
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:sum").unwrap()), "0");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:count").unwrap()), "0");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:max").unwrap()), "Cannot max as there are no numbers");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:mean").unwrap()), "Cannot mean as there are no numbers");
+}
+
+#[test]
+fn aggregate_nested() {
+    // every number reached is used, whether from a nested fan-out or nested in a value
+    let mut vec: Vec<Vec<u32>> = vec!(vec!(1, 2), vec!(), vec!(3));
+    assert_eq!(vec.node_step(NodeRunner::new("[*][*]:sum").unwrap()), "6");
+    assert_eq!(vec.node_step(NodeRunner::new("[*][*]:count").unwrap()), "3");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:sum").unwrap()), "6");
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:count").unwrap()), "3");
+}
+
+#[test]
+fn aggregate_context() {
+    let mut vec: ContextVec<u32> = ContextVec::from_vec(vec!(4, 5, 6));
+    vec.set_context_vec(vec!(2, 0));
+    assert_eq!(vec.node_step(NodeRunner::new("[?]:sum").unwrap()), "10");
+    assert_eq!(vec.node_step(NodeRunner::new("[?]:mean").unwrap()), "5.0");
+}
+
+#[test]
+fn aggregate_skips_other_values() {
+    let mut vec: Vec<(String, u32)> = vec!((String::from("jab"), 4), (String::from("dair"), 12));
+    assert_eq!(vec.node_step(NodeRunner::new("[*]:sum").unwrap()), "16");
+    assert_eq!(vec.node_step(NodeRunner::new("[*][0]:sum").unwrap()), "0");
+}
+
+#[test]
+fn aggregate_error() {
+    let mut vec: Vec<Vec<u32>> = vec!(vec!(1), vec!());
+    assert_eq!(vec.node_step(NodeRunner::new("[*][0]:sum").unwrap()), "Cannot sum as '[1][0]' failed: Used index 0 on an empty vector");
+    // count only counts the values that can be reached
+    assert_eq!(vec.node_step(NodeRunner::new("[*][0]:count").unwrap()), "1");
+}
+
+#[test]
+fn aggregate_without_fan_out() {
+    let mut vec: Vec<u32> = vec!(1, 2);
+    assert_eq!(vec.node_step(NodeRunner::new(":sum").unwrap()), "vector cannot 'Sum'");
+}
@@ -0,0 +1,146 @@
+extern crate treeflection;
+
+use std::collections::HashMap;
+
+use treeflection::{Node, NodeRunner, Session, ContextVec};
+
+#[test]
+fn vec_get_formats() {
+    let mut vec: Vec<(u32, String)> = vec!((1, String::from("Fox")), (2, String::from("Falco")));
+    assert_eq!(vec.node_step(NodeRunner::new(":get compact").unwrap()), r#"[[1,"Fox"],[2,"Falco"]]"#);
+    assert_eq!(vec.node_step(NodeRunner::new(":get json").unwrap()), vec.node_step(NodeRunner::new(":get").unwrap()));
+    assert_eq!(vec.node_step(NodeRunner::new(":get ron").unwrap()),
+r#"[
+    (1, "Fox"),
+    (2, "Falco"),
+]"#);
+    assert_eq!(vec.node_step(NodeRunner::new(":get yaml").unwrap()),
+r#"- - 1
+  - Fox
+- - 2
+  - Falco"#);
+    assert_eq!(vec.node_step(NodeRunner::new("[1]:get ron").unwrap()), r#"(2, "Falco")"#);
+}
+
+#[test]
+fn vec_set_formats() {
+    let mut vec: Vec<u32> = vec!();
+    assert_eq!(vec.node_step(NodeRunner::new(":set ron [1, 2,]").unwrap()), "");
+    assert_eq!(vec, vec!(1, 2));
+    assert_eq!(vec.node_step(NodeRunner::new(":set yaml [3]").unwrap()), "");
+    assert_eq!(vec, vec!(3));
+    assert_eq!(vec.node_step(NodeRunner::new(":set compact [4,5]").unwrap()), "");
+    assert_eq!(vec, vec!(4, 5));
+    assert_eq!(vec.node_step(NodeRunner::new("[0]:set ron 6").unwrap()), "");
+    assert_eq!(vec, vec!(6, 5));
+
+    assert_eq!(vec.node_step(NodeRunner::new(":set ron [1, 2").unwrap()), "vector set error: 1:6: Unexpected end of RON");
+    assert_eq!(vec, vec!(6, 5));
+}
+
+#[test]
+fn map_toml() {
+    let mut map: HashMap<String, u32> = HashMap::new();
+    map.insert(String::from("stocks"), 4);
+    assert_eq!(map.node_step(NodeRunner::new(":get toml").unwrap()), "stocks = 4");
+    assert_eq!(map.node_step(NodeRunner::new(":set toml time = 8").unwrap()), "");
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["time"], 8);
+
+    // toml documents are always tables
+    let mut vec: Vec<u32> = vec!(1);
+    assert_eq!(vec.node_step(NodeRunner::new(":get toml").unwrap()), "Failed to get as toml: unsupported rust type");
+}
+
+#[test]
+fn primitive_formats() {
+    let mut string = String::from("Fox");
+    assert_eq!(string.node_step(NodeRunner::new(":get ron").unwrap()), "\"Fox\"");
+    assert_eq!(string.node_step(NodeRunner::new(r#":set ron \"Falco\""#).unwrap()), "");
+    assert_eq!(string, "Falco");
+    assert_eq!(string.node_step(NodeRunner::new(":set ron Falco").unwrap()), "String set error: 1:1: Expected string");
+
+    let mut number: f32 = 1.5;
+    assert_eq!(number.node_step(NodeRunner::new(":get yaml").unwrap()), "1.5");
+    assert_eq!(number.node_step(NodeRunner::new(":set yaml 2.5").unwrap()), "");
+    assert_eq!(number, 2.5);
+
+    let mut boolean = false;
+    assert_eq!(boolean.node_step(NodeRunner::new(":set ron true").unwrap()), "");
+    assert!(boolean);
+    assert_eq!(boolean.node_step(NodeRunner::new(":set ron yes").unwrap()), "bool set error: 1:1: Expected boolean");
+    assert!(boolean);
+}
+
+#[test]
+fn context_vec_ron() {
+    let mut vec: ContextVec<u32> = ContextVec::new();
+    vec.push(3);
+    assert_eq!(vec.node_step(NodeRunner::new(":get ron").unwrap()), "[\n    3,\n]");
+    assert_eq!(vec.node_step(NodeRunner::new(":set ron [4]").unwrap()), "");
+    assert_eq!(vec.node_step(NodeRunner::new(":get compact").unwrap()), "[4]");
+}
+
+#[test]
+fn session_format() {
+    let mut session = Session::new();
+    let mut root: Vec<u32> = vec!(1, 2);
+    assert_eq!(session.run(&mut root, "format"), Ok(String::from("json")));
+    assert_eq!(session.run(&mut root, "format ron"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "format"), Ok(String::from("ron")));
+    assert_eq!(session.run(&mut root, ":get"), Ok(String::from("[\n    1,\n    2,\n]")));
+    assert_eq!(session.run(&mut root, ":set [3,]"), Ok(String::new()));
+    assert_eq!(root, vec!(3));
+
+    // an explicit format takes priority
+    assert_eq!(session.run(&mut root, ":get compact"), Ok(String::from("[3]")));
+
+    assert_eq!(session.run(&mut root, "format xml"), Err(String::from("Unknown format 'xml' (try one of: json, compact, ron, yaml, toml)")));
+    assert_eq!(session.run(&mut root, "format json"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, ":get"), Ok(String::from("[\n  3\n]")));
+}
+
+#[test]
+fn get_compact() {
+    let mut vec: Vec<Vec<u32>> = vec!(vec!(1, 2), vec!());
+    assert_eq!(vec.node_step(NodeRunner::new(":get --compact").unwrap()), "[[1,2],[]]");
+    assert_eq!(vec.node_step(NodeRunner::new(":get ron --compact").unwrap()), "[[1,2],[]]");
+    // yaml does not have a single line form
+    assert_eq!(vec.node_step(NodeRunner::new(":get yaml --compact").unwrap()), "- - 1\n  - 2\n- []");
+}
+
+#[test]
+fn get_depth() {
+    let mut vec: Vec<Vec<Vec<u32>>> = vec!(vec!(vec!(1), vec!()), vec!());
+    assert_eq!(vec.node_step(NodeRunner::new(":get --compact --depth 0").unwrap()), r#""…""#);
+    assert_eq!(vec.node_step(NodeRunner::new(":get --compact --depth 1").unwrap()), r#"["…",[]]"#);
+    assert_eq!(vec.node_step(NodeRunner::new(":get --compact --depth 2").unwrap()), r#"[["…",[]],[]]"#);
+    assert_eq!(vec.node_step(NodeRunner::new(":get --compact --depth 3").unwrap()), "[[[1],[]],[]]");
+    assert_eq!(vec.node_step(NodeRunner::new(":get --depth 1").unwrap()), "[\n  \"…\",\n  []\n]");
+    assert_eq!(vec.node_step(NodeRunner::new("[0]:get ron --depth 1").unwrap()), "[\n    \"…\",\n    [],\n]");
+}
+
+#[test]
+fn get_sorted() {
+    let mut map: HashMap<String, HashMap<String, u32>> = HashMap::new();
+    for (i, key) in ["d", "b", "a", "e", "c"].iter().enumerate() {
+        let mut inner = HashMap::new();
+        inner.insert(String::from("z"), i as u32);
+        inner.insert(String::from("y"), i as u32);
+        map.insert(key.to_string(), inner);
+    }
+    let expected = r#"{"a":{"y":2,"z":2},"b":{"y":1,"z":1},"c":{"y":4,"z":4},"d":{"y":0,"z":0},"e":{"y":3,"z":3}}"#;
+    assert_eq!(map.node_step(NodeRunner::new(":get --sorted --compact").unwrap()), expected);
+    assert_eq!(map.node_step(NodeRunner::new(":get --sorted --depth 1 --compact").unwrap()), r#"{"a":"…","b":"…","c":"…","d":"…","e":"…"}"#);
+    assert_eq!(map.node_step(NodeRunner::new(":get --sorted toml").unwrap()), "[a]\ny = 2\nz = 2\n\n[b]\ny = 1\nz = 1\n\n[c]\ny = 4\nz = 4\n\n[d]\ny = 0\nz = 0\n\n[e]\ny = 3\nz = 3");
+}
+
+#[test]
+fn session_get_options() {
+    let mut session = Session::new();
+    let mut root: Vec<Vec<u32>> = vec!(vec!(1), vec!(2));
+    assert_eq!(session.run(&mut root, "format ron"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, ":get --compact"), Ok(String::from("[[1],[2]]")));
+    assert_eq!(session.run(&mut root, ":get --depth 1"), Ok(String::from("[\n    \"…\",\n    \"…\",\n]")));
+    assert_eq!(session.run(&mut root, ":get json --compact"), Ok(String::from("[[1],[2]]")));
+}
@@ -0,0 +1,287 @@
+extern crate treeflection;
+#[macro_use] extern crate serde_json;
+
+mod common;
+
+use serde_json::Value;
+use common::assert_fan_out;
+use treeflection::{Node, NodeRunner, NodeToken};
+
+fn test_json() -> Value {
+    json!({
+        "name": "Fox",
+        "weight": 75,
+        "gravity": 0.23,
+        "grounded": true,
+        "tags": ["fast", "light"],
+        "moves": {
+            "jab": { "damage": 4 },
+            "dair": { "damage": 12 }
+        },
+        "nothing": null
+    })
+}
+
+fn run(value: &mut Value, command: &str) -> String {
+    value.node_step(NodeRunner::new(command).unwrap())
+}
+
+#[test]
+fn json_property() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "name:get"), "\"Fox\"");
+    assert_eq!(run(&mut value, "weight:get"), "75");
+    assert_eq!(run(&mut value, "gravity:get"), "0.23");
+    assert_eq!(run(&mut value, "grounded:get"), "true");
+    assert_eq!(run(&mut value, "nothing:get"), "null");
+    assert_eq!(run(&mut value, "moves.dair.damage:get"), "12");
+    assert_eq!(run(&mut value, "notname:get"), "json object does not have a property 'notname'");
+    assert_eq!(run(&mut value, "name.first:get"), "json string does not have a property 'first'");
+}
+
+#[test]
+fn json_length() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "tags.length:get"), "2");
+    assert_eq!(run(&mut value, "moves.length:get"), "2");
+    assert_eq!(run(&mut value, "length:get"), "7");
+
+    let mut value = json!({ "length": 42 });
+    assert_eq!(run(&mut value, "length:get"), "42");
+}
+
+#[test]
+fn json_chain_index() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "tags[0]:get"), "\"fast\"");
+    assert_eq!(run(&mut value, "tags[1]:get"), "\"light\"");
+    assert_eq!(run(&mut value, "tags[2]:get"), "Used index 2 on a json array of size 2 (try a value between 0-1)");
+    assert_eq!(run(&mut value, "name[0]:get"), "Cannot index json string");
+
+    let mut value = json!([]);
+    assert_eq!(run(&mut value, "[0]:get"), "Used index 0 on an empty json array");
+
+    let mut value = json!([1]);
+    assert_eq!(run(&mut value, "[1]:get"), "Used index 1 on a json array of size 1 (try 0)");
+}
+
+#[test]
+fn json_chain_key() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "moves[\"jab\"].damage:get"), "4");
+    assert_eq!(run(&mut value, "moves[\"fair\"]:get"), "Used key 'fair' on a json object that does not contain it. Try one of: 'dair', 'jab'");
+    assert_eq!(run(&mut value, "tags[\"fast\"]:get"), "Cannot use a key on json array");
+
+    let mut value = json!({});
+    assert_eq!(run(&mut value, "[\"foo\"]:get"), "Used key 'foo' on an empty json object.");
+}
+
+#[test]
+fn json_chain_all() {
+    let mut value = test_json();
+    assert_fan_out(&[("[0]", "\"fast\""), ("[1]", "\"light\"")], &run(&mut value, "tags[*]:get"));
+    assert_fan_out(&[("[\"dair\"].damage", "12"), ("[\"jab\"].damage", "4")], &run(&mut value, "moves[*].damage:get"));
+    assert_eq!(run(&mut value, "name[*]:get"), "json string cannot 'ChainAll'");
+}
+
+#[test]
+fn json_aggregate() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "moves[*].damage:sum"), "16");
+    assert_eq!(run(&mut value, "moves[*].damage:max"), "12");
+    assert_eq!(run(&mut value, "moves[*].damage:mean"), "8.0");
+    assert_eq!(run(&mut value, "moves[*]:count"), "2");
+    assert_eq!(run(&mut value, "tags[*]:count"), "2");
+}
+
+#[test]
+fn json_get() {
+    let mut value = json!({ "foo": [1, 2] });
+    let output =
+r#"{
+  "foo": [
+    1,
+    2
+  ]
+}"#;
+    assert_eq!(run(&mut value, ":get"), output);
+}
+
+#[test]
+fn json_set() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "weight:set 80"), "");
+    assert_eq!(value["weight"], json!(80));
+
+    assert_eq!(run(&mut value, "tags:set [\\\"heavy\\\"]"), "");
+    assert_eq!(value["tags"], json!(["heavy"]));
+
+    assert_eq!(run(&mut value, "nothing:set {\\\"foo\\\":true}"), "");
+    assert_eq!(value["nothing"], json!({ "foo": true }));
+
+    // strings can be set without quotes
+    assert_eq!(run(&mut value, "name:set Falco"), "");
+    assert_eq!(value["name"], json!("Falco"));
+    assert_eq!(run(&mut value, "name:set \\\"Marth\\\""), "");
+    assert_eq!(value["name"], json!("Marth"));
+
+    assert_eq!(run(&mut value, "weight:set heavy"), "json number set error: expected value at line 1 column 1");
+    assert_eq!(value["weight"], json!(80));
+}
+
+#[test]
+fn json_kind() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, ":kind"), "object");
+    assert_eq!(run(&mut value, "name:kind"), "string");
+    assert_eq!(run(&mut value, "weight:kind"), "number");
+    assert_eq!(run(&mut value, "grounded:kind"), "bool");
+    assert_eq!(run(&mut value, "tags:kind"), "array");
+    assert_eq!(run(&mut value, "nothing:kind"), "null");
+}
+
+#[test]
+fn json_type() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, ":type"), "serde_json::value::Value");
+    assert_eq!(run(&mut value, "name:type"), "serde_json::value::Value");
+}
+
+#[test]
+fn json_getkeys() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, ":getkeys"), "'gravity', 'grounded', 'moves', 'name', 'nothing', 'tags', 'weight'");
+    assert_eq!(run(&mut value, "tags:getkeys"), "json array cannot 'GetKeys'");
+}
+
+#[test]
+fn json_insert_array() {
+    let mut value = json!([1, 2]);
+    assert_eq!(run(&mut value, ":insert"), "");
+    assert_eq!(value, json!([1, 2, null]));
+
+    assert_eq!(run(&mut value, ":insert 0"), "");
+    assert_eq!(value, json!([null, 1, 2, null]));
+
+    assert_eq!(run(&mut value, ":insert 5"), "Tried to insert at index 5 on a json array of size 4 (try a value between 0-4)");
+    assert_eq!(value, json!([null, 1, 2, null]));
+}
+
+#[test]
+fn json_insert_object() {
+    let mut value = json!({ "foo": 1 });
+    assert_eq!(run(&mut value, ":insert bar"), "");
+    assert_eq!(value, json!({ "foo": 1, "bar": null }));
+
+    assert_eq!(run(&mut value, ":insert foo"), "Tried to insert key 'foo' on a json object that already contains it. Current keys: 'bar', 'foo'");
+    assert_eq!(value, json!({ "foo": 1, "bar": null }));
+
+    assert_eq!(run(&mut value, ":insert"), "json object cannot 'Insert'");
+
+    // keys that are also JSON values or indexes are still keys on an object
+    assert_eq!(run(&mut value, ":insert 5"), "");
+    assert_eq!(run(&mut value, ":insert true"), "");
+    assert_eq!(run(&mut value, ":insert 6 [1]"), "");
+    assert_eq!(value, json!({ "foo": 1, "bar": null, "5": null, "true": null, "6": [1] }));
+}
+
+#[test]
+fn json_insert_value() {
+    let mut value = json!([1, 2]);
+    assert_eq!(run(&mut value, r#":insert 1 {\"a\": true}"#), "");
+    assert_eq!(value, json!([1, { "a": true }, 2]));
+    assert_eq!(run(&mut value, ":push [3, 4]"), "");
+    assert_eq!(value, json!([1, { "a": true }, 2, [3, 4]]));
+    assert_eq!(run(&mut value, ":insert 9 5"), "Tried to insert at index 9 on a json array of size 4 (try a value between 0-4)");
+    assert_eq!(run(&mut value, ":push [3,"), "json array push error: EOF while parsing a value at line 1 column 3");
+
+    let mut value = json!({ "foo": 1 });
+    assert_eq!(run(&mut value, ":insert bar [null, 2.5]"), "");
+    assert_eq!(value, json!({ "foo": 1, "bar": [null, 2.5] }));
+    assert_eq!(run(&mut value, ":insert foo 2"), "Tried to insert key 'foo' on a json object that already contains it. Current keys: 'bar', 'foo'");
+    assert_eq!(run(&mut value, ":push 2"), "json object cannot 'Push(\"2\")'");
+}
+
+#[test]
+fn json_remove_array() {
+    let mut value = json!([1, 2, 3]);
+    assert_eq!(run(&mut value, ":remove"), "");
+    assert_eq!(value, json!([1, 2]));
+
+    assert_eq!(run(&mut value, ":remove 0"), "");
+    assert_eq!(value, json!([2]));
+
+    assert_eq!(run(&mut value, ":remove 1"), "Tried to remove the value at index 1 on a json array of size 1 (try a value between 0-0)");
+
+    assert_eq!(run(&mut value, ":remove"), "");
+    assert_eq!(run(&mut value, ":remove"), "Tried to remove from an empty json array.");
+    assert_eq!(run(&mut value, ":remove 0"), "Tried to remove the value at index 0 on an empty json array");
+}
+
+#[test]
+fn json_remove_object() {
+    let mut value = json!({ "foo": 1, "bar": 2 });
+    assert_eq!(run(&mut value, ":remove foo"), "");
+    assert_eq!(value, json!({ "bar": 2 }));
+
+    assert_eq!(run(&mut value, ":remove foo"), "Tried to remove key 'foo' on a json object that doesnt contain it. Current keys: 'bar'");
+    assert_eq!(value, json!({ "bar": 2 }));
+}
+
+#[test]
+fn json_reset() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "moves:reset"), "");
+    assert_eq!(value["moves"], Value::Null);
+}
+
+#[test]
+fn json_int_arithmetic() {
+    let mut value = json!(10);
+    assert_eq!(run(&mut value, ":add 5"), "");
+    assert_eq!(value, json!(15));
+    assert_eq!(run(&mut value, ":subtract 20"), "");
+    assert_eq!(value, json!(-5));
+    assert_eq!(run(&mut value, ":multiply 3"), "");
+    assert_eq!(value, json!(-15));
+    assert_eq!(run(&mut value, ":divide 2"), "");
+    assert_eq!(value, json!(-7));
+    assert_eq!(run(&mut value, ":divide 0"), "Invalid value for json number (needs to be: A number, excluding 0)");
+    assert_eq!(value, json!(-7));
+}
+
+#[test]
+fn json_float_arithmetic() {
+    let mut value = json!(10);
+    assert_eq!(run(&mut value, ":add 0.5"), "");
+    assert_eq!(value, json!(10.5));
+    assert_eq!(run(&mut value, ":multiply 2"), "");
+    assert_eq!(value, json!(21.0));
+    assert_eq!(run(&mut value, ":divide 0.0"), "json number 21 divide 0 does not result in a valid json number");
+    assert_eq!(run(&mut value, ":add"), "No value for json number (needs to be: A number)");
+    assert_eq!(run(&mut value, ":add foo"), "Invalid value for json number (needs to be: A number)");
+    assert_eq!(value, json!(21.0));
+}
+
+#[test]
+fn json_cannot() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "name:add 1"), "json string cannot 'add'");
+    assert_eq!(run(&mut value, "name:foo"), "json string cannot 'foo'");
+    assert_eq!(run(&mut value, "name:copy"), "json string cannot 'CopyFrom'");
+
+    let runner = NodeRunner { tokens: vec!(NodeToken::ChainContext) };
+    assert_eq!(value.node_step(runner), "json object cannot 'ChainContext'");
+}
+
+#[test]
+fn json_exists() {
+    let mut value = test_json();
+    assert_eq!(run(&mut value, "moves[\"dair\"].damage:exists"), "true");
+    assert_eq!(run(&mut value, "moves[\"nair\"].damage:exists"), "false");
+    assert_eq!(run(&mut value, "tags[1]:exists"), "true");
+    assert_eq!(run(&mut value, "tags[2]:exists"), "false");
+    assert_eq!(run(&mut value, "name.first:exists"), "false");
+    assert_eq!(run(&mut value, "name[0]:exists"), "false");
+    assert_eq!(value, test_json());
+}
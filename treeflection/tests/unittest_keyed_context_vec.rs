@@ -1,6 +1,9 @@
 extern crate treeflection;
 #[macro_use] extern crate matches;
 
+mod common;
+
+use common::assert_fan_out;
 use treeflection::{Node, NodeRunner, NodeToken, KeyedContextVec};
 
 fn test_vec4() -> KeyedContextVec<i32> {
@@ -396,16 +399,22 @@ fn node_step_help() {
 Keyed Context Vector Help
 
 Commands:
-*   help               - display this help
-*   get                - display JSON
-*   getkeys            - display keys
-*   set                - set to JSON
-*   insert $KEY        - create a new element at the end of the vector with $KEY
-*   insert $INDEX $KEY - create a new element at $INDEX with $KEY
-*   remove             - remove the element
-*   remove $KEY        - remove the element with $KEY
-*   remove $INDEX      - remove the element at $INDEX
-*   reset              - reset to empty vector
+*   help                  - display this help
+*   get                   - display JSON
+*   getkeys               - display keys
+*   set                   - set to JSON
+*   edit                  - edit JSON in your editor
+*   type                  - display the Rust type
+*   insert $KEY           - create a new element at the end of the vector with $KEY
+*   insert $INDEX $KEY    - create a new element at $INDEX with $KEY
+*   insert $KEY $JSON     - insert $JSON at the end of the vector with $KEY
+*   rename $KEY $NEW_KEY  - change $KEY to $NEW_KEY
+*   copykey $KEY $NEW_KEY - insert a copy of the element with $KEY at the end of the vector with $NEW_KEY
+*   movekey $KEY $INDEX   - move the element with $KEY to $INDEX
+*   remove                - remove the element
+*   remove $KEY           - remove the element with $KEY
+*   remove $INDEX         - remove the element at $INDEX
+*   reset                 - reset to empty vector
 
 Accessors:
 *   [INDEX] - access item at INDEX
@@ -422,22 +431,22 @@ fn node_step_chain_context() {
         NodeToken::ChainContext
     )};
 
-    assert_eq!("|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context(0);
-    assert_eq!("|10|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[\"foo\"]", "10")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context(1);
-    assert_eq!("|1337|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[\"bar\"]", "1337")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context(2);
-    assert_eq!("|42|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[\"baz\"]", "42")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context_vec(vec!(2, 0));
-    assert_eq!("|42|10|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[\"baz\"]", "42"), ("[\"foo\"]", "10")], &context_vec.node_step(runner.clone()));
 
     context_vec.set_context_vec(vec!(0, 1, 2));
-    assert_eq!("|10|1337|42|", context_vec.node_step(runner.clone()).as_str());
+    assert_fan_out(&[("[\"foo\"]", "10"), ("[\"bar\"]", "1337"), ("[\"baz\"]", "42")], &context_vec.node_step(runner.clone()));
 }
 
 #[test]
@@ -446,7 +455,7 @@ fn node_step_chain_all() {
         NodeToken::Get,
         NodeToken::ChainAll,
     )};
-    assert_eq!("|100000|13|-358|42|", test_vec4().node_step(runner));
+    assert_fan_out(&[("[\"foo\"]", "100000"), ("[\"bar\"]", "13"), ("[\"baz\"]", "-358"), ("[\"qux\"]", "42")], &test_vec4().node_step(runner));
 }
 
 #[test]
@@ -571,6 +580,96 @@ fn node_step_insert_key() {
     assert_eq!(some_vec[5], 0);
 }
 
+#[test]
+fn node_step_insert_key_value() {
+    let mut some_vec = test_vec4();
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert new 7").unwrap()), "");
+    assert_eq!(some_vec.len(), 5);
+    assert_eq!(some_vec[4], 7);
+    assert_eq!(some_vec.key_to_index("new"), Some(4));
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert foo 8").unwrap()), "Tried to insert with key 'foo' on a keyed context vector that already contains it. Current keys: 'foo', 'bar', 'baz', 'qux', 'new'");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert other [8]").unwrap()), "keyed context vector insert error: invalid type: sequence, expected i32 at line 1 column 0");
+    assert_eq!(some_vec.len(), 5);
+}
+
+#[test]
+fn node_step_insert_json_keys() {
+    // keys that are also JSON values are still keys, a number on its own is a key as there is no index without a key
+    let mut some_vec = test_vec1();
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert 0 123").unwrap()), "");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert 0 true").unwrap()), "");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert null").unwrap()), "");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert 42").unwrap()), "");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert false 5").unwrap()), "");
+    assert_eq!(some_vec.keys(), vec!("true", "123", "foo", "null", "42", "false"));
+    assert_eq!(some_vec[5], 5);
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert 1 42").unwrap()), "Tried to insert with key '42' on a keyed context vector that already contains it. Current keys: 'true', '123', 'foo', 'null', '42', 'false'");
+}
+
+#[test]
+fn node_step_rename() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(0, 2));
+    assert_eq!(some_vec.node_step(NodeRunner::new(":rename foo quux").unwrap()), "");
+    assert_eq!(some_vec.keys(), vec!("quux", "bar", "baz", "qux"));
+    assert_eq!(*some_vec, [100000, 13, -358, 42]);
+    assert_eq!(some_vec.get_context(), &[0, 2]);
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":rename foo corge").unwrap()), "Tried to rename key 'foo' on a keyed context vector that doesnt contain it. Current keys: 'quux', 'bar', 'baz', 'qux'");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":rename bar baz").unwrap()), "Tried to rename key 'bar' to 'baz' on a keyed context vector that already contains it. Current keys: 'quux', 'bar', 'baz', 'qux'");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":rename bar bar").unwrap()), "");
+    assert_eq!(some_vec.keys(), vec!("quux", "bar", "baz", "qux"));
+}
+
+#[test]
+fn node_step_copykey() {
+    let mut some_vec = test_vec2();
+    some_vec.set_context(1);
+    assert_eq!(some_vec.node_step(NodeRunner::new(":copykey foo new").unwrap()), "");
+    assert_eq!(some_vec.keys(), vec!("foo", "bar", "new"));
+    assert_eq!(*some_vec, [10, 1337, 10]);
+    assert_eq!(some_vec.selection(), vec!(&1337));
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":copykey foo bar").unwrap()), "Tried to copy key 'foo' to 'bar' on a keyed context vector that already contains it. Current keys: 'foo', 'bar', 'new'");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":copykey").unwrap()), "Missing value (needs to be: a key followed by a new key)");
+}
+
+#[test]
+fn node_step_movekey() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(0, 1, 3));
+    assert_eq!(some_vec.node_step(NodeRunner::new(":movekey foo 2").unwrap()), "");
+    assert_eq!(some_vec.keys(), vec!("bar", "baz", "foo", "qux"));
+    assert_eq!(*some_vec, [13, -358, 100000, 42]);
+    assert_eq!(some_vec.selection(), vec!(&100000, &13, &42));
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":movekey qux 0").unwrap()), "");
+    assert_eq!(some_vec.keys(), vec!("qux", "bar", "baz", "foo"));
+    assert_eq!(some_vec.selection(), vec!(&100000, &13, &42));
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":movekey qux 4").unwrap()), "Tried to move key 'qux' to index 4 on a keyed context vector of size 4 (try a value between 0-3)");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":movekey corge 0").unwrap()), "Tried to move key 'corge' on a keyed context vector that doesnt contain it. Current keys: 'qux', 'bar', 'baz', 'foo'");
+    assert_eq!(some_vec.node_step(NodeRunner::new(":movekey qux last").unwrap()), "Missing value (needs to be: a key followed by an index)");
+}
+
+#[test]
+fn move_key() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(2));
+    some_vec.move_key("baz", 0);
+    assert_eq!(some_vec.keys(), vec!("baz", "foo", "bar", "qux"));
+    assert_eq!(some_vec.get_context(), &[0]);
+}
+
+#[test]
+#[should_panic]
+fn rename_key_used() {
+    let mut some_vec = test_vec4();
+    some_vec.rename_key("foo", String::from("bar"));
+}
+
 #[test]
 fn node_step_insert_index_key() {
     let mut some_vec = test_vec4();
@@ -745,3 +844,22 @@ fn node_step_set_fail()
     let runner = NodeRunner { tokens: vec!(NodeToken::Set(String::from("[1, lol]"))) };
     assert_eq!(some_vec.node_step(runner), String::from("keyed context vector set error: expected value at line 1 column 5"));
 }
+
+#[test]
+fn node_step_set_keys() {
+    let mut some_vec = test_vec4();
+    some_vec.set_context_vec(vec!(3, 1));
+    assert_eq!(some_vec.node_step(NodeRunner::new(":set [1, 2]").unwrap()), "keyed context vector set error: Expected 4 values, one for each key, but got 2 (use insert and remove to change the keys)");
+    assert_eq!(*some_vec, [100000, 13, -358, 42]);
+    assert_eq!(some_vec.get_context(), &[3, 1]);
+
+    assert_eq!(some_vec.node_step(NodeRunner::new(":set compact [1,2,3,4]").unwrap()), "");
+    assert_eq!(some_vec.key_value_iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(), vec!(("foo", 1), ("bar", 2), ("baz", 3), ("qux", 4)));
+    assert!(some_vec.get_context().is_empty());
+
+    some_vec.set_context(2);
+    assert_eq!(some_vec.node_step(NodeRunner::new(":reset").unwrap()), "");
+    assert!(some_vec.keys().is_empty());
+    assert!(some_vec.get_context().is_empty());
+    assert_eq!(some_vec.node_step(NodeRunner::new(":insert foo").unwrap()), "");
+}
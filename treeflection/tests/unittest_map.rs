@@ -1,7 +1,10 @@
 extern crate treeflection;
 
+mod common;
+
 use std::collections::HashMap;
 
+use common::assert_fan_out;
 use treeflection::{Node, NodeRunner, NodeToken};
 
 fn test_map4() -> HashMap<String, i32> {
@@ -29,7 +32,7 @@ fn map_chain_all() {
         NodeToken::Get,
         NodeToken::ChainAll,
     )};
-    assert_eq!("|13|-358|100000|42|", test_map4().node_step(runner));
+    assert_fan_out(&[("[\"bar\"]", "13"), ("[\"baz\"]", "-358"), ("[\"foo\"]", "100000"), ("[\"qux\"]", "42")], &test_map4().node_step(runner));
 }
 
 #[test]
@@ -99,6 +102,68 @@ fn map_insert() {
     assert_eq!(*some_map.get("quux").unwrap(), 0);
 }
 
+#[test]
+fn map_insert_value() {
+    let mut some_map = test_map1();
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert bar 7").unwrap()), "");
+    assert_eq!(some_map.len(), 2);
+    assert_eq!(*some_map.get("bar").unwrap(), 7);
+
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert foo 8").unwrap()), "Tried to insert key 'foo' on a map that already contains it. Current keys: 'bar', 'foo'");
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert baz eight").unwrap()), "map insert error: expected value at line 1 column 1");
+    assert_eq!(some_map.len(), 2);
+    assert_eq!(*some_map.get("foo").unwrap(), 13);
+}
+
+#[test]
+fn map_insert_json_keys() {
+    // keys that are also JSON values or indexes are still keys on a map
+    let mut some_map = test_map1();
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert 42").unwrap()), "");
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert true").unwrap()), "");
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert null").unwrap()), "");
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert 7 3").unwrap()), "");
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert false 4").unwrap()), "");
+    assert_eq!(some_map.len(), 6);
+    assert_eq!(*some_map.get("42").unwrap(), 0);
+    assert_eq!(*some_map.get("true").unwrap(), 0);
+    assert_eq!(*some_map.get("null").unwrap(), 0);
+    assert_eq!(*some_map.get("7").unwrap(), 3);
+    assert_eq!(*some_map.get("false").unwrap(), 4);
+
+    assert_eq!(some_map.node_step(NodeRunner::new(":insert 42").unwrap()), "Tried to insert key '42' on a map that already contains it. Current keys: '42', '7', 'false', 'foo', 'null', 'true'");
+}
+
+#[test]
+fn map_rename() {
+    let mut some_map = test_map4();
+    assert_eq!(some_map.node_step(NodeRunner::new(":rename foo quux").unwrap()), "");
+    assert_eq!(some_map.len(), 4);
+    assert_eq!(some_map.get("foo"), None);
+    assert_eq!(some_map["quux"], 100000);
+
+    assert_eq!(some_map.node_step(NodeRunner::new(":rename foo corge").unwrap()), "Tried to rename key 'foo' on a map that doesnt contain it. Current keys: 'bar', 'baz', 'quux', 'qux'");
+    assert_eq!(some_map.node_step(NodeRunner::new(":rename bar baz").unwrap()), "Tried to rename key 'bar' to 'baz' on a map that already contains it. Current keys: 'bar', 'baz', 'quux', 'qux'");
+    assert_eq!(some_map.node_step(NodeRunner::new(":rename bar bar").unwrap()), "");
+    assert_eq!(some_map.node_step(NodeRunner::new(":rename bar").unwrap()), "Missing value (needs to be: a key followed by a new key)");
+    assert_eq!(some_map["bar"], 13);
+    assert_eq!(some_map["baz"], -358);
+}
+
+#[test]
+fn map_copykey() {
+    let mut some_map = test_map1();
+    assert_eq!(some_map.node_step(NodeRunner::new(":copykey foo bar").unwrap()), "");
+    assert_eq!(some_map.len(), 2);
+    assert_eq!(some_map["foo"], 13);
+    assert_eq!(some_map["bar"], 13);
+
+    assert_eq!(some_map.node_step(NodeRunner::new(":copykey foo bar").unwrap()), "Tried to copy key 'foo' to 'bar' on a map that already contains it. Current keys: 'bar', 'foo'");
+    assert_eq!(some_map.node_step(NodeRunner::new(":copykey baz qux").unwrap()), "Tried to copy key 'baz' on a map that doesnt contain it. Current keys: 'bar', 'foo'");
+    assert_eq!(some_map.node_step(NodeRunner::new(":movekey foo 0").unwrap()), "map cannot 'movekey'");
+    assert_eq!(some_map.len(), 2);
+}
+
 #[test]
 fn map_remove() {
     let mut some_map = test_map4();
@@ -171,13 +236,18 @@ fn map_help() {
 Map Help
 
 Commands:
-*   help    - display this help
-*   get     - display JSON
-*   getkeys - display keys
-*   set     - set to JSON
-*   insert  - create a new element
-*   remove  - remove an element
-*   reset   - reset to empty map
+*   help                  - display this help
+*   get                   - display JSON
+*   getkeys               - display keys
+*   set                   - set to JSON
+*   edit                  - edit JSON in your editor
+*   type                  - display the Rust type
+*   insert                - create a new element
+*   insert $KEY $JSON     - insert $JSON with $KEY
+*   rename $KEY $NEW_KEY  - change $KEY to $NEW_KEY
+*   copykey $KEY $NEW_KEY - insert a copy of the element with $KEY with $NEW_KEY
+*   remove                - remove an element
+*   reset                 - reset to empty map
 
 Accessors:
 *   [key]   - access item at the string key
@@ -186,3 +256,11 @@ Accessors:
     let runner = NodeRunner { tokens: vec!(NodeToken::Help) };
     assert_eq!(some_map.node_step(runner), String::from(output));
 }
+
+#[test]
+fn map_exists() {
+    let mut some_map = test_map4();
+    assert_eq!(some_map.node_step(NodeRunner::new("[\"foo\"]:exists").unwrap()), "true");
+    assert_eq!(some_map.node_step(NodeRunner::new("[\"quux\"]:exists").unwrap()), "false");
+    assert_eq!(some_map.node_step(NodeRunner::new("[0]:exists").unwrap()), "false");
+}
@@ -311,6 +311,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -329,6 +331,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -347,6 +351,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -365,6 +371,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -383,6 +391,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -401,6 +411,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -419,6 +431,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -437,6 +451,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -475,6 +491,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -493,6 +511,8 @@ Commands:
 *   paste            - paste the copied value here
 *   get              - display value
 *   set      $NUMBER - set to $NUMBER
+*   edit             - edit value in your editor
+*   type             - display the Rust type
 *   add      $NUMBER - adds $NUMBER to this number
 *   subtract $NUMBER - subtracts $NUMBER from this number
 *   multiply $NUMBER - multiply this number with $NUMBER
@@ -51,7 +51,9 @@ Commands:
 *   copy  - copy this value
 *   paste - paste the copied value here
 *   get   - display value
-*   set   - set to value"#;
+*   set   - set to value
+*   edit  - edit value in your editor
+*   type  - display the Rust type"#;
     let mut value = String::from("YO");
     let runner = NodeRunner { tokens: vec!( NodeToken::Help ) };
     assert_eq!(value.node_step(runner).as_str(), output);
@@ -81,7 +83,9 @@ Valid values: true or false
 Commands:
 *   help - display this help
 *   get  - display value
-*   set  - set to value"#;
+*   set  - set to value
+*   edit - edit value in your editor
+*   type - display the Rust type"#;
     let mut value = true;
     let runner = NodeRunner { tokens: vec!( NodeToken::Help ) };
     assert_eq!(value.node_step(runner).as_str(), output);
@@ -0,0 +1,203 @@
+#![cfg(feature = "server")]
+
+extern crate treeflection;
+#[macro_use] extern crate serde_json;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::thread;
+
+use serde_json::Value;
+use treeflection::CommandQueue;
+use treeflection::server::Server;
+
+fn test_json() -> Value {
+    json!({
+        "fighter": {
+            "name": "Fox",
+            "weight": 75
+        }
+    })
+}
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn connect(server: &Server) -> Client {
+        let writer = TcpStream::connect(server.local_addr()).unwrap();
+        Client {
+            reader: BufReader::new(writer.try_clone().unwrap()),
+            writer,
+        }
+    }
+
+    fn send(&mut self, line: &str) {
+        self.writer.write_all(line.as_bytes()).unwrap();
+        self.writer.write_all(b"\n").unwrap();
+    }
+
+    fn read_line(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line
+    }
+
+    /// Read a plain command response
+    fn read_frame(&mut self) -> (String, String) {
+        let header = self.read_line();
+        let mut header = header.trim_end().split(' ');
+        let status = header.next().unwrap().to_string();
+        let length: usize = header.next().unwrap().parse().unwrap();
+
+        let mut result = vec!(0; length + 1);
+        self.reader.read_exact(&mut result).unwrap();
+        assert_eq!(result.pop(), Some(b'\n'));
+        (status, String::from_utf8(result).unwrap())
+    }
+}
+
+/// Runs the client on another thread while the current thread acts as the game thread
+fn run_client<F>(root: &mut Value, test: F) where F: FnOnce(Client) + Send + 'static {
+    let mut queue = CommandQueue::new();
+    let server = Server::bind("127.0.0.1:0", queue.handle()).unwrap();
+    let client = Client::connect(&server);
+    run_client_on(&mut queue, root, client, test);
+}
+
+fn run_client_on<F>(queue: &mut CommandQueue, root: &mut Value, client: Client, test: F) where F: FnOnce(Client) + Send + 'static {
+    let client_thread = thread::spawn(move || test(client));
+    while !client_thread.is_finished() {
+        queue.process(root);
+        thread::yield_now();
+    }
+    client_thread.join().unwrap();
+}
+
+#[test]
+fn server_plain() {
+    let mut root = test_json();
+    run_client(&mut root, |mut client| {
+        client.send("fighter.name:get");
+        assert_eq!(client.read_frame(), (String::from("OK"), String::from("\"Fox\"")));
+
+        client.send("fighter.weight:set 80");
+        assert_eq!(client.read_frame(), (String::from("OK"), String::new()));
+
+        client.send("fighter.weight:get");
+        assert_eq!(client.read_frame(), (String::from("OK"), String::from("80")));
+
+        client.send("fighter.weight");
+        assert_eq!(client.read_frame(), (String::from("ERR"), String::from("Missing action")));
+
+        client.send("fighter:get");
+        assert_eq!(client.read_frame(), (String::from("OK"), String::from("{\n  \"name\": \"Fox\",\n  \"weight\": 80\n}")));
+    });
+    assert_eq!(root["fighter"]["weight"], json!(80));
+}
+
+#[test]
+fn server_json_rpc() {
+    let mut root = test_json();
+    run_client(&mut root, |mut client| {
+        client.send(r#"{"jsonrpc": "2.0", "id": 1, "method": "run", "params": ["fighter.name:get"]}"#);
+        assert_eq!(client.read_line(), "{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":\"\\\"Fox\\\"\"}\n");
+
+        client.send(r#"{"jsonrpc": "2.0", "id": "a", "method": "run", "params": {"command": "fighter.name:set Falco"}}"#);
+        assert_eq!(client.read_line(), "{\"id\":\"a\",\"jsonrpc\":\"2.0\",\"result\":\"\"}\n");
+
+        // notifications are run but not answered
+        client.send(r#"{"jsonrpc": "2.0", "method": "run", "params": ["fighter.weight:add 5"]}"#);
+
+        client.send(r#"{"jsonrpc": "2.0", "id": 2, "method": "run", "params": ["fighter.weight"]}"#);
+        assert_eq!(client.read_line(), "{\"error\":{\"code\":-32602,\"message\":\"Missing action\"},\"id\":2,\"jsonrpc\":\"2.0\"}\n");
+    });
+    assert_eq!(root["fighter"]["name"], json!("Falco"));
+    assert_eq!(root["fighter"]["weight"], json!(80));
+}
+
+#[test]
+fn server_json_rpc_errors() {
+    let mut root = test_json();
+    run_client(&mut root, |mut client| {
+        client.send(r#"{"jsonrpc": "2.0", "id": 1, "method": "run""#);
+        let response: Value = serde_json::from_str(&client.read_line()).unwrap();
+        assert_eq!(response["error"]["code"], json!(-32700));
+        assert_eq!(response["id"], Value::Null);
+
+        client.send(r#"{"id": 2, "method": "run", "params": ["fighter:get"]}"#);
+        assert_eq!(client.read_line(), "{\"error\":{\"code\":-32600,\"message\":\"Invalid request: jsonrpc must be \\\"2.0\\\"\"},\"id\":2,\"jsonrpc\":\"2.0\"}\n");
+
+        client.send(r#"{"jsonrpc": "2.0", "id": 3, "method": "walk", "params": ["fighter:get"]}"#);
+        assert_eq!(client.read_line(), "{\"error\":{\"code\":-32601,\"message\":\"Method not found: walk\"},\"id\":3,\"jsonrpc\":\"2.0\"}\n");
+
+        client.send(r#"{"jsonrpc": "2.0", "id": 4, "method": "run", "params": [1, 2]}"#);
+        let response: Value = serde_json::from_str(&client.read_line()).unwrap();
+        assert_eq!(response["error"]["code"], json!(-32602));
+        assert_eq!(response["id"], json!(4));
+
+        client.send(r#"{"jsonrpc": "2.0", "id": 5, "method": "run", "params": ["if fighter.name:set Falco == \"\" then fighter:get"]}"#);
+        let response: Value = serde_json::from_str(&client.read_line()).unwrap();
+        assert_eq!(response["error"]["code"], json!(-32602));
+        assert_eq!(response["error"]["message"], json!("The condition 'fighter.name:set Falco' must not modify anything (try an action like get)"));
+    });
+}
+
+#[test]
+fn server_multiple_clients() {
+    let mut root = test_json();
+    let mut queue = CommandQueue::new();
+    let server = Server::bind("127.0.0.1:0", queue.handle()).unwrap();
+    let clients: Vec<_> = (0..4).map(|_| Client::connect(&server)).collect();
+    let threads: Vec<_> = clients.into_iter().map(|mut client| {
+        thread::spawn(move || {
+            for _ in 0..10 {
+                client.send("fighter.weight:add 1");
+                assert_eq!(client.read_frame(), (String::from("OK"), String::new()));
+            }
+        })
+    }).collect();
+
+    while !threads.iter().all(|x| x.is_finished()) {
+        queue.process(&mut root);
+        thread::yield_now();
+    }
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(root["fighter"]["weight"], json!(115));
+}
+
+#[test]
+fn server_dropped() {
+    let mut root = test_json();
+    let mut queue = CommandQueue::new();
+    let server = Server::bind("127.0.0.1:0", queue.handle()).unwrap();
+    let client = Client::connect(&server);
+    drop(server);
+
+    // existing connections outlive the server
+    run_client_on(&mut queue, &mut root, client, |mut client| {
+        client.send("fighter.name:get");
+        assert_eq!(client.read_frame(), (String::from("OK"), String::from("\"Fox\"")));
+    });
+}
+
+#[test]
+fn server_queue_dropped() {
+    let queue = CommandQueue::new();
+    let server = Server::bind("127.0.0.1:0", queue.handle()).unwrap();
+    let mut client = Client::connect(&server);
+    drop(queue);
+
+    client.send("fighter.name:get");
+    assert_eq!(client.read_frame(), (String::from("ERR"), String::from("The command queue was dropped before running the command")));
+
+    // the queue failing is an internal error, unlike a command that can't be parsed
+    client.send(r#"{"jsonrpc": "2.0", "id": 1, "method": "run", "params": ["fighter.name:get"]}"#);
+    assert_eq!(client.read_line(), "{\"error\":{\"code\":-32603,\"message\":\"The command queue was dropped before running the command\"},\"id\":1,\"jsonrpc\":\"2.0\"}\n");
+    client.send(r#"{"jsonrpc": "2.0", "id": 2, "method": "run", "params": ["fighter.name"]}"#);
+    assert_eq!(client.read_line(), "{\"error\":{\"code\":-32602,\"message\":\"Missing action\"},\"id\":2,\"jsonrpc\":\"2.0\"}\n");
+}
@@ -0,0 +1,351 @@
+extern crate treeflection;
+#[macro_use] extern crate serde_json;
+
+use serde_json::Value;
+use std::collections::HashMap;
+use treeflection::{NodeToken, Session};
+
+fn test_json() -> Value {
+    json!({
+        "players": {
+            "p1": {
+                "fighter": {
+                    "name": "Fox",
+                    "hitboxes": [
+                        { "damage": 4 },
+                        { "damage": 12 }
+                    ]
+                }
+            },
+            "p2": {
+                "fighter": {
+                    "name": "Falco",
+                    "hitboxes": []
+                }
+            }
+        },
+        "stage": "Battlefield"
+    })
+}
+
+#[test]
+fn session_root() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.pwd(), "/");
+    assert_eq!(session.run(&mut root, "stage:get"), Ok(String::from("\"Battlefield\"")));
+    assert_eq!(session.run(&mut root, "players[\"p1\"].fighter.name:get"), Ok(String::from("\"Fox\"")));
+}
+
+#[test]
+fn session_cd() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "cd players[\"p1\"].fighter"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/players[\"p1\"].fighter");
+    assert_eq!(session.path(), &[
+        NodeToken::ChainProperty(String::from("players")),
+        NodeToken::ChainKey(String::from("p1")),
+        NodeToken::ChainProperty(String::from("fighter")),
+    ]);
+
+    assert_eq!(session.run(&mut root, "name:get"), Ok(String::from("\"Fox\"")));
+    assert_eq!(session.run(&mut root, "hitboxes[1].damage:get"), Ok(String::from("12")));
+    assert_eq!(session.run(&mut root, "hitboxes[0].damage:set 5"), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"]["hitboxes"][0]["damage"], json!(5));
+
+    assert_eq!(session.run(&mut root, "cd hitboxes[0]"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/players[\"p1\"].fighter.hitboxes[0]");
+    assert_eq!(session.run(&mut root, "damage:get"), Ok(String::from("5")));
+
+    assert_eq!(session.run(&mut root, "cd"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/");
+    assert_eq!(session.run(&mut root, "stage:get"), Ok(String::from("\"Battlefield\"")));
+}
+
+#[test]
+fn session_action_only() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    session.run(&mut root, "cd stage").unwrap();
+    assert_eq!(session.run(&mut root, ":get"), Ok(String::from("\"Battlefield\"")));
+    assert_eq!(session.run(&mut root, ":set FD"), Ok(String::new()));
+    assert_eq!(root["stage"], json!("FD"));
+}
+
+#[test]
+fn session_parent() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    session.run(&mut root, "cd players[\"p1\"].fighter.hitboxes").unwrap();
+
+    assert_eq!(session.run(&mut root, "..:kind"), Ok(String::from("object")));
+    assert_eq!(session.run(&mut root, "..name:get"), Ok(String::from("\"Fox\"")));
+    assert_eq!(session.run(&mut root, "../name:get"), Ok(String::from("\"Fox\"")));
+    assert_eq!(session.run(&mut root, "../..:getkeys"), Ok(String::from("'fighter'")));
+    assert_eq!(session.run(&mut root, "../../..[\"p2\"].fighter.name:get"), Ok(String::from("\"Falco\"")));
+
+    assert_eq!(session.run(&mut root, "cd ../.."), Ok(String::new()));
+    assert_eq!(session.pwd(), "/players[\"p1\"]");
+
+    assert_eq!(session.run(&mut root, "cd ..[\"p2\"]"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/players[\"p2\"]");
+
+    assert_eq!(session.run(&mut root, "cd ../../.."), Err(String::from("Cannot go above the root")));
+    assert_eq!(session.pwd(), "/players[\"p2\"]");
+}
+
+#[test]
+fn session_absolute() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    session.run(&mut root, "cd players[\"p1\"].fighter").unwrap();
+
+    assert_eq!(session.run(&mut root, "/stage:get"), Ok(String::from("\"Battlefield\"")));
+    assert_eq!(session.run(&mut root, "cd /players[\"p2\"]"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/players[\"p2\"]");
+    assert_eq!(session.run(&mut root, "cd /"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/");
+}
+
+#[test]
+fn session_cd_unreachable() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "cd players[\"p3\"]"), Err(String::from("Cannot cd to /players[\"p3\"]: Used key 'p3' on a json object that does not contain it. Try one of: 'p1', 'p2'")));
+    assert_eq!(session.pwd(), "/");
+
+    assert_eq!(session.run(&mut root, "cd players[*].fighter.hitboxes[0]"), Err(String::from("Cannot cd to /players[*].fighter.hitboxes[0]: [\"p2\"].fighter.hitboxes[0]: Used index 0 on an empty json array")));
+    assert_eq!(session.run(&mut root, "cd players[*].fighter.hitboxes[*].knockback"), Err(String::from("Cannot cd to /players[*].fighter.hitboxes[*].knockback: [\"p1\"].fighter.hitboxes: [0].knockback: json object does not have a property 'knockback'")));
+    assert_eq!(session.run(&mut root, "cd players[*].fighter.hitboxes"), Ok(String::new()));
+    assert_eq!(session.pwd(), "/players[*].fighter.hitboxes");
+}
+
+#[test]
+fn session_fan_out() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    session.run(&mut root, "cd players[*].fighter").unwrap();
+    assert_eq!(session.pwd(), "/players[*].fighter");
+    let output = r#"[
+  {
+    "path": "[\"p1\"].fighter.name",
+    "result": "\"Fox\""
+  },
+  {
+    "path": "[\"p2\"].fighter.name",
+    "result": "\"Falco\""
+  }
+]"#;
+    assert_eq!(session.run(&mut root, "name:get"), Ok(String::from(output)));
+}
+
+#[test]
+fn session_ls() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    let help = session.run(&mut root, "ls").unwrap();
+    assert!(help.starts_with("\nJSON Value Help"));
+    assert_eq!(session.run(&mut root, "ls players"), Ok(help.clone()));
+    session.run(&mut root, "cd players").unwrap();
+    assert_eq!(session.run(&mut root, "ls .."), Ok(help));
+}
+
+#[test]
+fn session_invalid() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "stage"), Err(String::from("Missing action")));
+    assert_eq!(session.run(&mut root, "cd stage[foo]"), Err(String::from("Invalid index: foo")));
+    assert_eq!(session.run(&mut root, "pwd foo"), Err(String::from("Missing action")));
+    assert_eq!(session.run(&mut root, ""), Ok(String::new()));
+    assert_eq!(session.pwd(), "/");
+}
+
+#[test]
+fn session_history() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "!!"), Err(String::from("History is empty")));
+
+    session.run(&mut root, "cd players").unwrap();
+    session.run(&mut root, "p1.fighter.name:get").unwrap();
+    assert_eq!(session.run(&mut root, "!!"), Ok(String::from("\"Fox\"")));
+    assert_eq!(session.run(&mut root, "!0"), Err(String::from("Cannot cd to /players.players: json object does not have a property 'players'")));
+    assert_eq!(session.pwd(), "/players");
+    assert_eq!(session.run(&mut root, "!9"), Err(String::from("!9 is not in the history")));
+
+    assert_eq!(session.history(), &[
+        String::from("cd players"),
+        String::from("p1.fighter.name:get"),
+        String::from("p1.fighter.name:get"),
+        String::from("cd players"),
+    ]);
+    assert_eq!(session.run(&mut root, "history"), Ok(String::from(
+"   0  cd players
+   1  p1.fighter.name:get
+   2  p1.fighter.name:get
+   3  cd players
+   4  history")));
+}
+
+#[test]
+fn session_help() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert!(session.run(&mut root, "help").unwrap().starts_with("\nSession Help"));
+}
+
+#[test]
+fn session_copyto() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "players[\"p1\"].fighter.hitboxes:copyto players[\"p2\"].fighter.hitboxes"), Ok(String::new()));
+    assert_eq!(root["players"]["p2"]["fighter"]["hitboxes"], json!([{ "damage": 4 }, { "damage": 12 }]));
+    assert_eq!(root["players"]["p1"]["fighter"]["hitboxes"], json!([{ "damage": 4 }, { "damage": 12 }]));
+
+    // both paths are relative to the current path
+    assert_eq!(session.run(&mut root, "cd players[\"p1\"].fighter"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "hitboxes[1]:copyto hitboxes[0]"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "/stage:copyto name"), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"], json!({ "name": "Battlefield", "hitboxes": [{ "damage": 12 }, { "damage": 12 }] }));
+
+    assert_eq!(session.run(&mut root, "name:copyto hitboxes[2]"), Ok(String::from("Cannot get /players[\"p1\"].fighter.hitboxes[2]: Used index 2 on a json array of size 2 (try a value between 0-1)")));
+    assert_eq!(session.run(&mut root, "hitboxes[*]:copyto name"), Err(String::from("copyto cannot be used with [*] or [?]")));
+
+    // the action is found by parsing the path, so it can appear in a key
+    let mut root = json!({ "a:copyto b": 1, "b": 2 });
+    assert_eq!(session.run(&mut root, "/[\"a:copyto b\"]:copyto /b"), Ok(String::new()));
+    assert_eq!(root, json!({ "a:copyto b": 1, "b": 1 }));
+}
+
+#[test]
+fn session_moveto() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "players[\"p1\"].fighter.hitboxes[0]:moveto players[\"p1\"].fighter.hitboxes[1]"), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"]["hitboxes"], json!([{ "damage": 4 }]));
+
+    assert_eq!(session.run(&mut root, "stage:moveto players[\"p2\"].fighter.name"), Ok(String::new()));
+    assert_eq!(root["players"]["p2"]["fighter"]["name"], json!("Battlefield"));
+    assert!(root.get("stage").is_none());
+
+    assert_eq!(session.run(&mut root, "players:moveto players[\"p1\"]"), Ok(String::from("Cannot move /players to /players[\"p1\"] as one contains the other")));
+    assert_eq!(session.run(&mut root, "players[\"p1\"]:moveto players"), Ok(String::from("Cannot move /players[\"p1\"] to /players as one contains the other")));
+    assert_eq!(session.run(&mut root, ":moveto players"), Ok(String::from("Cannot move / to /players as one contains the other")));
+}
+
+#[test]
+fn session_moveto_unremovable() {
+    // tuples can't remove fields, so the target is never written
+    let mut root: (u32, u32) = (1, 2);
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "[0]:moveto [1]"), Ok(String::from("Cannot move /[0] as it can't be removed: (T0, T1,) cannot 'CheckRemoveIndex(0)'")));
+    assert_eq!(root, (1, 2));
+
+    // a map removes its values by key but has no properties
+    let mut root: HashMap<String, Vec<u32>> = HashMap::new();
+    root.insert(String::from("a"), vec!(1));
+    root.insert(String::from("b"), vec!(2, 3));
+    assert_eq!(session.run(&mut root, "[\"b\"][1]:moveto [\"a\"][0]"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "[\"b\"]:moveto [\"a\"]"), Ok(String::new()));
+    assert_eq!(root.get("a"), Some(&vec!(2)));
+    assert!(!root.contains_key("b"));
+}
+
+#[test]
+fn session_swapwith() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "cd players"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "[\"p1\"].fighter.name:swapwith [\"p2\"].fighter.name"), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"]["name"], json!("Falco"));
+    assert_eq!(root["players"]["p2"]["fighter"]["name"], json!("Fox"));
+
+    assert_eq!(session.run(&mut root, "[\"p1\"]:swapwith [\"p1\"].fighter"), Ok(String::from("Cannot swap /players[\"p1\"] with /players[\"p1\"].fighter as one contains the other")));
+    assert_eq!(session.run(&mut root, "[\"p1\"]:swapwith"), Err(String::from("Missing path for swapwith")));
+}
+
+#[test]
+fn session_transfer_type_mismatch() {
+    let mut root: (String, u32) = (String::from("Fox"), 75);
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "[0]:copyto [1]"), Ok(String::from("Cannot copy /[0] to /[1]: Invalid value for u32 (needs to be: A number from 0 to 4,294,967,295)")));
+    assert_eq!(root, (String::from("Fox"), 75));
+}
+
+#[test]
+fn session_transfer_restores() {
+    let mut session = Session::new();
+
+    // the first value fits in both places but the second doesn't, so the first set is undone
+    let mut root: (Vec<i32>, Vec<u32>) = (vec!(-1), vec!(1, 2));
+    assert_eq!(session.run(&mut root, "[1]:swapwith [0]"), Ok(String::from("Cannot swap /[1] with /[0]: vector set error: invalid value: integer `-1`, expected u32 at line 1 column 3")));
+    assert_eq!(root, (vec!(-1), vec!(1, 2)));
+}
+
+#[test]
+fn session_exists() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "stage:exists"), Ok(String::from("true")));
+    assert_eq!(session.run(&mut root, "players[\"p3\"]:exists"), Ok(String::from("false")));
+    assert_eq!(session.run(&mut root, "cd players[\"p1\"].fighter"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "hitboxes[1]:exists"), Ok(String::from("true")));
+    assert_eq!(session.run(&mut root, "hitboxes[2]:exists"), Ok(String::from("false")));
+    assert_eq!(session.run(&mut root, "../..[\"p2\"].fighter.hitboxes[0]:exists"), Ok(String::from("false")));
+    let output = session.run(&mut root, "hitboxes[*].damage:exists").unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&output).unwrap(), json!([{ "path": "[0].damage", "result": "true" }, { "path": "[1].damage", "result": "true" }]));
+}
+
+#[test]
+fn session_type() {
+    let mut root: (String, Vec<u32>) = (String::from("Fox"), vec!(1));
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, ":type"), Ok(String::from("(alloc::string::String, alloc::vec::Vec<u32>)")));
+    assert_eq!(session.run(&mut root, "[1][0]:type"), Ok(String::from("u32")));
+
+    let mut root = test_json();
+    assert_eq!(session.run(&mut root, "stage:type"), Ok(String::from("serde_json::value::Value")));
+}
+
+#[test]
+fn session_if() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "cd players[\"p1\"].fighter"), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "if hitboxes[1].damage:get > 10 then hitboxes[1].damage:set 10"), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"]["hitboxes"][1]["damage"], json!(10));
+    assert_eq!(session.run(&mut root, "if hitboxes[1].damage:get > 10 then hitboxes[1].damage:set 0"), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"]["hitboxes"][1]["damage"], json!(10));
+
+    assert_eq!(session.run(&mut root, "if hitboxes[0].damage:get >= 4 then name:get else /stage:get"), Ok(String::from("\"Fox\"")));
+    assert_eq!(session.run(&mut root, "if hitboxes[0].damage:get != 4 then name:get else /stage:get"), Ok(String::from("\"Battlefield\"")));
+    assert_eq!(session.run(&mut root, "if name:get == Fox then name:set \"Fox McCloud\""), Ok(String::new()));
+    assert_eq!(session.run(&mut root, "if name:get == \"Fox McCloud\" then hitboxes[2]:exists"), Ok(String::from("false")));
+    assert_eq!(session.run(&mut root, r#"if hitboxes[2]:exists == false then hitboxes:push {\"damage\":1}"#), Ok(String::new()));
+    assert_eq!(root["players"]["p1"]["fighter"]["hitboxes"][2]["damage"], json!(1));
+
+    // only the line itself is added to the history
+    assert_eq!(session.history().len(), 8);
+}
+
+#[test]
+fn session_if_invalid() {
+    let mut root = test_json();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut root, "if stage:get == Battlefield"), Err(String::from("Missing then (needs to be: if COMMAND OPERATOR VALUE then COMMAND [else COMMAND])")));
+    assert_eq!(session.run(&mut root, "if stage:get then stage:set \"\""), Err(String::from("Missing operator (needs to be one of: ==, !=, <=, >=, <, >)")));
+    assert_eq!(session.run(&mut root, "if stage:get == then stage:set \"\""), Err(String::from("Missing command or value (needs to be: if COMMAND OPERATOR VALUE then COMMAND [else COMMAND])")));
+    assert_eq!(session.run(&mut root, "if stage:get == Battlefield then"), Err(String::from("Missing command (needs to be: if COMMAND OPERATOR VALUE then COMMAND [else COMMAND])")));
+    assert_eq!(session.run(&mut root, "if stage:get == Battlefield then stage:get else"), Err(String::from("Missing command (needs to be: if COMMAND OPERATOR VALUE then COMMAND [else COMMAND])")));
+    assert_eq!(session.run(&mut root, "if ..:get == 1 then stage:get"), Err(String::from("Cannot go above the root")));
+
+    // the condition can't change anything and an error from it isn't compared as a value
+    assert_eq!(session.run(&mut root, "if stage:set 5 == \"\" then stage:get"), Err(String::from("The condition 'stage:set 5' must not modify anything (try an action like get)")));
+    assert_eq!(root["stage"], json!("Battlefield"));
+    assert_eq!(session.run(&mut root, "if stage:moveto players == \"\" then stage:get"), Err(String::from("The condition 'stage:moveto players' must not modify anything (try an action like get)")));
+    assert_eq!(session.run(&mut root, "if players[\"p1\"].fighter.hitboxes[5].damage:get != 4 then stage:set 1"), Err(String::from("The condition 'players[\"p1\"].fighter.hitboxes[5].damage:get' failed: Used index 5 on a json array of size 2 (try a value between 0-1)")));
+    assert_eq!(session.run(&mut root, "if players[*].fighter.name:get == Fox then stage:set 1"), Err(String::from("The condition 'players[*].fighter.name:get' cannot use [*] or [?]")));
+    assert_eq!(root["stage"], json!("Battlefield"));
+}
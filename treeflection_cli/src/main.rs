@@ -0,0 +1,217 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process;
+
+use serde_json::Value;
+use treeflection::Session;
+
+const USAGE: &str = r#"
+Usage: treeflection-cli [OPTIONS] FILE [COMMAND...]
+
+Loads FILE into a JSON tree and runs each COMMAND on it, printing the results.
+If no COMMAND or --script is given, commands are read from stdin, one per line.
+Run the command ':help' to see what can be done with the root of the tree.
+Commands are run in a session, run 'help' to see the session commands such as cd and ls.
+
+Options:
+*   -f, --format FORMAT - format of FILE, json or ron (default: guessed from the extension)
+*   -s, --script PATH   - run the commands in PATH, one per line, lines starting with # are ignored
+*   -w, --write         - write the modified tree back to FILE
+*   -o, --output PATH   - write the modified tree to PATH
+*   -i, --repl          - start an interactive session after running any COMMAND or --script
+*   -h, --help          - display this help"#;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Ron,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Format, String> {
+        match name.to_lowercase().as_ref() {
+            "json" => Ok(Format::Json),
+            "ron"  => Ok(Format::Ron),
+            _      => Err(format!("Unknown format '{}' (try json or ron)", name)),
+        }
+    }
+
+    fn from_path(path: &str) -> Option<Format> {
+        Path::new(path).extension().and_then(|x| x.to_str()).and_then(|x| Format::from_name(x).ok())
+    }
+
+    fn load(self, text: &str) -> Result<Value, String> {
+        match self {
+            Format::Json => serde_json::from_str(text).map_err(|x| x.to_string()),
+            Format::Ron  => ron::from_str(text).map_err(|x| x.to_string()),
+        }
+    }
+
+    fn save(self, value: &Value) -> Result<String, String> {
+        let mut text = match self {
+            Format::Json => serde_json::to_string_pretty(value).map_err(|x| x.to_string())?,
+            Format::Ron  => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).map_err(|x| x.to_string())?,
+        };
+        text.push('\n');
+        Ok(text)
+    }
+}
+
+struct Args {
+    file:     String,
+    format:   Format,
+    commands: Vec<String>,
+    script:   Option<String>,
+    output:   Option<String>,
+    repl:     bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item=String>) -> Result<Args, String> {
+        let mut file = None;
+        let mut format = None;
+        let mut commands = vec!();
+        let mut script = None;
+        let mut output = None;
+        let mut write = false;
+        let mut repl = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "-h" | "--help" => return Err(String::new()),
+                "-w" | "--write" => write = true,
+                "-i" | "--repl" => repl = true,
+                "-f" | "--format" => {
+                    let name = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                    format = Some(Format::from_name(&name)?);
+                }
+                "-s" | "--script" => {
+                    script = Some(args.next().ok_or_else(|| format!("Missing value for {}", arg))?);
+                }
+                "-o" | "--output" => {
+                    output = Some(args.next().ok_or_else(|| format!("Missing value for {}", arg))?);
+                }
+                _ if file.is_none() => file = Some(arg),
+                _ => commands.push(arg),
+            }
+        }
+
+        let file = file.ok_or_else(|| String::from("Missing FILE"))?;
+        if write && output.is_none() {
+            output = Some(file.clone());
+        }
+
+        Ok(Args {
+            format: format.or_else(|| Format::from_path(&file)).unwrap_or(Format::Json),
+            file,
+            commands,
+            script,
+            output,
+            repl,
+        })
+    }
+}
+
+/// Runs a single command on the tree, printing the result to stdout.
+/// Returns false if the command could not be parsed.
+fn run_command(session: &mut Session, root: &mut Value, command: &str) -> bool {
+    match session.run(root, command) {
+        Ok (result) => {
+            if !result.is_empty() {
+                println!("{}", result);
+            }
+            true
+        }
+        Err (err) => {
+            eprintln!("{}: {}", command, err);
+            false
+        }
+    }
+}
+
+fn repl(session: &mut Session, root: &mut Value) -> Result<(), String> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("{}> ", session.pwd());
+        io::stdout().flush().map_err(|x| format!("Failed to write stdout: {}", x))?;
+
+        let line = match lines.next() {
+            Some (line) => line.map_err(|x| format!("Failed to read stdin: {}", x))?,
+            None        => {
+                println!();
+                return Ok(());
+            }
+        };
+        match line.trim() {
+            "exit" | "quit" => return Ok(()),
+            line => { run_command(session, root, line); }
+        }
+    }
+}
+
+fn script_commands(text: &str) -> impl Iterator<Item=&str> {
+    text.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with('#'))
+}
+
+fn run(args: Args) -> Result<bool, String> {
+    let text = fs::read_to_string(&args.file).map_err(|x| format!("Failed to read {}: {}", args.file, x))?;
+    let mut root = args.format.load(&text).map_err(|x| format!("Failed to parse {}: {}", args.file, x))?;
+
+    let mut session = Session::new();
+    let mut success = true;
+    if let Some(script) = &args.script {
+        let text = fs::read_to_string(script).map_err(|x| format!("Failed to read {}: {}", script, x))?;
+        for command in script_commands(&text) {
+            success &= run_command(&mut session, &mut root, command);
+        }
+    }
+    for command in &args.commands {
+        success &= run_command(&mut session, &mut root, command);
+    }
+    if args.repl {
+        repl(&mut session, &mut root)?;
+    }
+    else if args.script.is_none() && args.commands.is_empty() {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let line = line.map_err(|x| format!("Failed to read stdin: {}", x))?;
+            for command in script_commands(&line) {
+                success &= run_command(&mut session, &mut root, command);
+            }
+        }
+    }
+
+    if let Some(output) = &args.output {
+        let format = if *output == args.file { args.format } else { Format::from_path(output).unwrap_or(args.format) };
+        let text = format.save(&root)?;
+        fs::write(output, text).map_err(|x| format!("Failed to write {}: {}", output, x))?;
+    }
+
+    Ok(success)
+}
+
+fn main() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok (args) => args,
+        Err (err) => {
+            if err.is_empty() {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            eprintln!("{}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match run(args) {
+        Ok (true)  => { }
+        Ok (false) => process::exit(1),
+        Err (err)  => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const FIGHTER_JSON: &str = r#"{
+  "name": "Fox",
+  "weight": 75,
+  "moves": [
+    { "name": "jab", "damage": 4 },
+    { "name": "dair", "damage": 12 }
+  ]
+}"#;
+
+const FIGHTER_RON: &str = r#"{
+    "name": "Fox",
+    "weight": 75,
+}"#;
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("treeflection_cli_{}_{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn cli(args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_treeflection-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.unwrap_or("").as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+/// The output of `moves[*].damage` with the result of each move
+fn damage_fan_out(jab: &str, dair: &str) -> String {
+    format!(r#"[
+  {{
+    "path": "[0].damage",
+    "result": "{}"
+  }},
+  {{
+    "path": "[1].damage",
+    "result": "{}"
+  }}
+]"#, jab, dair)
+}
+
+#[test]
+fn commands_from_args() {
+    let file = temp_file("args.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "weight:get", "moves[1].name:get", "moves[*].damage:get"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("75\n\"dair\"\n{}\n", damage_fan_out("4", "12")));
+    assert_eq!(fs::read_to_string(&file).unwrap(), FIGHTER_JSON);
+}
+
+#[test]
+fn commands_from_stdin() {
+    let file = temp_file("stdin.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap()], Some("weight:add 5\n\n# comment\nweight:get\n"));
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "80\n");
+}
+
+#[test]
+fn commands_from_script() {
+    let file = temp_file("script.json", FIGHTER_JSON);
+    let script = temp_file("script.txt", "# double the damage of every move\nmoves[*].damage:multiply 2\nmoves[*].damage:get\n");
+    let output = cli(&[file.to_str().unwrap(), "--script", script.to_str().unwrap(), "moves[0].damage:get"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("{}\n{}\n8\n", damage_fan_out("", ""), damage_fan_out("8", "24")));
+}
+
+#[test]
+fn write_json() {
+    let file = temp_file("write.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "-w", "weight:set 80", "moves:remove 0"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+
+    let expected = r#"{
+  "moves": [
+    {
+      "damage": 12,
+      "name": "dair"
+    }
+  ],
+  "name": "Fox",
+  "weight": 80
+}
+"#;
+    assert_eq!(fs::read_to_string(&file).unwrap(), expected);
+}
+
+#[test]
+fn write_ron() {
+    let file = temp_file("write.ron", FIGHTER_RON);
+    let output = cli(&[file.to_str().unwrap(), "--write", "weight:get", "weight:set 80"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "75\n");
+
+    let output = cli(&[file.to_str().unwrap(), "weight:get", "name:get"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "80\n\"Fox\"\n");
+}
+
+#[test]
+fn output_other_format() {
+    let file = temp_file("convert.ron", FIGHTER_RON);
+    let out = env::temp_dir().join(format!("treeflection_cli_{}_converted.json", std::process::id()));
+    let output = cli(&[file.to_str().unwrap(), "-o", out.to_str().unwrap(), "name:set Falco"], None);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&out).unwrap(), "{\n  \"name\": \"Falco\",\n  \"weight\": 75\n}\n");
+    assert_eq!(fs::read_to_string(&file).unwrap(), FIGHTER_RON);
+}
+
+#[test]
+fn explicit_format() {
+    let file = temp_file("format.txt", FIGHTER_RON);
+    let output = cli(&["--format", "ron", file.to_str().unwrap(), "weight:get"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "75\n");
+
+    let output = cli(&["--format", "yaml", file.to_str().unwrap(), "weight:get"], None);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("Unknown format 'yaml' (try json or ron)\n"));
+}
+
+#[test]
+fn node_errors_are_printed() {
+    let file = temp_file("node_error.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "moves[5]:get"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "Used index 5 on a json array of size 2 (try a value between 0-1)\n");
+}
+
+#[test]
+fn invalid_command() {
+    let file = temp_file("invalid_command.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "weight", "weight:get"], None);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "75\n");
+    assert_eq!(stderr(&output), "weight: Missing action\n");
+}
+
+#[test]
+fn invalid_file() {
+    let file = temp_file("invalid_file.json", "{ not json");
+    let output = cli(&[file.to_str().unwrap(), ":get"], None);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Failed to parse "));
+}
+
+#[test]
+fn help() {
+    let output = cli(&["--help"], None);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("Usage: treeflection-cli [OPTIONS] FILE [COMMAND...]"));
+
+    let output = cli(&[], None);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("Missing FILE\n"));
+}
+
+#[test]
+fn session_commands() {
+    let file = temp_file("session.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "cd moves[1]", "pwd", "name:get", "..[0].name:get"], None);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "/moves[1]\n\"dair\"\n\"jab\"\n");
+}
+
+#[test]
+fn repl() {
+    let file = temp_file("repl.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "-i", "-w", "cd moves"], Some("[0].damage:set 5\n[0].damage:get\ncd ..\nfoo\nexit\nweight:get\n"));
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "/moves> /moves> 5\n/moves> /> /> ");
+    assert_eq!(stderr(&output), "foo: Missing action\n");
+
+    let output = cli(&[file.to_str().unwrap(), "moves[0].damage:get"], None);
+    assert_eq!(stdout(&output), "5\n");
+}
+
+#[test]
+fn repl_end_of_input() {
+    let file = temp_file("repl_eof.json", FIGHTER_JSON);
+    let output = cli(&[file.to_str().unwrap(), "--repl"], Some("weight:get\n"));
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "/> 75\n/> \n");
+}
@@ -0,0 +1,292 @@
+//! Generates get and set for `#[node(no_serde)]` types out of the get and set of each accessible field.
+//!
+//! The JSON has the same shape serde uses by default:
+//! objects for named fields, arrays for tuple fields, the inner value for newtypes and externally tagged enum variants.
+
+use proc_macro2::{Span, TokenStream};
+
+use syn::{Fields, Ident, Variant};
+use syn::punctuated::Iter;
+
+use super::{gen_variant, is_exposed, is_variant_field_exposed, property_names, serde_attrs};
+
+pub fn gen_struct_get(fields: &Fields, rename_all: Option<&str>) -> TokenStream {
+    let value = match fields {
+        Fields::Named (fields) => {
+            let fields: Vec<(String, TokenStream)> = fields.named.iter().filter(|x| is_exposed(x)).map(|field| {
+                let ident = &field.ident;
+                (property_names(field, rename_all).remove(0), quote_spanned!{ Span::call_site() => &mut self.#ident })
+            }).collect();
+            gen_get_named(&fields)
+        }
+        Fields::Unnamed (fields) => {
+            let (exprs, newtype) = struct_unnamed_exprs(fields.unnamed.iter());
+            gen_get_unnamed(&exprs, newtype)
+        }
+        Fields::Unit => quote_spanned!{ Span::call_site() => _treeflection::serde_json::Value::Null }
+    };
+
+    quote_spanned!{ Span::call_site() =>
+        _treeflection::compose::to_string(&#value)
+    }
+}
+
+pub fn gen_struct_set(name: &str, fields: &Fields, rename_all: Option<&str>) -> TokenStream {
+    let value = quote_spanned!{ Span::call_site() => value };
+    let set = match fields {
+        Fields::Named (fields) => {
+            let fields: Vec<(String, TokenStream)> = fields.named.iter().filter(|x| is_exposed(x)).map(|field| {
+                let ident = &field.ident;
+                (property_names(field, rename_all).remove(0), quote_spanned!{ Span::call_site() => &mut self.#ident })
+            }).collect();
+            gen_set_named(name, &fields, value)
+        }
+        Fields::Unnamed (fields) => {
+            let (exprs, newtype) = struct_unnamed_exprs(fields.unnamed.iter());
+            gen_set_unnamed(name, &exprs, newtype, value)
+        }
+        Fields::Unit => quote_spanned!{ Span::call_site() =>
+            if !value.is_null() {
+                return format!("{} set error: Expected null", #name);
+            }
+        }
+    };
+
+    gen_parse_then(name, set)
+}
+
+pub fn gen_enum_get(name: &Ident, variants: Iter<Variant>, rename_all: Option<&str>) -> TokenStream {
+    let mut arms: Vec<TokenStream> = vec!();
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let variant_name = serde_attrs::variant_name(variant, rename_all);
+        let field_rename_all = serde_attrs::rename_all(&variant.attrs);
+        let (pattern, fields_value) = match &variant.fields {
+            Fields::Named (fields) => {
+                let (bindings, fields) = variant_named_bindings(fields.named.iter(), field_rename_all.as_deref());
+                let pattern = quote_spanned!{ Span::call_site() => &mut #name::#variant_ident { #( #bindings, )* .. } };
+                (pattern, Some(gen_get_named(&fields)))
+            }
+            Fields::Unnamed (fields) => {
+                let (bindings, exprs, newtype) = variant_unnamed_bindings(fields.unnamed.iter());
+                let pattern = quote_spanned!{ Span::call_site() => &mut #name::#variant_ident ( #( #bindings ),* ) };
+                (pattern, Some(gen_get_unnamed(&exprs, newtype)))
+            }
+            Fields::Unit => (quote_spanned!{ Span::call_site() => &mut #name::#variant_ident }, None)
+        };
+
+        arms.push(match fields_value {
+            Some (fields_value) => quote_spanned!{ Span::call_site() =>
+                #pattern => {
+                    let mut object = _treeflection::serde_json::Map::new();
+                    object.insert(String::from(#variant_name), #fields_value);
+                    _treeflection::serde_json::Value::Object(object)
+                }
+            },
+            None => quote_spanned!{ Span::call_site() =>
+                #pattern => _treeflection::serde_json::Value::String(String::from(#variant_name)),
+            }
+        });
+    }
+
+    quote_spanned!{ Span::call_site() =>
+        let value = match self {
+            #( #arms )*
+        };
+        _treeflection::compose::to_string(&value)
+    }
+}
+
+/// Sets the variant with the same code as the variant action, then sets the fields of the variant
+pub fn gen_enum_set(name: &Ident, variants: Iter<Variant>, rename_all: Option<&str>) -> TokenStream {
+    let name_string = name.to_string();
+    let variant_arm = gen_variant(name, variants.clone(), rename_all);
+    let fields = quote_spanned!{ Span::call_site() => fields };
+
+    let mut arms: Vec<TokenStream> = vec!();
+    for variant in variants {
+        let variant_ident = &variant.ident;
+        let variant_name = serde_attrs::variant_name(variant, rename_all);
+        let field_rename_all = serde_attrs::rename_all(&variant.attrs);
+        arms.push(match &variant.fields {
+            Fields::Named (named) => {
+                let (bindings, named) = variant_named_bindings(named.named.iter(), field_rename_all.as_deref());
+                let set = gen_set_named(&variant_name, &named, fields.clone());
+                quote_spanned!{ Span::call_site() =>
+                    &mut #name::#variant_ident { #( #bindings, )* .. } => { #set }
+                }
+            }
+            Fields::Unnamed (unnamed) => {
+                let (bindings, exprs, newtype) = variant_unnamed_bindings(unnamed.unnamed.iter());
+                let set = gen_set_unnamed(&variant_name, &exprs, newtype, fields.clone());
+                quote_spanned!{ Span::call_site() =>
+                    &mut #name::#variant_ident ( #( #bindings ),* ) => { #set }
+                }
+            }
+            Fields::Unit => quote_spanned!{ Span::call_site() =>
+                &mut #name::#variant_ident => {
+                    if !fields.is_null() {
+                        return format!("{} set error: {} does not have any fields", #name_string, #variant_name);
+                    }
+                }
+            }
+        });
+    }
+
+    let set = quote_spanned!{ Span::call_site() =>
+        let (variant, fields) = match value {
+            _treeflection::serde_json::Value::String (variant) => (variant, None),
+            _treeflection::serde_json::Value::Object (object) if object.len() == 1 => {
+                let (variant, fields) = object.into_iter().next().unwrap();
+                (variant, Some (fields))
+            }
+            _ => return format!("{} set error: Expected a variant name or an object containing a single variant", #name_string)
+        };
+
+        let result = { #variant_arm };
+        if !result.is_empty() {
+            return result;
+        }
+
+        if let Some (fields) = fields {
+            match self {
+                #( #arms )*
+            }
+        }
+    };
+
+    gen_parse_then(&name_string, set)
+}
+
+/// Parses the JSON given to set into `value` before running `set`
+fn gen_parse_then(name: &str, set: TokenStream) -> TokenStream {
+    quote_spanned!{ Span::call_site() =>
+        let value = match _treeflection::compose::parse(#name, &value) {
+            Ok (value) => value,
+            Err (err) => return err
+        };
+        #set
+        String::new()
+    }
+}
+
+/// Each field is a pair of its name and an expression giving a mutable reference to it
+fn gen_get_named(fields: &[(String, TokenStream)]) -> TokenStream {
+    if fields.is_empty() {
+        return quote_spanned!{ Span::call_site() =>
+            _treeflection::serde_json::Value::Object(_treeflection::serde_json::Map::new())
+        };
+    }
+    let names = fields.iter().map(|x| &x.0);
+    let exprs = fields.iter().map(|x| &x.1);
+
+    quote_spanned!{ Span::call_site() =>
+        {
+            let mut object = _treeflection::serde_json::Map::new();
+            #( object.insert(String::from(#names), _treeflection::compose::get(#exprs)); )*
+            _treeflection::serde_json::Value::Object(object)
+        }
+    }
+}
+
+fn gen_get_unnamed(exprs: &[TokenStream], newtype: bool) -> TokenStream {
+    if newtype {
+        let expr = &exprs[0];
+        quote_spanned!{ Span::call_site() => _treeflection::compose::get(#expr) }
+    } else {
+        quote_spanned!{ Span::call_site() =>
+            _treeflection::serde_json::Value::Array(vec!(#( _treeflection::compose::get(#exprs) ),*))
+        }
+    }
+}
+
+/// Fields missing from the object are left unchanged
+fn gen_set_named(name: &str, fields: &[(String, TokenStream)], value: TokenStream) -> TokenStream {
+    let names: Vec<&String> = fields.iter().map(|x| &x.0).collect();
+    let exprs = fields.iter().map(|x| &x.1);
+
+    quote_spanned!{ Span::call_site() =>
+        match #value {
+            _treeflection::serde_json::Value::Object (object) => {
+                if let Err (err) = _treeflection::compose::check_keys(#name, &object, &[#( #names ),*]) {
+                    return err;
+                }
+                #(
+                    if let Some (value) = object.get(#names) {
+                        if let Err (err) = _treeflection::compose::set(#exprs, value) {
+                            return err;
+                        }
+                    }
+                )*
+            }
+            _ => return format!("{} set error: Expected an object", #name)
+        }
+    }
+}
+
+fn gen_set_unnamed(name: &str, exprs: &[TokenStream], newtype: bool, value: TokenStream) -> TokenStream {
+    if newtype {
+        let expr = &exprs[0];
+        return quote_spanned!{ Span::call_site() =>
+            if let Err (err) = _treeflection::compose::set(#expr, &#value) {
+                return err;
+            }
+        };
+    }
+    let len = exprs.len();
+    let indexes = 0..len;
+
+    quote_spanned!{ Span::call_site() =>
+        match #value {
+            _treeflection::serde_json::Value::Array (array) if array.len() == #len => {
+                #(
+                    if let Err (err) = _treeflection::compose::set(#exprs, &array[#indexes]) {
+                        return err;
+                    }
+                )*
+            }
+            _ => return format!("{} set error: Expected an array of {} values", #name, #len)
+        }
+    }
+}
+
+/// A single accessible field is treated as a newtype, matching serde
+fn struct_unnamed_exprs(fields: Iter<syn::Field>) -> (Vec<TokenStream>, bool) {
+    let len = fields.len();
+    let exprs: Vec<TokenStream> = fields.enumerate().filter(|(_, x)| is_exposed(x)).map(|(i, _)| {
+        let index = syn::Index::from(i);
+        quote_spanned!{ Span::call_site() => &mut self.#index }
+    }).collect();
+    let newtype = len == 1 && exprs.len() == 1;
+    (exprs, newtype)
+}
+
+/// The bindings used in the pattern for the variant and the (name, binding) pair of each field
+fn variant_named_bindings(fields: Iter<syn::Field>, rename_all: Option<&str>) -> (Vec<TokenStream>, Vec<(String, TokenStream)>) {
+    let mut bindings: Vec<TokenStream> = vec!();
+    let mut named: Vec<(String, TokenStream)> = vec!();
+    for field in fields.filter(|x| is_variant_field_exposed(x)) {
+        let ident = &field.ident;
+        bindings.push(quote_spanned!{ Span::call_site() => ref mut #ident });
+        named.push((property_names(field, rename_all).remove(0), quote_spanned!{ Span::call_site() => #ident }));
+    }
+    (bindings, named)
+}
+
+/// The bindings used in the pattern for the variant, the binding of each accessible field and whether it is a newtype variant
+fn variant_unnamed_bindings(fields: Iter<syn::Field>) -> (Vec<TokenStream>, Vec<TokenStream>, bool) {
+    let len = fields.len();
+    let mut bindings: Vec<TokenStream> = vec!();
+    let mut exprs: Vec<TokenStream> = vec!();
+    for (i, field) in fields.enumerate() {
+        if is_variant_field_exposed(field) {
+            let ident = Ident::new(&format!("x{}", i), Span::call_site());
+            bindings.push(quote_spanned!{ Span::call_site() => ref mut #ident });
+            exprs.push(quote_spanned!{ Span::call_site() => #ident });
+        } else {
+            bindings.push(quote_spanned!{ Span::call_site() => _ });
+        }
+    }
+    let newtype = len == 1 && exprs.len() == 1;
+    (bindings, exprs, newtype)
+}
@@ -1,70 +1,172 @@
 #![recursion_limit = "128"]
 #[macro_use] extern crate quote;
+#[macro_use] extern crate syn;
 
 use proc_macro2::{Span, TokenStream};
 
 use syn::{
     Attribute,
+    AttributeArgs,
     Data,
     DeriveInput,
     Field,
     Fields,
+    Generics,
     Ident,
+    ItemImpl,
     Lit,
     LitStr,
     Meta,
+    MetaNameValue,
+    Path,
     NestedMeta,
     Type,
     Variant,
     Visibility,
+    WherePredicate,
 };
 use syn::punctuated::{Iter, Punctuated};
 use syn::spanned::Spanned;
 use syn::token::Comma;
 
-#[proc_macro_derive(Node, attributes(NodeActions))]
+mod serde_attrs;
+mod node_actions;
+mod compose;
+
+#[proc_macro_derive(Node, attributes(NodeActions, node))]
 pub fn treeflection_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: DeriveInput = syn::parse(input).unwrap();
-    let name = &ast.ident;
-    let actions = attrs_to_actions(&ast.attrs);
+    let ast = parse_macro_input!(input as DeriveInput);
+    match derive_node(&ast) {
+        Ok (impl_for) => impl_for.into(),
+        Err (err)     => err.to_compile_error().into(),
+    }
+}
 
-    let impl_for = match ast.data {
-        Data::Enum(ref data)   => gen_enum(name, &data.variants, &actions),
-        Data::Struct(ref data) => gen_struct(name, &data.fields, &actions),
-        _ => unimplemented!()
+fn derive_node(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+    let options = attrs_to_options(&ast.attrs)?;
+    let actions = CustomActions { actions: attrs_to_actions(&ast.attrs)?, node_actions: options.actions };
+    check_fields(&ast.data)?;
+    let rename_all = serde_attrs::rename_all(&ast.attrs);
+    let rename_all = rename_all.as_deref();
+    let description = match help_text(&ast.attrs, "\n") {
+        Some (help) => format!("\n{}\n", help),
+        None        => String::new(),
     };
-    let copy_var = gen_copy_var(name);
 
-    let quote_tokens = quote!{
-        #impl_for
-        #copy_var
+    let generics = add_bounds(&ast.generics, &ast.data, &options);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let impl_node = quote_spanned!{ Span::call_site() =>
+        impl #impl_generics _treeflection::Node for #name #ty_generics #where_clause
+    };
+    let capabilities = Capabilities {
+        serde:   !options.no_serde,
+        default: !options.no_default,
+        // the clipboard is keyed by TypeId, which is only available for 'static types
+        clone:   !options.no_clone && ast.generics.lifetimes().next().is_none(),
     };
 
-    quote_tokens.into()
-}
+    let impl_for = match ast.data {
+        Data::Enum(_) if options.transparent => Err(syn::Error::new(name.span(), "Invalid node attribute: transparent can only be used on structs")),
+        Data::Struct(_) if options.transparent && options.actions => Err(syn::Error::new(name.span(), "Invalid node attribute: actions cannot be used with transparent")),
+        Data::Struct(ref data) if options.transparent => gen_transparent(name, &impl_node, &data.fields),
+        Data::Enum(ref data)   => Ok(gen_enum(name, &impl_node, &capabilities, &data.variants, &actions, rename_all, description)),
+        Data::Struct(ref data) => Ok(gen_struct(name, &impl_node, &capabilities, &data.fields, &actions, rename_all, description)),
+        Data::Union(ref data)  => Err(syn::Error::new(data.union_token.span, "Node cannot be derived for unions")),
+    }?;
 
-fn copy_var_name(name: &str) -> Ident {
-    let mut var_name = name.to_uppercase();
-    var_name.push_str("_COPY");
-    Ident::new(&var_name, Span::call_site())
+    Ok(wrap_in_const(options.krate.as_ref(), impl_for))
 }
 
-fn gen_copy_var(name: &Ident) -> TokenStream {
-    let var_name = copy_var_name(&name.to_string());
+/// Generated code refers to treeflection through the `_treeflection` alias declared here,
+/// so it works without any imports and when treeflection is re-exported under another path.
+fn wrap_in_const(krate: Option<&Path>, tokens: TokenStream) -> TokenStream {
+    let krate = match krate {
+        Some (krate) => quote_spanned!{ Span::call_site() => #krate },
+        None         => quote_spanned!{ Span::call_site() => ::treeflection },
+    };
     quote_spanned!{ Span::call_site() =>
-        static mut #var_name: Option<#name> = None;
+        const _: () = {
+            use #krate as _treeflection;
+            #tokens
+        };
     }
 }
 
+/// Implements treeflection::NodeActions for the methods of the impl block marked with #[action].
+/// Use #[node(actions)] on the type deriving Node to make these actions available to commands.
+#[proc_macro_attribute]
+pub fn node_actions(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(attr as AttributeArgs);
+    let mut item = parse_macro_input!(item as ItemImpl);
+    let mut krate: Option<Path> = None;
+    for arg in args.iter() {
+        match arg {
+            NestedMeta::Meta (Meta::NameValue (name_value)) if name_value.path.is_ident("crate") => {
+                match node_lit_str(name_value).and_then(parse_crate_path) {
+                    Ok (path) => krate = Some(path),
+                    Err (err) => return err.to_compile_error().into(),
+                }
+            }
+            _ => return syn::Error::new(arg.span(), "#[node_actions] only takes crate = \"...\"").to_compile_error().into()
+        }
+    }
+    let impl_actions = node_actions::expand(&mut item);
+    let impl_actions = wrap_in_const(krate.as_ref(), impl_actions);
+    let output = quote_spanned!{ Span::call_site() =>
+        #item
+        #impl_actions
+    };
+    output.into()
+}
+
+/// Bound each type parameter by everything the generated impl needs from it, unless the bounds are given with #[node(bound = "...")]
+fn add_bounds(generics: &Generics, data: &Data, options: &Options) -> Generics {
+    let mut generics = generics.clone();
+    let predicates: Vec<WherePredicate> = match options.bound {
+        Some (ref bound) => bound.clone(),
+        // a transparent impl only steps into its field
+        None if options.transparent => match data {
+            Data::Struct (ref data) if generics.type_params().next().is_some() => data.fields.iter().map(|field| {
+                let ty = &field.ty;
+                parse_quote!{ #ty: _treeflection::Node }
+            }).collect(),
+            _ => vec!()
+        },
+        None => generics.type_params().map(|param| {
+            let param = &param.ident;
+            let mut bound: WherePredicate = parse_quote!{ #param: _treeflection::Node };
+            if let WherePredicate::Type (ref mut bound) = bound {
+                if !options.no_serde {
+                    bound.bounds.push(parse_quote!{ _treeflection::serde::Serialize });
+                    bound.bounds.push(parse_quote!{ _treeflection::serde::de::DeserializeOwned });
+                }
+                if !options.no_default {
+                    bound.bounds.push(parse_quote!{ Default });
+                }
+                if !options.no_clone {
+                    bound.bounds.push(parse_quote!{ Clone });
+                }
+            }
+            bound
+        }).collect()
+    };
+
+    if !options.transparent && !options.no_clone && generics.type_params().next().is_some() && generics.lifetimes().next().is_none() {
+        generics.make_where_clause().predicates.push(parse_quote!{ Self: 'static });
+    }
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
+
 fn gen_get(name: &str) -> TokenStream {
     quote_spanned!{ Span::call_site() =>
-        use serde_json;
-        match serde_json::to_string_pretty(self) {
+        match _treeflection::serde_json::to_string_pretty(self) {
             Ok(result) => {
                 result
             }
             Err(err) => {
-                format!("{} get Error: {}", #name, err)
+                format!("{} get error: {}", #name, err)
             }
         }
     }
@@ -72,35 +174,72 @@ fn gen_get(name: &str) -> TokenStream {
 
 fn gen_set(name: &str) -> TokenStream {
     quote_spanned!{ Span::call_site() =>
-        use serde_json;
-        match serde_json::from_str(value.as_str()) {
+        match _treeflection::serde_json::from_str(value.as_str()) {
             Ok(result) => {
                 *self = result;
                 String::from("")
             }
             Err(err) => {
-                format!("{} set Error: {}", #name, err)
+                format!("{} set error: {}", #name, err)
             }
         }
     }
 }
 
-fn gen_copy(name: &str) -> TokenStream {
-    let var_name = copy_var_name(name);
-    quote_spanned!{ Span::call_site() =>
-        let copy = Some(self.clone());
-        unsafe {
-            #var_name = copy;
+/// Types without serde get in and set from JSON, converting to and from the other format
+fn gen_format_arms(name: &str, serde: bool) -> TokenStream {
+    if serde {
+        quote_spanned!{ Span::call_site() =>
+            _treeflection::NodeToken::GetWith (options) => { _treeflection::format::get(self, &options) }
+            _treeflection::NodeToken::SetWith (format, value) => {
+                match _treeflection::format::set(self, &value, format) {
+                    Ok (()) => String::new(),
+                    Err (err) => format!("{} set error: {}", #name, err)
+                }
+            }
+        }
+    } else {
+        quote_spanned!{ Span::call_site() =>
+            _treeflection::NodeToken::GetWith (options) => {
+                _treeflection::format::get(&_treeflection::compose::get(self), &options)
+            }
+            _treeflection::NodeToken::SetWith (format, value) => {
+                match _treeflection::format::from_str::<_treeflection::serde_json::Value>(&value, format) {
+                    Ok (value) => {
+                        let json = _treeflection::compose::to_string(&value);
+                        _treeflection::Node::node_step(self, _treeflection::NodeRunner { tokens: vec!(_treeflection::NodeToken::Set (json)) })
+                    }
+                    Err (err) => format!("{} set error: {}", #name, err)
+                }
+            }
+        }
+    }
+}
+
+/// Types without Default fall through to the "cannot" arm instead
+fn gen_default_arm(default: bool) -> TokenStream {
+    if default {
+        quote_spanned!{ Span::call_site() =>
+            _treeflection::NodeToken::SetDefault => {
+                *self = Default::default();
+                String::new()
+            }
         }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn gen_copy() -> TokenStream {
+    quote_spanned!{ Span::call_site() =>
+        _treeflection::clipboard::copy(self);
         String::new()
     }
 }
 
 fn gen_paste(name: &str) -> TokenStream {
-    let var_name = copy_var_name(name);
     quote_spanned!{ Span::call_site() =>
-        let paste = unsafe { #var_name.clone() };
-        match paste {
+        match _treeflection::clipboard::paste::<Self>() {
             Some (value) => {
                 *self = value;
                 String::new()
@@ -112,16 +251,49 @@ fn gen_paste(name: &str) -> TokenStream {
     }
 }
 
-fn gen_custom_actions(name: &str, actions: &[Action]) -> TokenStream {
+/// Types that can't be copied fall through to the "cannot" arm instead
+fn gen_copy_paste_arms(name: &str, copyable: bool) -> TokenStream {
+    if copyable {
+        let copy_arm = gen_copy();
+        let paste_arm = gen_paste(name);
+        quote_spanned!{ Span::call_site() =>
+            _treeflection::NodeToken::CopyFrom => { #copy_arm }
+            _treeflection::NodeToken::PasteTo  => { #paste_arm }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+fn gen_custom_actions(name: &str, actions: &CustomActions) -> TokenStream {
     let mut arms: Vec<TokenStream> = vec!();
-    for action in actions {
+    for action in &actions.actions {
         let action_name = &action.action;
         let span = action.function.span();
         let function_name = Ident::new(action.function.value().as_ref(), span);
-        let mut args: Vec<TokenStream> = vec!();
+        let arg_count = action.args;
+
+        // each argument is parsed into the type of the matching parameter, which is inferred from the call unless given by arg_types
+        let mut arg_names: Vec<Ident> = vec!();
+        let mut arg_parsers: Vec<TokenStream> = vec!();
         for i in 0..action.args {
-            args.push(quote_spanned! { Span::call_site() => args[#i].clone() });
+            let arg_name = Ident::new(&format!("arg{}", i), Span::call_site());
+            let parse_arg = match action.arg_types {
+                Some (ref types) => {
+                    let ty = &types[i];
+                    quote_spanned!{ Span::call_site() => _treeflection::args::parse_arg::<#ty> }
+                }
+                None => quote_spanned!{ Span::call_site() => _treeflection::args::parse_arg }
+            };
+            arg_parsers.push(quote_spanned!{ Span::call_site() =>
+                let #arg_name = match #parse_arg(&args, #i) {
+                    Ok (arg) => arg,
+                    Err (err) => return format!("{} {}: {}", #name, #action_name, err)
+                };
+            });
+            arg_names.push(arg_name);
         }
+        let args = &arg_names;
 
         let function_call = if action.return_string {
             quote_spanned!{ span =>
@@ -136,35 +308,53 @@ fn gen_custom_actions(name: &str, actions: &[Action]) -> TokenStream {
 
         arms.push(quote_spanned!{ Span::call_site() =>
             #action_name => {
+                if args.len() > #arg_count {
+                    return format!("{} {}: Takes {} arguments but was given {}", #name, #action_name, #arg_count, args.len());
+                }
+                #( #arg_parsers )*
                 self.#function_call
             }
         });
     }
 
+    // actions from #[node_actions] are only tried when none of the NodeActions match
+    let fallback = if actions.node_actions {
+        quote_spanned!{ Span::call_site() =>
+            a => match _treeflection::NodeActions::node_action(self, a, &args) {
+                Some (result) => result,
+                None          => format!("{} cannot '{}'", #name, a)
+            }
+        }
+    } else {
+        quote_spanned!{ Span::call_site() =>
+            a => format!("{} cannot '{}'", #name, a)
+        }
+    };
+
     quote_spanned!{ Span::call_site() =>
         match action.as_str() {
             #( #arms )*
-            a => format!("{} cannot '{}'", #name, a)
+            #fallback
         }
     }
 }
 
-fn gen_enum(name: &Ident, variants: &Punctuated<Variant, Comma>, actions: &[Action]) -> TokenStream {
+fn gen_enum(name: &Ident, impl_node: &TokenStream, capabilities: &Capabilities, variants: &Punctuated<Variant, Comma>, actions: &CustomActions, rename_all: Option<&str>, description: String) -> TokenStream {
     let name_string = name.to_string();
 
-    let property_arm = gen_enum_property(&name, variants.iter());
-    let index_arm = gen_enum_index(&name, variants.iter());
-    let get_arm = gen_get(&name_string);
-    let set_arm = gen_set(&name_string);
-    let copy_arm = gen_copy(&name_string);
-    let paste_arm = gen_paste(&name_string);
-    let help_arm = gen_enum_help(&name_string, variants.iter(), actions);
-    let variant_arm = gen_variant(&name, variants.iter());
-    let custom_arm = gen_custom_actions(&name_string, actions);
-    let default_arm = quote_spanned!{ Span::call_site() =>
-        *self = Default::default();
-        String::new()
+    let property_arm = gen_enum_property(name, variants.iter(), rename_all);
+    let index_arm = gen_enum_index(name, variants.iter(), rename_all);
+    let (get_arm, set_arm) = if capabilities.serde {
+        (gen_get(&name_string), gen_set(&name_string))
+    } else {
+        (compose::gen_enum_get(name, variants.iter(), rename_all), compose::gen_enum_set(name, variants.iter(), rename_all))
     };
+    let copy_paste_arms = gen_copy_paste_arms(&name_string, capabilities.clone);
+    let format_arms = gen_format_arms(&name_string, capabilities.serde);
+    let help_arm = gen_enum_help(&name_string, &description, capabilities, variants.iter(), actions, rename_all);
+    let variant_arm = gen_variant(name, variants.iter(), rename_all);
+    let custom_arm = gen_custom_actions(&name_string, actions);
+    let default_arm = gen_default_arm(capabilities.default);
 
     // this is required to avoid an unused variable warning from generated code
     let index_name = if check_using_index(variants.iter()) {
@@ -172,27 +362,31 @@ fn gen_enum(name: &Ident, variants: &Punctuated<Variant, Comma>, actions: &[Acti
     } else {
         quote_spanned!{ Span::call_site() => _ }
     };
-    let args = if actions.iter().all(|x| x.args == 0) {
+    let args = if actions.is_empty() {
         quote_spanned!{ Span::call_site() => _ }
     } else {
         quote_spanned!{ Span::call_site() => args }
     };
 
     quote_spanned!{ Span::call_site() =>
-        impl Node for #name {
-            fn node_step(&mut self, mut runner: NodeRunner) -> String {
+        #impl_node {
+            fn node_step(&mut self, mut runner: _treeflection::NodeRunner) -> String {
                 match runner.step() {
-                    NodeToken::ChainProperty (property) => { #property_arm }
-                    NodeToken::ChainIndex (#index_name) => { #index_arm }
-                    NodeToken::Get                      => { #get_arm }
-                    NodeToken::Set (value)              => { #set_arm }
-                    NodeToken::CopyFrom                 => { #copy_arm }
-                    NodeToken::PasteTo                  => { #paste_arm }
-                    NodeToken::Help                     => { #help_arm }
-                    NodeToken::SetVariant (variant)     => { #variant_arm }
-                    NodeToken::SetDefault               => { #default_arm }
-                    NodeToken::Custom (action, #args)   => { #custom_arm }
-                    action                              => { format!("{} cannot '{:?}'", #name_string, action) }
+                    _treeflection::NodeToken::ChainProperty (property) => { #property_arm }
+                    _treeflection::NodeToken::ChainIndex (#index_name) => { #index_arm }
+                    _treeflection::NodeToken::Get                      => { #get_arm }
+                    _treeflection::NodeToken::Set (value)              => { #set_arm }
+                    #format_arms
+                    #copy_paste_arms
+                    _treeflection::NodeToken::Edit                     => { _treeflection::edit::edit(self) }
+                    _treeflection::NodeToken::GetType                  => { String::from(::std::any::type_name::<Self>()) }
+                    _treeflection::NodeToken::Exists                   => { String::from("true") }
+                    _treeflection::NodeToken::CheckPath                => { String::new() }
+                    _treeflection::NodeToken::Help                     => { #help_arm }
+                    _treeflection::NodeToken::SetVariant (variant)     => { #variant_arm }
+                    #default_arm
+                    _treeflection::NodeToken::Custom (action, #args)   => { #custom_arm }
+                    action                              => { _treeflection::node::path_error(&runner, format!("{} cannot '{:?}'", #name_string, action)) }
                 }
             }
         }
@@ -208,14 +402,14 @@ fn check_using_index(variants: Iter<Variant>) -> bool {
     false
 }
 
-fn gen_variant(name: &Ident, variants: Iter<Variant>) -> TokenStream {
+fn gen_variant(name: &Ident, variants: Iter<Variant>, rename_all: Option<&str>) -> TokenStream {
     let name_string = name.to_string();
     let mut variant_arms: Vec<TokenStream> = vec!();
-    for variant in variants {
+    for variant in variants.filter(|x| !serde_attrs::is_skipped(&x.attrs)) {
         let variant_name = &variant.ident;
-        let variant_name_string = variant_name.to_string();
+        let variant_name_string = serde_attrs::variant_name(variant, rename_all);
         variant_arms.push(match &variant.fields {
-            &Fields::Named (ref fields) => {
+            Fields::Named (fields) => {
                 let mut field_values: Vec<TokenStream> = vec!();
                 for field in fields.named.iter() {
                     let field_name = field.ident.as_ref().unwrap();
@@ -230,7 +424,7 @@ fn gen_variant(name: &Ident, variants: Iter<Variant>) -> TokenStream {
                     }
                 }
             }
-            &Fields::Unnamed (ref fields) => {
+            Fields::Unnamed (fields) => {
                 let field_values: Vec<_> = fields.unnamed.iter().map(|_|
                     quote_spanned!{ Span::call_site() =>
                         Default::default()
@@ -262,48 +456,50 @@ fn gen_variant(name: &Ident, variants: Iter<Variant>) -> TokenStream {
     }
 }
 
-fn gen_enum_property(name: &Ident, variants: Iter<Variant>) -> TokenStream {
+fn gen_enum_property(name: &Ident, variants: Iter<Variant>, rename_all: Option<&str>) -> TokenStream {
     let mut enum_arms: Vec<TokenStream> = vec!();
 
     for variant in variants {
         let variant_name = &variant.ident;
-        let variant_name_string = &variant.ident.to_string();
+        let variant_name_string = &serde_attrs::variant_name(variant, rename_all);
+        let field_rename_all = serde_attrs::rename_all(&variant.attrs);
         enum_arms.push(match &variant.fields {
-            &Fields::Named(ref fields) => {
+            Fields::Named(fields) => {
                 let mut field_names: Vec<TokenStream> = vec!();
                 let mut property_arms: Vec<TokenStream> = vec!();
-                for field in fields.named.iter() {
+                for field in fields.named.iter().filter(|x| is_variant_field_exposed(x)) {
                     let field_name = &field.ident;
-                    let field_name_string = field_name.as_ref().unwrap().to_string();
+                    let property_names = property_names(field, field_rename_all.as_deref());
                     let span = field.span();
                     field_names.push(quote_spanned!{ Span::call_site() => ref mut #field_name });
                     let runner = quote_spanned!{ Span::call_site() => runner };
+                    let step = gen_field_step(variant_name_string, field, &property_names[0], quote_spanned!{ span => _treeflection::Node::node_step(#field_name, #runner) });
 
-                    property_arms.push(quote_spanned!{ span => #field_name_string => { #field_name.node_step(#runner) } });
+                    property_arms.push(quote_spanned!{ Span::call_site() => #( #property_names )|* => { #step } });
                 }
 
                 quote_spanned!{ Span::call_site() =>
-                    &mut #name::#variant_name { #( #field_names ),* } => {
+                    &mut #name::#variant_name { #( #field_names, )* .. } => {
                         match property.as_str() {
                             #( #property_arms )*
-                            _ => { format!("{} does not have a property '{}'", #variant_name_string, property) }
+                            _ => { _treeflection::node::path_error(&runner, format!("{} does not have a property '{}'", #variant_name_string, property)) }
                         }
                     }
                 }
             }
-            &Fields::Unnamed (ref fields) => {
+            Fields::Unnamed (fields) => {
                 let mut underscores: Vec<TokenStream> = vec!();
                 for _ in fields.unnamed.iter() {
                     underscores.push(quote_spanned!{ Span::call_site() => _});
                 }
 
                 quote_spanned!{ Span::call_site() =>
-                    &mut #name::#variant_name ( #( #underscores ),* ) => { format!("{} does not have a property '{}'", #variant_name_string, property) }
+                    &mut #name::#variant_name ( #( #underscores ),* ) => { _treeflection::node::path_error(&runner, format!("{} does not have a property '{}'", #variant_name_string, property)) }
                 }
             }
             &Fields::Unit => {
                 quote_spanned!{ Span::call_site() =>
-                    &mut #name::#variant_name => { format!("{} does not have a property '{}'", #variant_name_string, property) }
+                    &mut #name::#variant_name => { _treeflection::node::path_error(&runner, format!("{} does not have a property '{}'", #variant_name_string, property)) }
                 }
             }
         });
@@ -316,14 +512,14 @@ fn gen_enum_property(name: &Ident, variants: Iter<Variant>) -> TokenStream {
     }
 }
 
-fn gen_enum_index(name: &Ident, variants: Iter<Variant>) -> TokenStream {
+fn gen_enum_index(name: &Ident, variants: Iter<Variant>, rename_all: Option<&str>) -> TokenStream {
     let mut enum_arms: Vec<TokenStream> = vec!();
 
     for variant in variants {
         let variant_name = &variant.ident;
-        let variant_name_string = &variant.ident.to_string();
+        let variant_name_string = &serde_attrs::variant_name(variant, rename_all);
         enum_arms.push(match &variant.fields {
-            &Fields::Named (ref fields) => {
+            Fields::Named (fields) => {
                 let mut name_pairs: Vec<TokenStream> = vec!();
                 for field in fields.named.iter() {
                     let field_name = &field.ident;
@@ -331,32 +527,43 @@ fn gen_enum_index(name: &Ident, variants: Iter<Variant>) -> TokenStream {
                 }
 
                 quote_spanned!{ Span::call_site() =>
-                    &mut #name::#variant_name { #( #name_pairs ),* } => { format!("Cannot index {}", #variant_name_string) }
+                    &mut #name::#variant_name { #( #name_pairs ),* } => { _treeflection::node::path_error(&runner, format!("Cannot index {}", #variant_name_string)) }
                 }
             }
-            &Fields::Unnamed (ref fields) => {
+            Fields::Unnamed (fields) => {
                 let mut tuple_names: Vec<TokenStream> = vec!();
                 let mut index_arms: Vec<TokenStream> = vec!();
                 for (i, field) in fields.unnamed.iter().enumerate() {
+                    if !is_variant_field_exposed(field) {
+                        tuple_names.push(quote_spanned!{ Span::call_site() => _ });
+                        continue;
+                    }
                     let tuple_name = Ident::new(&format!("x{}", i), Span::call_site());
                     tuple_names.push(quote_spanned!{ Span::call_site() => ref mut #tuple_name });
                     let runner = quote_spanned!{ Span::call_site() => runner };
-                    index_arms.push(quote_spanned!{ field.span() => #i => { #tuple_name.node_step(#runner) } });
+                    let step = gen_field_step(variant_name_string, field, &format!("[{}]", i), quote_spanned!{ field.span() => _treeflection::Node::node_step(#tuple_name, #runner) });
+                    index_arms.push(quote_spanned!{ Span::call_site() => #i => { #step } });
                 }
-                let highest_index = fields.unnamed.len()-1;
-
-                quote_spanned!{ Span::call_site() =>
-                    &mut #name::#variant_name ( #( #tuple_names ),* ) => {
-                        match index {
-                            #( #index_arms ),*
-                            _ => { format!("Used index {} on a {} (try a value between 0-{}", index, #variant_name_string, #highest_index ) }
+                // an empty tuple variant e.g. A() has no index to suggest
+                if fields.unnamed.is_empty() {
+                    quote_spanned!{ Span::call_site() =>
+                        &mut #name::#variant_name () => { _treeflection::node::path_error(&runner, format!("Cannot index {}", #variant_name_string)) }
+                    }
+                } else {
+                    let highest_index = fields.unnamed.len() - 1;
+                    quote_spanned!{ Span::call_site() =>
+                        &mut #name::#variant_name ( #( #tuple_names ),* ) => {
+                            match index {
+                                #( #index_arms ),*
+                                _ => { _treeflection::node::path_error(&runner, format!("Used index {} on a {} (try a value between 0-{})", index, #variant_name_string, #highest_index )) }
+                            }
                         }
                     }
                 }
             }
             &Fields::Unit => {
                 quote_spanned!{ Span::call_site() =>
-                    &mut #name::#variant_name => { format!("Cannot index {}", #variant_name_string) }
+                    &mut #name::#variant_name => { _treeflection::node::path_error(&runner, format!("Cannot index {}", #variant_name_string)) }
                 }
             }
         });
@@ -369,63 +576,162 @@ fn gen_enum_index(name: &Ident, variants: Iter<Variant>) -> TokenStream {
     }
 }
 
-fn gen_struct(name: &Ident, data: &Fields, actions: &[Action]) -> TokenStream {
+fn gen_struct(name: &Ident, impl_node: &TokenStream, capabilities: &Capabilities, data: &Fields, actions: &CustomActions, rename_all: Option<&str>, description: String) -> TokenStream {
     let name_string = name.to_string();
-    match data {
-        &Fields::Named(ref fields_named) => {
-            let property_arm = gen_struct_property(&name_string, fields_named.named.iter());
-            let get_arm = gen_get(&name_string);
-            let set_arm = gen_set(&name_string);
-            let copy_arm = gen_copy(&name_string);
-            let paste_arm = gen_paste(&name_string);
-            let help_arm = gen_struct_help(&name_string, fields_named.named.iter(), actions);
-            let custom_arm = gen_custom_actions(&name_string, actions);
-            let default_arm = quote_spanned!{ Span::call_site() =>
-                *self = Default::default();
-                String::new()
+    let (chain_arms, accessors, has_property) = match data {
+        Fields::Named (fields) => {
+            let property_arm = gen_struct_property(&name_string, fields.named.iter(), rename_all);
+            let chain_arms = quote_spanned!{ Span::call_site() =>
+                _treeflection::NodeToken::ChainProperty (property) => { #property_arm }
             };
+            let has_property = gen_struct_has_property(fields.named.iter(), rename_all);
+            (chain_arms, Some(struct_accessor_help(fields.named.iter(), rename_all)), has_property)
+        }
+        Fields::Unnamed (fields) => {
+            let chain_arms = gen_tuple_struct_chain(&name_string, fields.unnamed.iter());
+            (chain_arms, Some(tuple_struct_accessor_help(fields.unnamed.iter())), TokenStream::new())
+        }
+        Fields::Unit => (TokenStream::new(), None, TokenStream::new())
+    };
 
-            // this is required to avoid an unused variable warning from generated code
-            let args = if actions.iter().all(|x| x.args == 0) {
-                quote_spanned!{ Span::call_site() => _ }
-            } else {
-                quote_spanned!{ Span::call_site() => args }
-            };
+    let (get_arm, set_arm) = if capabilities.serde {
+        (gen_get(&name_string), gen_set(&name_string))
+    } else {
+        (compose::gen_struct_get(data, rename_all), compose::gen_struct_set(&name_string, data, rename_all))
+    };
+    let copy_paste_arms = gen_copy_paste_arms(&name_string, capabilities.clone);
+    let format_arms = gen_format_arms(&name_string, capabilities.serde);
+    let help_arm = gen_struct_help(&name_string, &description, capabilities, accessors, actions);
+    let custom_arm = gen_custom_actions(&name_string, actions);
+    let default_arm = gen_default_arm(capabilities.default);
 
-            quote_spanned! { Span::call_site() =>
-                impl Node for #name {
-                    fn node_step(&mut self, mut runner: NodeRunner) -> String {
-                        match runner.step() {
-                            NodeToken::ChainProperty (property) => { #property_arm }
-                            NodeToken::Get                      => { #get_arm }
-                            NodeToken::Set (value)              => { #set_arm }
-                            NodeToken::CopyFrom                 => { #copy_arm }
-                            NodeToken::PasteTo                  => { #paste_arm }
-                            NodeToken::Help                     => { #help_arm }
-                            NodeToken::SetDefault               => { #default_arm }
-                            NodeToken::Custom (action, #args)   => { #custom_arm }
-                            action                              => { format!("{} cannot '{:?}'", #name_string, action) }
-                        }
-                    }
+    // this is required to avoid an unused variable warning from generated code
+    let args = if actions.is_empty() {
+        quote_spanned!{ Span::call_site() => _ }
+    } else {
+        quote_spanned!{ Span::call_site() => args }
+    };
+
+    quote_spanned! { Span::call_site() =>
+        #impl_node {
+            fn node_step(&mut self, mut runner: _treeflection::NodeRunner) -> String {
+                match runner.step() {
+                    #chain_arms
+                    _treeflection::NodeToken::Get                      => { #get_arm }
+                    _treeflection::NodeToken::Set (value)              => { #set_arm }
+                    #format_arms
+                    #copy_paste_arms
+                    _treeflection::NodeToken::Edit                     => { _treeflection::edit::edit(self) }
+                    _treeflection::NodeToken::GetType                  => { String::from(::std::any::type_name::<Self>()) }
+                    _treeflection::NodeToken::Exists                   => { String::from("true") }
+                    _treeflection::NodeToken::CheckPath                => { String::new() }
+                    _treeflection::NodeToken::Help                     => { #help_arm }
+                    #default_arm
+                    _treeflection::NodeToken::Custom (action, #args)   => { #custom_arm }
+                    action                              => { _treeflection::node::path_error(&runner, format!("{} cannot '{:?}'", #name_string, action)) }
                 }
             }
+
+            #has_property
         }
-        _ => unimplemented!()
     }
 }
 
-fn gen_struct_property(name: &str, fields: Iter<Field>) -> TokenStream {
-    let mut arms: Vec<TokenStream> = vec!();
-    for field in fields {
-        if let Visibility::Public(_) = field.vis {
-            let field_name = &field.ident.as_ref().unwrap();
-            let field_name_string = field_name.to_string();
+/// Every token is passed straight to the only field, so the struct behaves exactly like the field
+fn gen_transparent(name: &Ident, impl_node: &TokenStream, data: &Fields) -> syn::Result<TokenStream> {
+    let field = match data.iter().next() {
+        Some (field) if data.len() == 1 => field,
+        _ => {
+            let span = if data.is_empty() { name.span() } else { data.span() };
+            return Err(syn::Error::new(span, "Invalid node attribute: transparent requires a struct with exactly one field"));
+        }
+    };
+    let field = match field.ident {
+        Some (ref ident) => quote_spanned!{ Span::call_site() => #ident },
+        None => {
+            let index = syn::Index::from(0);
+            quote_spanned!{ Span::call_site() => #index }
+        }
+    };
+
+    Ok(quote_spanned! { Span::call_site() =>
+        #impl_node {
+            fn node_step(&mut self, runner: _treeflection::NodeRunner) -> String {
+                _treeflection::Node::node_step(&mut self.#field, runner)
+            }
+        }
+    })
+}
+
+/// Only public fields can be indexed, matching the handling of named fields
+fn gen_tuple_struct_chain(name: &str, fields: Iter<Field>) -> TokenStream {
+    let mut index_arms: Vec<TokenStream> = vec!();
+    let mut all_steps: Vec<TokenStream> = vec!();
+    let mut indexes: Vec<String> = vec!();
+    for (i, field) in fields.enumerate() {
+        if is_exposed(field) {
+            let index = syn::Index::from(i);
+            let display_name = format!("[{}]", i);
             let runner = quote_spanned!{ Span::call_site() => runner };
-            let step = quote_spanned!{ field.span() =>
-                #field_name.node_step(#runner)
-            };
+            let step = gen_field_step(name, field, &display_name, quote_spanned!{ field.span() =>
+                _treeflection::Node::node_step(&mut self.#index, #runner)
+            });
+            index_arms.push(quote_spanned!{ Span::call_site() =>
+                #i => { #step }
+            });
+            let step_clone = gen_field_step(name, field, &display_name, quote_spanned!{ Span::call_site() =>
+                _treeflection::Node::node_step(&mut self.#index, runner.clone())
+            });
+            all_steps.push(quote_spanned!{ Span::call_site() =>
+                fan_out.push(_treeflection::NodeToken::ChainIndex (#i), { #step_clone });
+            });
+            indexes.push(i.to_string());
+        }
+    }
+
+    if index_arms.is_empty() {
+        return quote_spanned!{ Span::call_site() =>
+            _treeflection::NodeToken::ChainIndex (_) => { _treeflection::node::path_error(&runner, format!("Cannot index {}", #name)) }
+        };
+    }
+    let indexes = indexes.join(", ");
+
+    quote_spanned!{ Span::call_site() =>
+        _treeflection::NodeToken::ChainIndex (index) => {
+            match index {
+                #( #index_arms )*
+                _ => { _treeflection::node::path_error(&runner, format!("Used index {} on a {} (try one of: {})", index, #name, #indexes)) }
+            }
+        }
+        _treeflection::NodeToken::ChainAll => {
+            let mut fan_out = _treeflection::fan_out::FanOut::new(&mut runner);
+            #( #all_steps )*
+            fan_out.finish()
+        }
+    }
+}
+
+fn gen_struct_property(name: &str, fields: Iter<Field>, rename_all: Option<&str>) -> TokenStream {
+    let mut arms: Vec<TokenStream> = vec!();
+    let mut flattened_arms: Vec<TokenStream> = vec!();
+    for field in fields.filter(|x| is_exposed(x)) {
+        let field_name = &field.ident.as_ref().unwrap();
+        let property_names = property_names(field, rename_all);
+        let runner = quote_spanned!{ Span::call_site() => runner };
+        let step = gen_field_step(name, field, &property_names[0], quote_spanned!{ field.span() =>
+            _treeflection::Node::node_step(&mut self.#field_name, #runner)
+        });
+        if serde_attrs::is_flattened(&field.attrs) {
+            // the property belongs to the flattened field, so give it back the property token
+            flattened_arms.push(quote_spanned!{ Span::call_site() =>
+                _ if _treeflection::Node::node_has_property(&self.#field_name, &property) => {
+                    runner.tokens.push(_treeflection::NodeToken::ChainProperty(property));
+                    #step
+                }
+            });
+        } else {
             arms.push(quote_spanned!{ Span::call_site() =>
-                #field_name_string => { self.#step }
+                #( #property_names )|* => { #step }
             });
         }
     }
@@ -433,61 +739,189 @@ fn gen_struct_property(name: &str, fields: Iter<Field>) -> TokenStream {
     quote_spanned!{ Span::call_site() =>
         match property.as_str() {
             #( #arms )*
-            prop => format!("{} does not have a property '{}'", #name, prop)
+            #( #flattened_arms )*
+            prop => _treeflection::node::path_error(&runner, format!("{} does not have a property '{}'", #name, prop))
         }
     }
 }
 
-fn gen_struct_help(name: &str, fields: Iter<Field>, actions: &[Action]) -> TokenStream {
-    let mut output = format!(r#"
-{} Help
+/// Lets a parent that flattens this struct find out which of its properties belong to this struct
+fn gen_struct_has_property(fields: Iter<Field>, rename_all: Option<&str>) -> TokenStream {
+    let mut names: Vec<String> = vec!();
+    let mut flattened: Vec<TokenStream> = vec!();
+    for field in fields.filter(|x| is_exposed(x)) {
+        let field_name = &field.ident.as_ref().unwrap();
+        if serde_attrs::is_flattened(&field.attrs) {
+            flattened.push(quote_spanned!{ Span::call_site() => _treeflection::Node::node_has_property(&self.#field_name, property) });
+        } else {
+            names.extend(property_names(field, rename_all));
+        }
+    }
 
-Actions:
-*   help  - display this help
-*   get   - display JSON
-*   set   - set to JSON
-*   copy  - copy the values from this struct
-*   paste - paste the copied values to this struct
-*   reset - reset to default values
+    quote_spanned!{ Span::call_site() =>
+        fn node_has_property(&self, property: &str) -> bool {
+            [#( #names ),*].contains(&property) #( || #flattened )*
+        }
+    }
+}
+
+/// Public fields and fields marked #[node(expose)] can be accessed unless they are skipped
+fn is_exposed(field: &Field) -> bool {
+    let options = field_options(&field.attrs);
+    let public = matches!(field.vis, Visibility::Public(_));
+    (public || options.expose) && !options.skip && !serde_attrs::is_skipped(&field.attrs)
+}
+
+/// Variant fields can't have a visibility so they can be accessed unless they are skipped
+fn is_variant_field_exposed(field: &Field) -> bool {
+    !field_options(&field.attrs).skip && !serde_attrs::is_skipped(&field.attrs)
+}
+
+/// The names a named field can be accessed by, the first name is the one displayed
+fn property_names(field: &Field, rename_all: Option<&str>) -> Vec<String> {
+    let options = field_options(&field.attrs);
+    let mut names = vec!(options.rename.unwrap_or_else(|| serde_attrs::field_name(field, rename_all)));
+    names.extend(options.aliases);
+    names
+}
+
+/// Readonly fields only allow stepping into them when the action doesn't modify anything
+fn gen_field_step(owner: &str, field: &Field, display_name: &str, step: TokenStream) -> TokenStream {
+    if field_options(&field.attrs).readonly {
+        quote_spanned!{ Span::call_site() =>
+            if runner.is_read_only() {
+                #step
+            } else {
+                format!("{} property '{}' is readonly", #owner, #display_name)
+            }
+        }
+    } else {
+        step
+    }
+}
+
+/// Extra information about a field for the accessor list in help
+fn accessor_notes(field: &Field, property_names: &[String]) -> String {
+    let mut notes: Vec<String> = vec!();
+    if property_names.len() > 1 {
+        notes.push(format!("alias: {}", property_names[1..].join(", ")));
+    }
+    if field_options(&field.attrs).readonly {
+        notes.push(String::from("readonly"));
+    }
+
+    if notes.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", notes.join(", "))
+    }
+}
+
+fn gen_struct_help(name: &str, description: &str, capabilities: &Capabilities, accessors: Option<String>, actions: &CustomActions) -> TokenStream {
+    let mut builtin_actions = String::from("*   help  - display this help\n*   get   - display JSON\n*   set   - set to JSON\n*   edit  - edit JSON in your editor\n*   type  - display the Rust type\n");
+    if capabilities.clone {
+        builtin_actions.push_str("*   copy  - copy the values from this struct\n*   paste - paste the copied values to this struct\n");
+    }
+    if capabilities.default {
+        builtin_actions.push_str("*   reset - reset to default values\n");
+    }
+
+    let before = format!(r#"
+{} Help
 {}
-Accessors:
-"#, name, custom_action_help(actions));
+Actions:
+{}{}"#, name, description, builtin_actions, custom_action_help(&actions.actions));
 
-    for field in fields {
-        if let Visibility::Public(_) = field.vis {
-            let field_name = &field.ident.as_ref().unwrap();
-            let field_type = type_string(&field.ty);
-            output.push_str(format!("*   {} - {}\n", field_name, field_type).as_ref());
+    let mut after = String::new();
+    if let Some(accessors) = accessors {
+        after.push_str("\nAccessors:\n");
+        after.push_str(&accessors);
+    }
+
+    gen_help_output(&before, &after, actions.node_actions, true)
+}
+
+/// Inserts the help of the actions from #[node_actions] between `before` and `after`, optionally removing the trailing newline
+fn gen_help_output(before: &str, after: &str, node_actions: bool, pop: bool) -> TokenStream {
+    if node_actions {
+        let pop = if pop {
+            quote_spanned!{ Span::call_site() => output.pop(); }
+        } else {
+            TokenStream::new()
+        };
+        quote_spanned!{ Span::call_site() =>
+            {
+                let mut output = format!("{}{}{}", #before, <Self as _treeflection::NodeActions>::node_actions_help(), #after);
+                #pop
+                output
+            }
+        }
+    } else {
+        let mut output = format!("{}{}", before, after);
+        if pop {
+            output.pop();
+        }
+        quote_spanned!{ Span::call_site() =>
+            String::from(#output)
         }
     }
-    output.pop();
+}
 
-    quote_spanned!{ Span::call_site() =>
-        String::from(#output)
+fn struct_accessor_help(fields: Iter<Field>, rename_all: Option<&str>) -> String {
+    let mut output = String::new();
+    for field in fields.filter(|x| is_exposed(x)) {
+        let field_type = type_string(&field.ty);
+        let property_names = property_names(field, rename_all);
+        let notes = accessor_notes(field, &property_names);
+        let description = accessor_description(&field.attrs);
+        if serde_attrs::is_flattened(&field.attrs) {
+            output.push_str(format!("*   the properties of {}{}{}\n", field_type, notes, description).as_ref());
+        } else {
+            output.push_str(format!("*   {} - {}{}{}\n", property_names[0], field_type, notes, description).as_ref());
+        }
     }
+    output
 }
 
-fn gen_enum_help(name: &str, variants: Iter<Variant>, actions: &[Action]) -> TokenStream {
+fn tuple_struct_accessor_help(fields: Iter<Field>) -> String {
+    let mut output = String::new();
+    for (i, field) in fields.enumerate() {
+        if is_exposed(field) {
+            let field_type = type_string(&field.ty);
+            let notes = accessor_notes(field, &[]);
+            let description = accessor_description(&field.attrs);
+            output.push_str(format!("*   [{}] - {}{}{}\n", i, field_type, notes, description).as_ref());
+        }
+    }
+    output
+}
+
+fn gen_enum_help(name: &str, description: &str, capabilities: &Capabilities, variants: Iter<Variant>, actions: &CustomActions, rename_all: Option<&str>) -> TokenStream {
     let mut variant_list = String::new();
     let mut accessor_list = String::new();
 
-    for variant in variants {
-        let variant_name = &variant.ident.to_string();
-        variant_list.push_str(format!("*   {}\n", variant_name).as_ref());
+    for variant in variants.filter(|x| !serde_attrs::is_skipped(&x.attrs)) {
+        let variant_name = &serde_attrs::variant_name(variant, rename_all);
+        let field_rename_all = serde_attrs::rename_all(&variant.attrs);
+        variant_list.push_str(format!("*   {}{}\n", variant_name, accessor_description(&variant.attrs)).as_ref());
         match &variant.fields {
-            &Fields::Named (ref fields) => {
+            Fields::Named (fields) => {
                 accessor_list.push_str(format!("As {}:\n", variant_name).as_ref());
-                for field in fields.named.iter() {
-                    let field_name = field.ident.as_ref().unwrap().to_string();
+                for field in fields.named.iter().filter(|x| is_variant_field_exposed(x)) {
+                    let property_names = property_names(field, field_rename_all.as_deref());
                     let field_type = type_string(&field.ty);
-                    accessor_list.push_str(format!("*   .{} - {}\n", field_name, field_type).as_ref());
+                    let notes = accessor_notes(field, &property_names);
+                    let description = accessor_description(&field.attrs);
+                    accessor_list.push_str(format!("*   .{} - {}{}{}\n", property_names[0], field_type, notes, description).as_ref());
                 }
             }
-            &Fields::Unnamed (ref fields) => {
+            Fields::Unnamed (fields) => {
                 accessor_list.push_str(format!("As {}:\n", variant_name).as_ref());
-                for (i, field) in fields.unnamed.iter().enumerate() {
+                for (i, field) in fields.unnamed.iter().enumerate().filter(|(_, x)| is_variant_field_exposed(x)) {
                     let field_type = type_string(&field.ty);
-                    accessor_list.push_str(format!("*   [{}] - {}\n", i, field_type).as_ref());
+                    let notes = accessor_notes(field, &[]);
+                    let description = accessor_description(&field.attrs);
+                    accessor_list.push_str(format!("*   [{}] - {}{}{}\n", i, field_type, notes, description).as_ref());
                 }
             }
             &Fields::Unit => { }
@@ -500,34 +934,84 @@ fn gen_enum_help(name: &str, variants: Iter<Variant>, actions: &[Action]) -> Tok
         String::from("Accessors:\nChanges depending on which variant the enum is currently set to:\n")
     };
 
-    let custom_actions = custom_action_help(actions);
+    let mut builtin_actions = String::from("*   help    - display this help\n*   get     - display JSON\n*   set     - set to JSON\n*   edit    - edit JSON in your editor\n*   type    - display the Rust type\n");
+    if capabilities.clone {
+        builtin_actions.push_str("*   copy    - copy the values from this enum\n*   paste   - paste the copied values to this enum\n");
+    }
+    if capabilities.default {
+        builtin_actions.push_str("*   reset   - reset to default variant\n");
+    }
+    builtin_actions.push_str("*   variant - set to the specified variant\n");
 
-    let output = format!(r#"
+    let before = format!(r#"
 {} Help
-
-Actions:
-*   help    - display this help
-*   get     - display JSON
-*   set     - set to JSON
-*   copy    - copy the values from this enum
-*   paste   - paste the copied values to this enum
-*   reset   - reset to default variant
-*   variant - set to the specified variant
 {}
+Actions:
+{}{}"#, name, description, builtin_actions, custom_action_help(&actions.actions));
+    let after = format!(r#"
 Valid variants:
 {}
 {}
-{}"#, name, custom_actions, variant_list, accessor_info, accessor_list);
+{}"#, variant_list, accessor_info, accessor_list);
 
-    quote_spanned!{ Span::call_site() =>
-        String::from(#output)
+    gen_help_output(&before, &after, actions.node_actions, false)
+}
+
+/// The description of a field or variant for the lists in help
+fn accessor_description(attrs: &[Attribute]) -> String {
+    match help_text(attrs, " ") {
+        Some (help) => format!(" - {}", help),
+        None        => String::new(),
+    }
+}
+
+/// Use #[node(help = "...")] if it's there, otherwise use the doc comment with its lines joined by `separator`
+fn help_text(attrs: &[Attribute], separator: &str) -> Option<String> {
+    for attr in attrs {
+        if let Ok (Meta::List (list)) = attr.parse_meta() {
+            if list.path.is_ident("node") {
+                for nest_meta in list.nested.iter() {
+                    if let NestedMeta::Meta (Meta::NameValue (name_value)) = nest_meta {
+                        if name_value.path.is_ident("help") {
+                            // a help value that isn't a string is reported by attrs_to_options or check_fields
+                            if let Lit::Str (lit) = &name_value.lit {
+                                return Some(lit.value());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    doc_text(attrs, separator)
+}
+
+/// The doc comment with its lines joined by `separator`
+fn doc_text(attrs: &[Attribute], separator: &str) -> Option<String> {
+    let mut doc_lines: Vec<String> = vec!();
+    for attr in attrs {
+        if let Ok (Meta::NameValue (name_value)) = attr.parse_meta() {
+            if name_value.path.is_ident("doc") {
+                if let Lit::Str (lit) = &name_value.lit {
+                    doc_lines.push(lit.value().trim().to_string());
+                }
+            }
+        }
+    }
+
+    let doc = doc_lines.join(separator);
+    let doc = doc.trim();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc.to_string())
     }
 }
 
 fn custom_action_help(actions: &[Action]) -> String {
     let mut result = String::new();
     for action in actions {
-        let action_string = if let &Some(ref help) = &action.help {
+        let action_string = if let Some(help) = &action.help {
             format!("*   {} - {}\n", action.action.value(), help)
         } else {
             format!("*   {}\n", action.action.value())
@@ -539,103 +1023,288 @@ fn custom_action_help(actions: &[Action]) -> String {
 
 fn type_string(ty: &Type) -> String {
     match ty {
-        &Type::Path (ref path) => {
-            String::from(path.path.segments[0].ident.to_string())
+        Type::Path (path) => {
+            // the last segment is the name of the type, the rest is the module it is in e.g. serde_json::Value
+            match path.path.segments.last() {
+                Some (segment) => segment.ident.to_string(),
+                None           => String::from("UNABLE TO GET TYPE")
+            }
         }
         &Type::Tuple (_) => String::from("Tuple"),
         _ => String::from("UNABLE TO GET TYPE")
     }
 }
 
-fn attrs_to_actions(attrs: &[Attribute]) -> Vec<Action> {
+fn attrs_to_actions(attrs: &[Attribute]) -> syn::Result<Vec<Action>> {
     let mut actions: Vec<Action> = vec!();
-    for attr in attrs {
-        if let Ok(Meta::List (list)) = attr.parse_meta() {
-            if list.path.is_ident("NodeActions") {
-                for nest_meta in list.nested.iter() {
-                    if let &NestedMeta::Meta (ref sub_attr) = nest_meta {
-                        actions.push(attr_to_action(sub_attr));
+    for attr in attrs.iter().filter(|x| x.path.is_ident("NodeActions")) {
+        let list = match attr.parse_meta()? {
+            Meta::List (list) => list,
+            meta => return Err(syn::Error::new(meta.span(), "Invalid NodeActions attribute: Needs to be a list of NodeAction"))
+        };
+        for nest_meta in list.nested.iter() {
+            match nest_meta {
+                NestedMeta::Meta (sub_attr) => actions.push(attr_to_action(sub_attr)?),
+                NestedMeta::Lit (lit) => return Err(syn::Error::new(lit.span(), "Invalid NodeActions attribute: Needs to be a list of NodeAction"))
+            }
+        }
+    }
+    Ok(actions)
+}
+
+fn attr_to_action(attr: &Meta) -> syn::Result<Action> {
+    let list = match attr {
+        Meta::List (list) if list.path.is_ident("NodeAction") => list,
+        _ => return Err(syn::Error::new(attr.span(), "Invalid NodeAction attribute: Needs to be a list e.g. NodeAction(function = \"...\")"))
+    };
+
+    let mut action: Option<LitStr> = None;
+    let mut function: Option<LitStr> = None;
+    let mut args: Option<usize> = None;
+    let mut return_string = false;
+    let mut help: Option<String> = None;
+    let mut arg_types: Option<Vec<Type>> = None;
+    for nest_meta in list.nested.iter() {
+        match nest_meta {
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("return_string") => {
+                return_string = true;
+            }
+            NestedMeta::Meta (Meta::NameValue (name_value)) => {
+                let key = name_value.path.get_ident().map(|x| x.to_string()).unwrap_or_default();
+                let lit = match &name_value.lit {
+                    Lit::Str (lit) => lit,
+                    lit => return Err(syn::Error::new(lit.span(), format!("Invalid NodeAction attribute: Expected a string for {} value", key)))
+                };
+                match key.as_ref() {
+                    "action"   => action = Some(lit.clone()),
+                    "function" => function = Some(lit.clone()),
+                    "help"     => help = Some(lit.value()),
+                    "arg_types" => {
+                        let types = lit.parse_with(Punctuated::<Type, Comma>::parse_terminated)
+                            .map_err(|_| syn::Error::new(lit.span(), "Invalid NodeAction attribute: Expected a comma separated list of types for arg_types value"))?;
+                        arg_types = Some(types.into_iter().collect());
                     }
-                    else {
-                        panic!("Invalid NodeActions attribute: Needs to be a list of NodeAction")
+                    "args" => {
+                        args = Some(lit.value().parse::<usize>()
+                            .map_err(|_| syn::Error::new(lit.span(), "Invalid NodeAction attribute: Expected a string that can parse into usize"))?);
                     }
+                    _ => return Err(syn::Error::new(name_value.path.span(), "Invalid NodeAction attribute: Invalid value in list"))
                 }
             }
+            _ => return Err(syn::Error::new(nest_meta.span(), "Invalid NodeAction attribute: Invalid value in list"))
         }
     }
-    actions
+
+    let function = function.ok_or_else(|| syn::Error::new(list.span(), "Invalid NodeAction attribute: Needs to specify a function"))?;
+    let args = match (args, arg_types.as_ref()) {
+        (Some (args), Some (types)) if args != types.len() => {
+            return Err(syn::Error::new(list.span(), "Invalid NodeAction attribute: args does not match the number of arg_types"));
+        }
+        (_, Some (types)) => types.len(),
+        (Some (args), None) => args,
+        (None, None) => 0,
+    };
+
+    Ok(Action {
+        action:        action.unwrap_or_else(|| function.clone()),
+        function,
+        args,
+        return_string,
+        help,
+        arg_types,
+    })
 }
 
-fn attr_to_action(attr: &Meta) -> Action {
-    if let &Meta::List (ref list) = attr {
-        if list.path.is_ident("NodeAction") {
-            let mut action: Option<LitStr> = None;
-            let mut function: Option<LitStr> = None;
-            let mut args: usize = 0;
-            let mut return_string = false;
-            let mut help: Option<String> = None;
-            for nest_meta in list.nested.iter() {
-                if let &NestedMeta::Meta (ref meta) = nest_meta {
-                    match meta {
-                        &Meta::Path (ref path) => {
-                            if path.is_ident("return_string") {
-                                return_string = true;
-                            } else {
-                                panic!("Invalid NodeAction attribute: Invalid value in list");
-                            }
-                        }
-                        &Meta::NameValue (ref name_value) => {
-                            let ident_string = name_value.path.segments.first().map(|x| x.ident.to_string());
-                            match ident_string.as_ref().map(|x| x.as_ref()) {
-                                Some("action") => {
-                                    if let &Lit::Str(ref lit) = &name_value.lit { action = Some(lit.clone()); }
-                                    else { panic!("Invalid NodeAction attribute: Expected a string for action value"); }
-                                }
-                                Some("function") => {
-                                    if let &Lit::Str(ref lit) = &name_value.lit { function = Some(lit.clone()) }
-                                    else { panic!("Invalid NodeAction attribute: Expected a string for function value"); }
-                                }
-                                Some("help") => {
-                                    if let &Lit::Str(ref lit) = &name_value.lit { help = Some(lit.value()) }
-                                    else { panic!("Invalid NodeAction attribute: Expected a string for help value"); }
-                                }
-                                Some("args") => {
-                                    if let &Lit::Str(ref lit) = &name_value.lit {
-                                        args = lit.value().parse::<usize>().expect("Invalid NodeAction attribute: Expected a string that can parse into usize");
-                                    }
-                                    else {
-                                        panic!("Invalid NodeAction attribute: Expected a string for args value");
-                                    }
-                                }
-                                _ => { panic!("Invalid NodeAction attribute: Invalid value in list"); }
-                            }
-                        }
-                        &Meta::List (_) => {
-                            panic!("Invalid NodeAction attribute: Invalid value in list");
-                        }
-                    }
-                }
-                else {
-                    panic!("Invalid NodeAction attribute: Invalid value in list");
+/// The contents of every #[node(...)] attribute
+fn node_metas(attrs: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut metas: Vec<NestedMeta> = vec!();
+    for attr in attrs.iter().filter(|x| x.path.is_ident("node")) {
+        match attr.parse_meta()? {
+            Meta::List (list) => metas.extend(list.nested),
+            meta => return Err(syn::Error::new(meta.span(), "Invalid node attribute: Needs to be a list"))
+        }
+    }
+    Ok(metas)
+}
+
+fn node_lit_str(name_value: &MetaNameValue) -> syn::Result<&LitStr> {
+    match &name_value.lit {
+        Lit::Str (lit) => Ok(lit),
+        lit => {
+            let key = name_value.path.get_ident().map(|x| x.to_string()).unwrap_or_default();
+            Err(syn::Error::new(lit.span(), format!("Invalid node attribute: Expected a string for {} value", key)))
+        }
+    }
+}
+
+fn invalid_node_value(meta: &NestedMeta) -> syn::Error {
+    syn::Error::new(meta.span(), "Invalid node attribute: Invalid value in list")
+}
+
+fn attrs_to_options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options { bound: None, transparent: false, actions: false, krate: None, no_serde: false, no_default: false, no_clone: false };
+    for nest_meta in node_metas(attrs)? {
+        match &nest_meta {
+            NestedMeta::Meta (Meta::NameValue (name_value)) if name_value.path.is_ident("bound") => {
+                let lit = node_lit_str(name_value)?;
+                let bound = lit.parse_with(Punctuated::<WherePredicate, Comma>::parse_terminated)
+                    .map_err(|_| syn::Error::new(lit.span(), "Invalid node attribute: Expected where clause predicates for bound value"))?;
+                options.bound = Some(bound.into_iter().collect());
+            }
+            NestedMeta::Meta (Meta::NameValue (name_value)) if name_value.path.is_ident("crate") => {
+                options.krate = Some(parse_crate_path(node_lit_str(name_value)?)?);
+            }
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("transparent") => {
+                options.transparent = true;
+            }
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("actions") => {
+                options.actions = true;
+            }
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("no_serde") => {
+                options.no_serde = true;
+            }
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("no_default") => {
+                options.no_default = true;
+            }
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("no_clone") => {
+                options.no_clone = true;
+            }
+            NestedMeta::Meta (Meta::NameValue (name_value)) if name_value.path.is_ident("help") => {
+                node_lit_str(name_value)?; // read by help_text
+            }
+            _ => return Err(invalid_node_value(&nest_meta))
+        }
+    }
+    Ok(options)
+}
+
+fn parse_crate_path(lit: &LitStr) -> syn::Result<Path> {
+    lit.parse().map_err(|_| syn::Error::new(lit.span(), "Invalid node attribute: Expected a path for crate value"))
+}
+
+/// Errors are reported by check_fields before any code is generated
+fn field_options(attrs: &[Attribute]) -> FieldOptions {
+    parse_field_options(attrs).unwrap_or_default()
+}
+
+fn parse_field_options(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for nest_meta in node_metas(attrs)? {
+        match &nest_meta {
+            NestedMeta::Meta (Meta::NameValue (name_value)) => {
+                let value = node_lit_str(name_value)?.value();
+                if name_value.path.is_ident("rename") {
+                    options.rename = Some(value);
+                } else if name_value.path.is_ident("alias") {
+                    options.aliases.push(value);
+                } else if !name_value.path.is_ident("help") { // read by help_text
+                    return Err(invalid_node_value(&nest_meta));
                 }
             }
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("skip")     => options.skip = true,
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("readonly") => options.readonly = true,
+            NestedMeta::Meta (Meta::Path (path)) if path.is_ident("expose")   => options.expose = true,
+            _ => return Err(invalid_node_value(&nest_meta))
+        }
+    }
+    Ok(options)
+}
 
-            let function = function.expect("Invalid NodeAction attribute: Needs to specify a function");
+/// Variants only support #[node(help = "...")]
+fn check_variant_options(attrs: &[Attribute]) -> syn::Result<()> {
+    for nest_meta in node_metas(attrs)? {
+        match &nest_meta {
+            NestedMeta::Meta (Meta::NameValue (name_value)) if name_value.path.is_ident("help") => {
+                node_lit_str(name_value)?;
+            }
+            _ => return Err(invalid_node_value(&nest_meta))
+        }
+    }
+    Ok(())
+}
 
-            Action {
-                action:        action.unwrap_or(function.clone()),
-                function:      function,
-                args:          args,
-                return_string: return_string,
-                help:          help,
+/// Reports the invalid #[node(...)] attributes on every field and variant at once
+fn check_fields(data: &Data) -> syn::Result<()> {
+    let mut results: Vec<syn::Result<()>> = vec!();
+    match data {
+        Data::Struct (data) => {
+            for field in data.fields.iter() {
+                results.push(parse_field_options(&field.attrs).map(|_| ()));
             }
         }
-        else {
-            panic!("Invalid NodeAction attribute: Needs to be a list")
+        Data::Enum (data) => {
+            for variant in data.variants.iter() {
+                results.push(check_variant_options(&variant.attrs));
+                for field in variant.fields.iter() {
+                    results.push(parse_field_options(&field.attrs).map(|_| ()));
+                }
+            }
         }
+        Data::Union (_) => { }
     }
-    else {
-        panic!("Invalid NodeAction attribute: Needs to be a list")
+
+    let mut errors = results.into_iter().filter_map(|x| x.err());
+    match errors.next() {
+        Some (mut error) => {
+            for other in errors {
+                error.combine(other);
+            }
+            Err(error)
+        }
+        None => Ok(())
+    }
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    /// Replaces the serialized name of the field
+    pub rename: Option<String>,
+    /// Extra names the field can be accessed by
+    pub aliases: Vec<String>,
+    pub skip: bool,
+    /// Only allow actions that don't modify the field
+    pub readonly: bool,
+    /// Allow access to a private field
+    pub expose: bool,
+}
+
+struct Options {
+    /// Replaces the inferred bounds on type parameters
+    pub bound: Option<Vec<WherePredicate>>,
+    /// Delegate everything to the only field
+    pub transparent: bool,
+    /// Run the actions implemented with #[node_actions] for any action that isn't handled otherwise
+    pub actions: bool,
+    /// The path to the treeflection crate, for when it is re-exported by another crate
+    pub krate: Option<Path>,
+    /// Build get and set from the fields instead of requiring Serialize and Deserialize
+    pub no_serde: bool,
+    /// Don't require Default, the reset action is unavailable
+    pub no_default: bool,
+    /// Don't require Clone, the copy and paste actions are unavailable
+    pub no_clone: bool,
+}
+
+/// The built in actions that can be generated, depending on the traits the type implements
+struct Capabilities {
+    /// get and set use serde_json directly
+    pub serde: bool,
+    /// reset
+    pub default: bool,
+    /// copy and paste
+    pub clone: bool,
+}
+
+struct CustomActions {
+    /// Declared with #[NodeActions(...)]
+    pub actions: Vec<Action>,
+    /// Fall back to the actions implemented with #[node_actions], enabled by #[node(actions)]
+    pub node_actions: bool,
+}
+
+impl CustomActions {
+    fn is_empty(&self) -> bool {
+        self.actions.is_empty() && !self.node_actions
     }
 }
 
@@ -645,4 +1314,6 @@ struct Action {
     pub args:          usize,
     pub return_string: bool,
     pub help:          Option<String>,
+    /// Types to parse the arguments into, instead of inferring them from the function
+    pub arg_types:     Option<Vec<Type>>,
 }
@@ -0,0 +1,251 @@
+//! The `#[node_actions]` attribute, which implements `treeflection::NodeActions` for the methods of an impl block marked with `#[action]`.
+//!
+//! The arguments and return handling of each action are inferred from the method signature.
+
+use proc_macro2::{Span, TokenStream};
+
+use syn::{
+    Attribute,
+    FnArg,
+    GenericArgument,
+    GenericParam,
+    ImplItem,
+    ImplItemMethod,
+    ItemImpl,
+    Lit,
+    Meta,
+    NestedMeta,
+    PathArguments,
+    ReturnType,
+    Type,
+};
+use syn::spanned::Spanned;
+
+/// Removes the #[action] attributes from `item` and returns the NodeActions impl
+pub fn expand(item: &mut ItemImpl) -> TokenStream {
+    if let Some ((_, ref path, _)) = item.trait_ {
+        return syn::Error::new(path.span(), "#[node_actions] can only be used on an impl block without a trait").to_compile_error();
+    }
+    let name = match *item.self_ty {
+        Type::Path (ref path) => path.path.segments.last().unwrap().ident.to_string(),
+        ref ty => return syn::Error::new(ty.span(), "#[node_actions] can only be used on an impl block for a named type").to_compile_error(),
+    };
+
+    let mut actions: Vec<ActionMethod> = vec!();
+    let mut errors = TokenStream::new();
+    for impl_item in item.items.iter_mut() {
+        if let ImplItem::Method (method) = impl_item {
+            if let Some (attr) = take_action_attr(&mut method.attrs) {
+                match action_method(method, &attr) {
+                    Ok (action) => actions.push(action),
+                    Err (err)   => errors.extend(err.to_compile_error()),
+                }
+            }
+        }
+    }
+
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+    let node_action = gen_node_action(&name, &actions);
+    let help: String = actions.iter().map(|action| match action.help {
+        Some (ref help) => format!("*   {} - {}\n", action.name, help),
+        None            => format!("*   {}\n", action.name),
+    }).collect();
+
+    quote_spanned!{ Span::call_site() =>
+        impl #impl_generics _treeflection::NodeActions for #self_ty #where_clause {
+            #node_action
+
+            fn node_actions_help() -> String {
+                String::from(#help)
+            }
+        }
+
+        #errors
+    }
+}
+
+fn gen_node_action(name: &str, actions: &[ActionMethod]) -> TokenStream {
+    if actions.is_empty() {
+        return quote_spanned!{ Span::call_site() =>
+            fn node_action(&mut self, _action: &str, _args: &[String]) -> Option<String> {
+                None
+            }
+        };
+    }
+
+    let mut arms: Vec<TokenStream> = vec!();
+    for action in actions {
+        let action_name = &action.name;
+        let method = &action.method;
+        let arg_count = action.arg_types.len();
+
+        let mut arg_names: Vec<TokenStream> = vec!();
+        let mut arg_parsers: Vec<TokenStream> = vec!();
+        for (i, ty) in action.arg_types.iter().enumerate() {
+            let arg_name = format_ident!("arg{}", i);
+            arg_parsers.push(quote_spanned!{ Span::call_site() =>
+                let #arg_name = match _treeflection::args::parse_arg::<#ty>(args, #i) {
+                    Ok (arg) => arg,
+                    Err (err) => return Some(format!("{} {}: {}", #name, #action_name, err))
+                };
+            });
+            arg_names.push(quote_spanned!{ Span::call_site() => #arg_name });
+        }
+
+        let call = quote_spanned!{ method.span() => self.#method(#( #arg_names ),*) };
+        let output = match action.output {
+            Output::Unit => quote_spanned!{ Span::call_site() =>
+                #call;
+                String::new()
+            },
+            Output::Display => quote_spanned!{ Span::call_site() =>
+                #call.to_string()
+            },
+            Output::Result { ok_unit } => {
+                let ok_arm = if ok_unit {
+                    quote_spanned!{ Span::call_site() => Ok (()) => String::new() }
+                } else {
+                    quote_spanned!{ Span::call_site() => Ok (value) => value.to_string() }
+                };
+                quote_spanned!{ Span::call_site() =>
+                    match #call {
+                        #ok_arm,
+                        Err (err) => format!("{} {}: {}", #name, #action_name, err)
+                    }
+                }
+            }
+        };
+
+        arms.push(quote_spanned!{ Span::call_site() =>
+            #action_name => {
+                if args.len() > #arg_count {
+                    return Some(format!("{} {}: Takes {} arguments but was given {}", #name, #action_name, #arg_count, args.len()));
+                }
+                #( #arg_parsers )*
+                #output
+            }
+        });
+    }
+
+    quote_spanned!{ Span::call_site() =>
+        fn node_action(&mut self, action: &str, args: &[String]) -> Option<String> {
+            let result = match action {
+                #( #arms )*
+                _ => return None
+            };
+            Some(result)
+        }
+    }
+}
+
+/// Removes the #[action] attribute from the method, as it is not a real attribute outside of #[node_actions]
+fn take_action_attr(attrs: &mut Vec<Attribute>) -> Option<Attribute> {
+    let index = attrs.iter().position(|x| x.path.is_ident("action"))?;
+    Some(attrs.remove(index))
+}
+
+fn action_method(method: &ImplItemMethod, attr: &Attribute) -> syn::Result<ActionMethod> {
+    let mut name = method.sig.ident.to_string();
+    let mut help = super::doc_text(&method.attrs, " ");
+    match attr.parse_meta()? {
+        Meta::Path (_) => { }
+        Meta::List (list) => {
+            for nest_meta in list.nested.iter() {
+                match nest_meta {
+                    NestedMeta::Meta (Meta::NameValue (name_value)) if name_value.path.is_ident("name") || name_value.path.is_ident("help") => {
+                        let value = match &name_value.lit {
+                            Lit::Str (lit) => lit.value(),
+                            lit => return Err(syn::Error::new(lit.span(), "Invalid action attribute: Expected a string"))
+                        };
+                        if name_value.path.is_ident("name") {
+                            name = value;
+                        } else {
+                            help = Some(value);
+                        }
+                    }
+                    _ => return Err(syn::Error::new(nest_meta.span(), "Invalid action attribute: Expected name or help"))
+                }
+            }
+        }
+        Meta::NameValue (name_value) => return Err(syn::Error::new(name_value.span(), "Invalid action attribute: Needs to be #[action] or a list e.g. #[action(name = \"...\")]"))
+    }
+
+    if let Some (param) = method.sig.generics.params.iter().find(|x| !matches!(x, GenericParam::Lifetime (_))) {
+        return Err(syn::Error::new(param.span(), "Invalid action: methods with type or const parameters cannot be actions"));
+    }
+
+    let mut inputs = method.sig.inputs.iter();
+    match inputs.next() {
+        Some (FnArg::Receiver (receiver)) if receiver.reference.is_some() => { }
+        _ => return Err(syn::Error::new(method.sig.ident.span(), "Invalid action: Needs to take &self or &mut self"))
+    }
+
+    let mut arg_types: Vec<Type> = vec!();
+    for input in inputs {
+        if let FnArg::Typed (pat_type) = input {
+            if let Type::ImplTrait (_) = *pat_type.ty {
+                return Err(syn::Error::new(pat_type.ty.span(), "Invalid action: Arguments need a type that can be deserialized, not impl Trait"));
+            }
+            arg_types.push((*pat_type.ty).clone());
+        }
+    }
+
+    let output = match method.sig.output {
+        ReturnType::Default => Output::Unit,
+        ReturnType::Type (_, ref ty) if is_unit(ty) => Output::Unit,
+        ReturnType::Type (_, ref ty) => match result_ok_type(ty) {
+            Some (ok) => Output::Result { ok_unit: is_unit(ok) },
+            None      => Output::Display,
+        }
+    };
+
+    Ok(ActionMethod {
+        name,
+        help,
+        method: method.sig.ident.clone(),
+        arg_types,
+        output,
+    })
+}
+
+fn is_unit(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple (tuple) => tuple.elems.is_empty(),
+        _ => false
+    }
+}
+
+/// The T of Result<T, E>, also handles aliases such as io::Result<T>
+fn result_ok_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path (path) = ty {
+        let segment = path.path.segments.last()?;
+        if segment.ident == "Result" {
+            if let PathArguments::AngleBracketed (ref args) = segment.arguments {
+                if let Some (GenericArgument::Type (ok)) = args.args.first() {
+                    return Some(ok);
+                }
+            }
+        }
+    }
+    None
+}
+
+struct ActionMethod {
+    pub name:      String,
+    pub help:      Option<String>,
+    pub method:    syn::Ident,
+    /// Each argument is parsed into its type before calling the method
+    pub arg_types: Vec<Type>,
+    pub output:    Output,
+}
+
+/// How the return value of the method becomes the result of the action
+enum Output {
+    /// The result is empty
+    Unit,
+    /// The result is the return value formatted with Display, this includes String and impl Display
+    Display,
+    /// Ok is handled like Unit or Display, Err is formatted as an error
+    Result { ok_unit: bool },
+}
@@ -0,0 +1,153 @@
+//! Reads the serde attributes that change how a type is serialized,
+//! so that properties, variants and help use the same names as the JSON from `:get`.
+
+use syn::{Attribute, Field, Lit, Meta, NestedMeta, Variant};
+
+/// The `rename_all` rule of a container or enum variant
+pub fn rename_all(attrs: &[Attribute]) -> Option<String> {
+    serialize_value(attrs, "rename_all")
+}
+
+/// The serialized name of a named field
+pub fn field_name(field: &Field, rename_all: Option<&str>) -> String {
+    if let Some(name) = serialize_value(&field.attrs, "rename") {
+        return name;
+    }
+    let name = field.ident.as_ref().unwrap().to_string();
+    let name = name.trim_start_matches("r#");
+    match rename_all {
+        Some (rule) => rename_field(name, rule),
+        None        => name.to_string(),
+    }
+}
+
+/// The serialized name of an enum variant
+pub fn variant_name(variant: &Variant, rename_all: Option<&str>) -> String {
+    if let Some(name) = serialize_value(&variant.attrs, "rename") {
+        return name;
+    }
+    let name = variant.ident.to_string();
+    match rename_all {
+        Some (rule) => rename_variant(&name, rule),
+        None        => name,
+    }
+}
+
+/// True if the field or variant is left out of the serialized form
+pub fn is_skipped(attrs: &[Attribute]) -> bool {
+    has_flag(attrs, "skip") || has_flag(attrs, "skip_serializing")
+}
+
+/// True if the fields of the field are serialized as part of the parent
+pub fn is_flattened(attrs: &[Attribute]) -> bool {
+    has_flag(attrs, "flatten")
+}
+
+fn serde_metas(attrs: &[Attribute]) -> Vec<Meta> {
+    let mut metas = vec!();
+    for attr in attrs {
+        if attr.path.is_ident("serde") {
+            if let Ok(Meta::List (list)) = attr.parse_meta() {
+                for nested in list.nested {
+                    if let NestedMeta::Meta (meta) = nested {
+                        metas.push(meta);
+                    }
+                }
+            }
+        }
+    }
+    metas
+}
+
+fn has_flag(attrs: &[Attribute], flag: &str) -> bool {
+    serde_metas(attrs).iter().any(|meta| match meta {
+        Meta::Path (path) => path.is_ident(flag),
+        _ => false
+    })
+}
+
+/// Handles both `key = "value"` and `key(serialize = "value")`
+fn serialize_value(attrs: &[Attribute], key: &str) -> Option<String> {
+    for meta in serde_metas(attrs) {
+        match meta {
+            Meta::NameValue (name_value) if name_value.path.is_ident(key) => {
+                if let Lit::Str (lit) = name_value.lit {
+                    return Some(lit.value());
+                }
+            }
+            Meta::List (list) if list.path.is_ident(key) => {
+                for nested in list.nested {
+                    if let NestedMeta::Meta (Meta::NameValue (name_value)) = nested {
+                        if name_value.path.is_ident("serialize") {
+                            if let Lit::Str (lit) = name_value.lit {
+                                return Some(lit.value());
+                            }
+                        }
+                    }
+                }
+            }
+            _ => { }
+        }
+    }
+    None
+}
+
+/// Field names start out in snake_case
+fn rename_field(name: &str, rule: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => {
+            let mut result = String::new();
+            let mut capitalize = true;
+            for c in name.chars() {
+                if c == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    result.push(c.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    result.push(c);
+                }
+            }
+            result
+        }
+        "camelCase" => {
+            let pascal = rename_field(name, "PascalCase");
+            lowercase_first(&pascal)
+        }
+        "kebab-case"           => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string() // lowercase and snake_case leave field names unchanged
+    }
+}
+
+/// Variant names start out in PascalCase
+fn rename_variant(name: &str, rule: &str) -> String {
+    match rule {
+        "lowercase" => name.to_ascii_lowercase(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "camelCase" => lowercase_first(name),
+        "snake_case" => {
+            let mut result = String::new();
+            for (i, c) in name.char_indices() {
+                if i > 0 && c.is_uppercase() {
+                    result.push('_');
+                }
+                result.push(c.to_ascii_lowercase());
+            }
+            result
+        }
+        "SCREAMING_SNAKE_CASE" => rename_variant(name, "snake_case").to_ascii_uppercase(),
+        "kebab-case"           => rename_variant(name, "snake_case").replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => rename_variant(name, "SCREAMING_SNAKE_CASE").replace('_', "-"),
+        _ => name.to_string() // PascalCase leaves variant names unchanged
+    }
+}
+
+fn lowercase_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some (first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None         => String::new(),
+    }
+}
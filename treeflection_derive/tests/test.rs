@@ -6,7 +6,12 @@ extern crate treeflection;
 extern crate serde;
 extern crate serde_json;
 
-use treeflection::{Node, NodeRunner, NodeToken};
+use serde_json::{json, Value};
+use treeflection::{Node, NodeRunner, NodeToken, Session};
+
+fn parse_json(output: &str) -> Value {
+    serde_json::from_str(output).unwrap()
+}
 
 #[derive(Node, Serialize, Deserialize, Default, Clone)]
 struct Parent {
@@ -17,11 +22,11 @@ struct Parent {
     private: i64,
 }
 
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
 #[NodeActions(
     NodeAction(action="action_name", function="function_name", args="1", help="add the first argument to qux"),
     NodeAction(function="same_name", return_string),
 )]
-#[derive(Node, Serialize, Deserialize, Default, Clone)]
 struct Child {
     pub qux: i32,
 }
@@ -78,6 +83,43 @@ fn custom_function_name() {
     assert_eq!(child.qux, 420);
 }
 
+#[test]
+fn get_type() {
+    let mut child = Child::new();
+    let runner = NodeRunner { tokens: vec!(NodeToken::GetType) };
+    assert_eq!(child.node_step(runner), "test::Child");
+
+    let mut some_enum = SomeEnum::Foo;
+    let runner = NodeRunner { tokens: vec!(NodeToken::GetType) };
+    assert_eq!(some_enum.node_step(runner), "test::SomeEnum");
+}
+
+#[test]
+fn exists() {
+    let mut parent = Parent::new();
+    assert_eq!(parent.node_step(NodeRunner::new("child.qux:exists").unwrap()), "true");
+    assert_eq!(parent.node_step(NodeRunner::new("child.quux:exists").unwrap()), "false");
+    assert_eq!(parent.node_step(NodeRunner::new("private:exists").unwrap()), "false");
+    assert_eq!(parent.node_step(NodeRunner::new("[0]:exists").unwrap()), "false");
+
+    let mut some_enum = SomeEnum::Qux (1);
+    assert_eq!(some_enum.node_step(NodeRunner::new("[0]:exists").unwrap()), "true");
+    assert_eq!(some_enum.node_step(NodeRunner::new("[1]:exists").unwrap()), "false");
+    assert_eq!(some_enum.node_step(NodeRunner::new("x:exists").unwrap()), "false");
+}
+
+#[test]
+fn moveto_struct_field() {
+    // struct fields can't be removed, so neither value changes
+    let mut parent = Parent::new();
+    let mut session = Session::new();
+    assert_eq!(session.run(&mut parent, "foo:moveto child.qux"), Ok(String::from("Cannot move /foo as it can't be removed: Parent cannot 'CheckRemoveKey(\"foo\")'")));
+    assert_eq!(session.run(&mut parent, "bar:moveto child.qux"), Ok(String::from("Cannot move /bar as it can't be removed: Parent cannot 'CheckRemoveKey(\"bar\")'")));
+    assert_eq!(parent.foo, "hiya");
+    assert_eq!(parent.bar, 42);
+    assert_eq!(parent.child.qux, -13);
+}
+
 #[test]
 fn custom_same_name() {
     let mut child = Child::new();
@@ -115,7 +157,7 @@ fn set_struct() {
     assert_eq!(parent.node_step(runner), String::from(""));
     assert_eq!(parent.foo, String::from("Memes"));
     assert_eq!(parent.bar, 42);
-    assert_eq!(parent.baz, true);
+    assert!(parent.baz);
     assert_eq!(parent.child.qux, 1337);
     assert_eq!(parent.private, -1);
 }
@@ -182,7 +224,7 @@ fn default_struct() {
     assert_eq!(parent.node_step(runner), String::from(""));
     assert_eq!(parent.foo, String::new());
     assert_eq!(parent.bar, 0);
-    assert_eq!(parent.baz, false);
+    assert!(!parent.baz);
     assert_eq!(parent.child.qux, 0);
     assert_eq!(parent.private, 0);
 }
@@ -222,6 +264,8 @@ Actions:
 *   help  - display this help
 *   get   - display JSON
 *   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
 *   copy  - copy the values from this struct
 *   paste - paste the copied values to this struct
 *   reset - reset to default values
@@ -245,6 +289,8 @@ Actions:
 *   help  - display this help
 *   get   - display JSON
 *   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
 *   copy  - copy the values from this struct
 *   paste - paste the copied values to this struct
 *   reset - reset to default values
@@ -258,8 +304,9 @@ Accessors:
     assert_eq!(parent.node_step(runner), String::from(output));
 }
 
-#[derive(Node, Serialize, Deserialize, Clone, Debug)]
+#[derive(Node, Serialize, Deserialize, Clone, Debug, Default)]
 enum SomeEnum {
+    #[default]
     Foo,
     Bar,
     Baz { x: f32, y: f32 },
@@ -270,22 +317,12 @@ enum SomeEnum {
     GenericInTupleUnnamed ((Vec<usize>, Vec<String>)),
 }
 
-impl Default for SomeEnum {
-    fn default() -> SomeEnum {
-        SomeEnum::Foo
-    }
-}
-
 // test for unused variable warnings in generated code
-#[derive(Node, Serialize, Deserialize, Clone)]
+#[derive(Node, Serialize, Deserialize, Clone, Default)]
 enum SimpleEnum {
+    #[default]
     Foo,
-}
-
-impl Default for SimpleEnum {
-    fn default() -> SimpleEnum {
-        SimpleEnum::Foo
-    }
+    Empty (),
 }
 
 #[test]
@@ -323,7 +360,7 @@ fn set_unit_enum() {
 
     let mut some_enum = SomeEnum::Foo;
     let runner = NodeRunner { tokens: vec!( NodeToken::Set(String::from("\"Aether\"")) )};
-    assert_eq!(some_enum.node_step(runner), "SomeEnum set Error: unknown variant `Aether`, expected one of `Foo`, `Bar`, `Baz`, `Qux`, `Quux`, `GenericUnnamed`, `GenericNamed`, `GenericInTupleUnnamed` at line 1 column 8");
+    assert_eq!(some_enum.node_step(runner), "SomeEnum set error: unknown variant `Aether`, expected one of `Foo`, `Bar`, `Baz`, `Qux`, `Quux`, `GenericUnnamed`, `GenericNamed`, `GenericInTupleUnnamed` at line 1 column 8");
     assert!(matches!(some_enum, SomeEnum::Foo));
 }
 
@@ -481,7 +518,17 @@ fn index_tuple_enum() {
         NodeToken::Get,
         NodeToken::ChainIndex(3),
     )};
-    assert_eq!(some_enum.node_step(runner), String::from("Used index 3 on a Quux (try a value between 0-2"));
+    assert_eq!(some_enum.node_step(runner), String::from("Used index 3 on a Quux (try a value between 0-2)"));
+}
+
+#[test]
+fn index_empty_tuple_enum() {
+    let mut simple_enum = SimpleEnum::Empty ();
+    let runner = NodeRunner { tokens: vec!(
+        NodeToken::Get,
+        NodeToken::ChainIndex(0),
+    )};
+    assert_eq!(simple_enum.node_step(runner), String::from("Cannot index Empty"));
 }
 
 #[test]
@@ -544,6 +591,8 @@ Actions:
 *   help    - display this help
 *   get     - display JSON
 *   set     - set to JSON
+*   edit    - edit JSON in your editor
+*   type    - display the Rust type
 *   copy    - copy the values from this enum
 *   paste   - paste the copied values to this enum
 *   reset   - reset to default variant
@@ -582,3 +631,1010 @@ As GenericInTupleUnnamed:
     let runner = NodeRunner { tokens: vec!(NodeToken::Help) };
     assert_eq!(some_enum.node_step(runner), String::from(output));
 }
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct Track<T> {
+    pub keys: Vec<T>,
+    pub looping: bool,
+}
+
+#[derive(Node, Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum Either<L, R> where L: PartialEq {
+    Left (L),
+    Right { value: R },
+}
+
+impl<L: PartialEq + Default, R> Default for Either<L, R> {
+    fn default() -> Either<L, R> {
+        Either::Left(L::default())
+    }
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+#[node(bound = "T: Node + serde::Serialize + serde::de::DeserializeOwned + Default + Clone + Copy")]
+struct Pair<T> {
+    pub first: T,
+    pub second: T,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+struct Named<'a> {
+    pub value: i32,
+    name: std::borrow::Cow<'a, str>,
+}
+
+#[test]
+fn generic_struct() {
+    let mut track = Track { keys: vec!(1.0f32, 2.5), looping: false };
+    let runner = NodeRunner::new("keys[1]:get").unwrap();
+    assert_eq!(track.node_step(runner), "2.5");
+
+    let runner = NodeRunner::new("keys[0]:set 4").unwrap();
+    assert_eq!(track.node_step(runner), "");
+    assert_eq!(track.keys, vec!(4.0, 2.5));
+
+    let runner = NodeRunner::new(r#":set {\"keys\":[],\"looping\":true}"#).unwrap();
+    assert_eq!(track.node_step(runner), "");
+    assert_eq!(track, Track { keys: vec!(), looping: true });
+
+    let mut track: Track<Track<u8>> = Track::default();
+    let runner = NodeRunner::new(":help").unwrap();
+    assert!(track.node_step(runner).contains("*   keys - Vec"));
+}
+
+#[test]
+fn generic_enum() {
+    let mut either: Either<u32, String> = Either::Left(3);
+    let runner = NodeRunner::new("[0]:get").unwrap();
+    assert_eq!(either.node_step(runner), "3");
+
+    let runner = NodeRunner::new(":variant Right").unwrap();
+    assert_eq!(either.node_step(runner), "");
+    assert_eq!(either, Either::Right { value: String::new() });
+
+    let runner = NodeRunner::new(".value:set foo").unwrap();
+    assert_eq!(either.node_step(runner), "");
+    assert_eq!(either, Either::Right { value: String::from("foo") });
+}
+
+#[test]
+fn generic_bound() {
+    let mut pair = Pair { first: 1u8, second: 2u8 };
+    let runner = NodeRunner::new("second:get").unwrap();
+    assert_eq!(pair.node_step(runner), "2");
+}
+
+#[test]
+fn generic_copy_paste() {
+    let mut a = Track { keys: vec!(1u8), looping: true };
+    let mut b = Track { keys: vec!(2u8), looping: false };
+    let mut c: Track<u16> = Track::default();
+
+    assert_eq!(b.node_step(NodeRunner::new(":paste").unwrap()), "Track has not been copied");
+    assert_eq!(a.node_step(NodeRunner::new(":copy").unwrap()), "");
+    // each instantiation has its own copy
+    assert_eq!(c.node_step(NodeRunner::new(":paste").unwrap()), "Track has not been copied");
+    assert_eq!(b.node_step(NodeRunner::new(":paste").unwrap()), "");
+    assert_eq!(b, a);
+}
+
+#[test]
+fn lifetime_struct() {
+    let mut named = Named { value: 1, name: std::borrow::Cow::Borrowed("foo") };
+    assert_eq!(named.node_step(NodeRunner::new("value:set 5").unwrap()), "");
+    assert_eq!(named.value, 5);
+    assert_eq!(named.node_step(NodeRunner::new(":copy").unwrap()), "Named cannot 'CopyFrom'");
+    assert_eq!(named.name, "foo");
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct Point(pub f32, pub f32, u8);
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[node(transparent)]
+struct FrameIndex(u32);
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[node(transparent)]
+struct Frames<T> {
+    frames: Vec<T>,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct Marker;
+
+#[test]
+fn tuple_struct_index() {
+    let mut point = Point(1.0, 2.5, 3);
+    assert_eq!(point.node_step(NodeRunner::new("[1]:get").unwrap()), "2.5");
+    assert_eq!(point.node_step(NodeRunner::new("[0]:set 4").unwrap()), "");
+    assert_eq!(point, Point(4.0, 2.5, 3));
+    assert_eq!(point.node_step(NodeRunner::new("[2]:get").unwrap()), "Used index 2 on a Point (try one of: 0, 1)");
+    assert_eq!(parse_json(&point.node_step(NodeRunner::new("[*]:get").unwrap())), json!([{ "path": "[0]", "result": "4" }, { "path": "[1]", "result": "2.5" }]));
+    assert_eq!(point.node_step(NodeRunner::new(":get").unwrap()), "[\n  4.0,\n  2.5,\n  3\n]");
+}
+
+#[test]
+fn tuple_struct_help() {
+    let output =
+r#"
+Point Help
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+*   copy  - copy the values from this struct
+*   paste - paste the copied values to this struct
+*   reset - reset to default values
+
+Accessors:
+*   [0] - f32
+*   [1] - f32"#;
+    let mut point = Point(1.0, 2.5, 3);
+    assert_eq!(point.node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[test]
+fn transparent_newtype() {
+    let mut index = FrameIndex(4);
+    assert_eq!(index.node_step(NodeRunner::new(":get").unwrap()), "4");
+    assert_eq!(index.node_step(NodeRunner::new(":add 3").unwrap()), "");
+    assert_eq!(index, FrameIndex(7));
+    assert!(index.node_step(NodeRunner::new(":help").unwrap()).starts_with("\nu32 Help"));
+
+    let mut frames = Frames { frames: vec!(FrameIndex(1), FrameIndex(2)) };
+    assert_eq!(frames.node_step(NodeRunner::new("[1]:get").unwrap()), "2");
+    assert_eq!(frames.node_step(NodeRunner::new(":insert 0").unwrap()), "");
+    assert_eq!(frames.frames.len(), 3);
+}
+
+#[test]
+fn unit_struct() {
+    let mut marker = Marker;
+    assert_eq!(marker.node_step(NodeRunner::new(":get").unwrap()), "null");
+    assert_eq!(marker.node_step(NodeRunner::new(":set null").unwrap()), "");
+    assert_eq!(marker.node_step(NodeRunner::new(":set 1").unwrap()), "Marker set error: invalid type: integer `1`, expected unit struct Marker at line 1 column 1");
+    assert_eq!(marker.node_step(NodeRunner::new("foo:get").unwrap()), "Marker cannot 'ChainProperty(\"foo\")'");
+
+    let output =
+r#"
+Marker Help
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+*   copy  - copy the values from this struct
+*   paste - paste the copied values to this struct
+*   reset - reset to default values"#;
+    assert_eq!(marker.node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Hitbox {
+    pub base_damage: f32,
+    #[serde(rename = "kb")]
+    pub knockback: f32,
+    #[serde(skip)]
+    pub cached: u32,
+    #[serde(flatten)]
+    pub position: Position,
+    #[serde(flatten)]
+    pub extra: serde_json::Value,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+struct Position {
+    #[serde(rename = "X")]
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum HitboxKind {
+    #[default]
+    DealDamage,
+    #[serde(rename = "grab")]
+    Grabbing,
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    SetAngle { angle_degrees: f32 },
+    #[serde(skip)]
+    Internal,
+}
+
+fn hitbox() -> Hitbox {
+    Hitbox {
+        base_damage: 4.0,
+        knockback: 10.0,
+        cached: 7,
+        position: Position { x: 1.0, y: 2.0 },
+        extra: serde_json::json!({ "sound": "slash" }),
+    }
+}
+
+#[test]
+fn serde_rename_struct() {
+    let mut hitbox = hitbox();
+    assert_eq!(hitbox.node_step(NodeRunner::new("baseDamage:get").unwrap()), "4");
+    assert_eq!(hitbox.node_step(NodeRunner::new("kb:set 12").unwrap()), "");
+    assert_eq!(hitbox.knockback, 12.0);
+    assert_eq!(hitbox.node_step(NodeRunner::new("base_damage:get").unwrap()), "Hitbox does not have a property 'base_damage'");
+    assert_eq!(hitbox.node_step(NodeRunner::new("knockback:get").unwrap()), "Hitbox does not have a property 'knockback'");
+    assert_eq!(hitbox.node_step(NodeRunner::new("cached:get").unwrap()), "Hitbox does not have a property 'cached'");
+    assert_eq!(hitbox.cached, 7);
+}
+
+#[test]
+fn serde_flatten() {
+    let mut hitbox = hitbox();
+    assert_eq!(hitbox.node_step(NodeRunner::new("X:get").unwrap()), "1");
+    assert_eq!(hitbox.node_step(NodeRunner::new("y:set 5").unwrap()), "");
+    assert_eq!(hitbox.position.y, 5.0);
+    assert_eq!(hitbox.node_step(NodeRunner::new("sound:get").unwrap()), "\"slash\"");
+    assert_eq!(hitbox.node_step(NodeRunner::new("z:get").unwrap()), "Hitbox does not have a property 'z'");
+    assert!(hitbox.node_has_property("X"));
+    assert!(hitbox.node_has_property("sound"));
+    assert!(!hitbox.node_has_property("position"));
+
+    let json = hitbox.node_step(NodeRunner::new(":get").unwrap());
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json, serde_json::json!({ "baseDamage": 4.0, "kb": 10.0, "X": 1.0, "y": 5.0, "sound": "slash" }));
+}
+
+#[test]
+fn serde_rename_struct_help() {
+    let output =
+r#"
+Hitbox Help
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+*   copy  - copy the values from this struct
+*   paste - paste the copied values to this struct
+*   reset - reset to default values
+
+Accessors:
+*   baseDamage - f32
+*   kb - f32
+*   the properties of Position
+*   the properties of Value"#;
+    assert_eq!(hitbox().node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[test]
+fn serde_rename_enum() {
+    let mut kind = HitboxKind::DealDamage;
+    assert_eq!(kind.node_step(NodeRunner::new(":variant grab").unwrap()), "");
+    assert_eq!(kind, HitboxKind::Grabbing);
+    assert_eq!(kind.node_step(NodeRunner::new(":variant set_angle").unwrap()), "");
+    assert_eq!(kind.node_step(NodeRunner::new(".ANGLE_DEGREES:set 45").unwrap()), "");
+    assert_eq!(kind, HitboxKind::SetAngle { angle_degrees: 45.0 });
+    assert_eq!(kind.node_step(NodeRunner::new(".angle_degrees:get").unwrap()), "set_angle does not have a property 'angle_degrees'");
+    assert_eq!(kind.node_step(NodeRunner::new(":variant SetAngle").unwrap()), "HitboxKind does not have a variant 'SetAngle'");
+    assert_eq!(kind.node_step(NodeRunner::new(":variant internal").unwrap()), "HitboxKind does not have a variant 'internal'");
+
+    let mut internal = HitboxKind::Internal;
+    assert_eq!(internal.node_step(NodeRunner::new(".foo:get").unwrap()), "internal does not have a property 'foo'");
+
+    let help = kind.node_step(NodeRunner::new(":help").unwrap());
+    assert!(help.contains("Valid variants:\n*   deal_damage\n*   grab\n*   set_angle\n\n"), "{}", help);
+    assert!(help.contains("As set_angle:\n*   .ANGLE_DEGREES - f32\n"), "{}", help);
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+struct Fighter {
+    #[node(rename = "kb_growth", alias = "growth", alias = "kbg")]
+    pub knockback_growth: f32,
+    #[node(readonly)]
+    pub id: u32,
+    #[node(skip)]
+    pub frame_cache: Vec<u32>,
+    #[node(expose)]
+    weight: u32,
+    #[node(expose, readonly)]
+    name: String,
+    #[node(readonly)]
+    pub position: Position,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct Version(#[node(readonly)] pub u32, pub u32);
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+enum Shape {
+    #[default]
+    Empty,
+    Circle {
+        #[node(rename = "r", alias = "radius")]
+        radius: f32,
+        #[node(skip)]
+        cached_area: f32,
+    },
+    Rect (#[node(readonly)] f32, f32),
+}
+
+fn fighter() -> Fighter {
+    Fighter {
+        knockback_growth: 1.5,
+        id: 3,
+        frame_cache: vec!(1),
+        weight: 98,
+        name: String::from("Fox"),
+        position: Position { x: 1.0, y: 2.0 },
+    }
+}
+
+#[test]
+fn field_rename_alias() {
+    let mut fighter = fighter();
+    assert_eq!(fighter.node_step(NodeRunner::new("kb_growth:get").unwrap()), "1.5");
+    assert_eq!(fighter.node_step(NodeRunner::new("growth:set 2").unwrap()), "");
+    assert_eq!(fighter.node_step(NodeRunner::new("kbg:get").unwrap()), "2");
+    assert_eq!(fighter.node_step(NodeRunner::new("knockback_growth:get").unwrap()), "Fighter does not have a property 'knockback_growth'");
+}
+
+#[test]
+fn field_skip_expose() {
+    let mut fighter = fighter();
+    assert_eq!(fighter.node_step(NodeRunner::new("frame_cache:get").unwrap()), "Fighter does not have a property 'frame_cache'");
+    assert_eq!(fighter.frame_cache, vec!(1));
+    assert_eq!(fighter.node_step(NodeRunner::new("weight:set 100").unwrap()), "");
+    assert_eq!(fighter.weight, 100);
+}
+
+#[test]
+fn field_readonly() {
+    let mut fighter = fighter();
+    assert_eq!(fighter.node_step(NodeRunner::new("id:get").unwrap()), "3");
+    assert_eq!(fighter.node_step(NodeRunner::new("id:set 4").unwrap()), "Fighter property 'id' is readonly");
+    assert_eq!(fighter.node_step(NodeRunner::new("id:reset").unwrap()), "Fighter property 'id' is readonly");
+    assert_eq!(fighter.id, 3);
+    assert_eq!(fighter.node_step(NodeRunner::new("name:get").unwrap()), "Fox");
+    assert_eq!(fighter.node_step(NodeRunner::new("name:set Falco").unwrap()), "Fighter property 'name' is readonly");
+    assert_eq!(fighter.name, "Fox");
+
+    // readonly applies to everything below the field
+    assert_eq!(fighter.node_step(NodeRunner::new("position.y:get").unwrap()), "2");
+    assert_eq!(fighter.node_step(NodeRunner::new("position.y:set 5").unwrap()), "Fighter property 'position' is readonly");
+    assert!(fighter.node_step(NodeRunner::new("position:help").unwrap()).starts_with("\nPosition Help"));
+    assert_eq!(fighter.position.y, 2.0);
+}
+
+#[test]
+fn field_options_help() {
+    let output =
+r#"
+Fighter Help
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+*   copy  - copy the values from this struct
+*   paste - paste the copied values to this struct
+*   reset - reset to default values
+
+Accessors:
+*   kb_growth - f32 (alias: growth, kbg)
+*   id - u32 (readonly)
+*   weight - u32
+*   name - String (readonly)
+*   position - Position (readonly)"#;
+    assert_eq!(fighter().node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[test]
+fn tuple_field_readonly() {
+    let mut version = Version(1, 2);
+    assert_eq!(version.node_step(NodeRunner::new("[0]:set 5").unwrap()), "Version property '[0]' is readonly");
+    assert_eq!(version.node_step(NodeRunner::new("[1]:set 5").unwrap()), "");
+    assert_eq!(parse_json(&version.node_step(NodeRunner::new("[*]:get").unwrap())), json!([{ "path": "[0]", "result": "1" }, { "path": "[1]", "result": "5" }]));
+    assert_eq!(parse_json(&version.node_step(NodeRunner::new("[*]:set 7").unwrap())), json!([{ "path": "[0]", "result": "Version property '[0]' is readonly" }, { "path": "[1]", "result": "" }]));
+    assert_eq!(version, Version(1, 7));
+}
+
+#[test]
+fn variant_field_options() {
+    let mut shape = Shape::Circle { radius: 2.0, cached_area: 12.5 };
+    assert_eq!(shape.node_step(NodeRunner::new(".r:get").unwrap()), "2");
+    assert_eq!(shape.node_step(NodeRunner::new(".radius:set 3").unwrap()), "");
+    assert_eq!(shape.node_step(NodeRunner::new(".cached_area:get").unwrap()), "Circle does not have a property 'cached_area'");
+    assert_eq!(shape, Shape::Circle { radius: 3.0, cached_area: 12.5 });
+
+    let mut shape = Shape::Rect(1.0, 2.0);
+    assert_eq!(shape.node_step(NodeRunner::new("[0]:set 4").unwrap()), "Rect property '[0]' is readonly");
+    assert_eq!(shape.node_step(NodeRunner::new("[1]:set 4").unwrap()), "");
+    assert_eq!(shape, Shape::Rect(1.0, 4.0));
+
+    let help = Shape::Empty.node_step(NodeRunner::new(":help").unwrap());
+    assert!(help.contains("As Circle:\n*   .r - f32 (alias: radius)\nAs Rect:\n*   [0] - f32 (readonly)\n*   [1] - f32\n"), "{}", help);
+}
+
+/// The physical properties of a fighter.
+/// Used by the physics engine every frame.
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+struct Physics {
+    /// How much knockback increases as the fighter takes damage.
+    /// Higher values launch the fighter further.
+    pub knockback_growth: f32,
+    #[node(help = "Multiplier on gravity", readonly)]
+    /// ignored as #[node(help)] takes priority
+    pub gravity: f32,
+    pub friction: f32,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+#[node(help = "Which way the fighter is facing")]
+enum Facing {
+    /// Facing towards positive x
+    #[default]
+    Right,
+    #[node(help = "Facing towards negative x")]
+    Left,
+    Turning {
+        /// Frames until the turn finishes
+        frames: u32
+    },
+}
+
+#[test]
+fn help_doc_comments() {
+    let output =
+r#"
+Physics Help
+
+The physical properties of a fighter.
+Used by the physics engine every frame.
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+*   copy  - copy the values from this struct
+*   paste - paste the copied values to this struct
+*   reset - reset to default values
+
+Accessors:
+*   knockback_growth - f32 - How much knockback increases as the fighter takes damage. Higher values launch the fighter further.
+*   gravity - f32 (readonly) - Multiplier on gravity
+*   friction - f32"#;
+    assert_eq!(Physics::default().node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[test]
+fn help_doc_comments_enum() {
+    let output =
+r#"
+Facing Help
+
+Which way the fighter is facing
+
+Actions:
+*   help    - display this help
+*   get     - display JSON
+*   set     - set to JSON
+*   edit    - edit JSON in your editor
+*   type    - display the Rust type
+*   copy    - copy the values from this enum
+*   paste   - paste the copied values to this enum
+*   reset   - reset to default variant
+*   variant - set to the specified variant
+
+Valid variants:
+*   Right - Facing towards positive x
+*   Left - Facing towards negative x
+*   Turning
+
+Accessors:
+Changes depending on which variant the enum is currently set to:
+
+As Turning:
+*   .frames - u32 - Frames until the turn finishes
+"#;
+    assert_eq!(Facing::default().node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[NodeActions(
+    NodeAction(function="scale", args="2", help="scale by $FACTOR, optionally rounding"),
+    NodeAction(function="set_facing", args="1"),
+    NodeAction(function="describe", arg_types="bool", return_string),
+    NodeAction(function="set_generic", arg_types="u8"),
+    NodeAction(function="reset_all"),
+)]
+struct Knockback {
+    pub value: f32,
+    pub facing: Direction,
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+enum Direction {
+    #[default]
+    Left,
+    Right,
+}
+
+impl Knockback {
+    fn scale(&mut self, factor: f32, round: Option<bool>) {
+        self.value *= factor;
+        if round == Some(true) {
+            self.value = self.value.round();
+        }
+    }
+
+    fn set_facing(&mut self, facing: Direction) {
+        self.facing = facing;
+    }
+
+    fn describe(&self, verbose: bool) -> String {
+        if verbose { format!("{} {:?}", self.value, self.facing) } else { self.value.to_string() }
+    }
+
+    fn set_generic<T: Into<f32>>(&mut self, value: T) {
+        self.value = value.into();
+    }
+
+    fn reset_all(&mut self) {
+        *self = Knockback::default();
+    }
+}
+
+#[test]
+fn typed_action_args() {
+    let mut knockback = Knockback { value: 1.5, facing: Direction::Left };
+    assert_eq!(knockback.node_step(NodeRunner::new(":scale 2").unwrap()), "");
+    assert_eq!(knockback.value, 3.0);
+    assert_eq!(knockback.node_step(NodeRunner::new(":scale 0.55 true").unwrap()), "");
+    assert_eq!(knockback.value, 2.0);
+
+    assert_eq!(knockback.node_step(NodeRunner::new(":set_facing Right").unwrap()), "");
+    assert_eq!(knockback.facing, Direction::Right);
+    assert_eq!(knockback.node_step(NodeRunner::new(":describe true").unwrap()), "2 Right");
+    assert_eq!(knockback.node_step(NodeRunner::new(":set_generic 7").unwrap()), "");
+    assert_eq!(knockback.value, 7.0);
+    assert_eq!(knockback.node_step(NodeRunner::new(":reset_all").unwrap()), "");
+    assert_eq!(knockback, Knockback::default());
+}
+
+#[test]
+fn typed_action_args_errors() {
+    let mut knockback = Knockback { value: 1.5, facing: Direction::Left };
+    assert_eq!(knockback.node_step(NodeRunner::new(":scale").unwrap()), "Knockback scale: Missing argument 1 (needs to be: f32)");
+    assert_eq!(
+        knockback.node_step(NodeRunner::new(":scale fast").unwrap()),
+        "Knockback scale: Invalid value 'fast' for argument 1 (needs to be: f32): invalid type: string \"fast\", expected f32"
+    );
+    assert_eq!(
+        knockback.node_step(NodeRunner::new(":scale 2 3").unwrap()),
+        "Knockback scale: Invalid value '3' for argument 2 (needs to be: Option<bool>): invalid type: integer `3`, expected a boolean at line 1 column 1"
+    );
+    assert_eq!(knockback.node_step(NodeRunner::new(":scale 2 true 4").unwrap()), "Knockback scale: Takes 2 arguments but was given 3");
+    assert_eq!(knockback.node_step(NodeRunner::new(":reset_all 1").unwrap()), "Knockback reset_all: Takes 0 arguments but was given 1");
+    assert_eq!(
+        knockback.node_step(NodeRunner::new(":set_facing Up").unwrap()),
+        "Knockback set_facing: Invalid value 'Up' for argument 1 (needs to be: Direction): unknown variant `Up`, expected `Left` or `Right`"
+    );
+    assert!(knockback.node_step(NodeRunner::new(":set_generic 300").unwrap()).starts_with("Knockback set_generic: Invalid value '300' for argument 1 (needs to be: u8)"));
+    assert_eq!(knockback, Knockback { value: 1.5, facing: Direction::Left });
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[node(actions)]
+#[NodeActions(
+    NodeAction(function="reset_radius", help="set the radius to 1"),
+)]
+struct Moon {
+    pub radius: f32,
+    pub craters: u32,
+}
+
+#[node_actions]
+impl Moon {
+    /// set the radius to 0
+    #[action(name = "explode")]
+    fn explode_internal_naming_scheme(&mut self) {
+        self.radius = 0.0;
+    }
+
+    #[action(help = "scale the radius, optionally rounding")]
+    fn scale(&mut self, factor: f32, round: Option<bool>) {
+        self.radius *= factor;
+        if round == Some(true) {
+            self.radius = self.radius.round();
+        }
+    }
+
+    #[action]
+    fn describe(&self) -> String {
+        format!("A moon with {} craters", self.craters)
+    }
+
+    #[action]
+    fn diameter(&self) -> impl std::fmt::Display {
+        self.radius * 2.0
+    }
+
+    #[action]
+    fn impact(&mut self, count: u32) -> Result<(), String> {
+        if count > 10 {
+            Err(format!("{} impacts would destroy the moon", count))
+        } else {
+            self.craters += count;
+            Ok(())
+        }
+    }
+
+    #[action]
+    fn circumference(&self) -> Result<f32, std::fmt::Error> {
+        Ok(self.radius * 2.0 * 3.0)
+    }
+
+    // not an action, still callable from rust
+    fn reset_radius(&mut self) {
+        self.radius = 1.0;
+    }
+}
+
+#[test]
+fn node_actions_attribute() {
+    let mut moon = Moon { radius: 2.0, craters: 3 };
+    assert_eq!(moon.node_step(NodeRunner::new(":describe").unwrap()), "A moon with 3 craters");
+    assert_eq!(moon.node_step(NodeRunner::new(":diameter").unwrap()), "4");
+    assert_eq!(moon.node_step(NodeRunner::new(":circumference").unwrap()), "12");
+    assert_eq!(moon.node_step(NodeRunner::new(":impact 2").unwrap()), "");
+    assert_eq!(moon.craters, 5);
+    assert_eq!(moon.node_step(NodeRunner::new(":scale 1.3 true").unwrap()), "");
+    assert_eq!(moon.radius, 3.0);
+    assert_eq!(moon.node_step(NodeRunner::new(":scale 2").unwrap()), "");
+    assert_eq!(moon.radius, 6.0);
+    assert_eq!(moon.node_step(NodeRunner::new(":explode").unwrap()), "");
+    assert_eq!(moon.radius, 0.0);
+    assert_eq!(moon.node_step(NodeRunner::new(":reset_radius").unwrap()), "");
+    assert_eq!(moon.radius, 1.0);
+}
+
+#[test]
+fn node_actions_attribute_errors() {
+    let mut moon = Moon { radius: 2.0, craters: 3 };
+    assert_eq!(moon.node_step(NodeRunner::new(":impact 11").unwrap()), "Moon impact: 11 impacts would destroy the moon");
+    assert_eq!(moon.node_step(NodeRunner::new(":impact").unwrap()), "Moon impact: Missing argument 1 (needs to be: u32)");
+    assert_eq!(moon.node_step(NodeRunner::new(":describe 1").unwrap()), "Moon describe: Takes 0 arguments but was given 1");
+    assert_eq!(moon.node_step(NodeRunner::new(":explode_internal_naming_scheme").unwrap()), "Moon cannot 'explode_internal_naming_scheme'");
+    assert_eq!(moon.node_step(NodeRunner::new(":orbit").unwrap()), "Moon cannot 'orbit'");
+    assert_eq!(moon, Moon { radius: 2.0, craters: 3 });
+}
+
+#[test]
+fn node_actions_attribute_help() {
+    let output =
+r#"
+Moon Help
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+*   copy  - copy the values from this struct
+*   paste - paste the copied values to this struct
+*   reset - reset to default values
+*   reset_radius - set the radius to 1
+*   explode - set the radius to 0
+*   scale - scale the radius, optionally rounding
+*   describe
+*   diameter
+*   impact
+*   circumference
+
+Accessors:
+*   radius - f32
+*   craters - u32"#;
+    assert_eq!(Moon::default().node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[node(actions)]
+enum Phase {
+    #[default]
+    Waxing,
+    Waning,
+}
+
+#[node_actions]
+impl Phase {
+    #[action(help = "switch between waxing and waning")]
+    fn flip(&mut self) {
+        *self = match self {
+            Phase::Waxing => Phase::Waning,
+            Phase::Waning => Phase::Waxing,
+        };
+    }
+}
+
+#[derive(Node, Serialize, Deserialize, Default, Clone)]
+#[node(actions)]
+struct Orbit<T> {
+    pub bodies: Vec<T>,
+}
+
+#[node_actions]
+impl<T> Orbit<T> {
+    #[action]
+    fn size(&self) -> usize {
+        self.bodies.len()
+    }
+}
+
+#[test]
+fn node_actions_attribute_enum_and_generic() {
+    let mut phase = Phase::Waxing;
+    assert_eq!(phase.node_step(NodeRunner::new(":flip").unwrap()), "");
+    assert_eq!(phase, Phase::Waning);
+    let help = phase.node_step(NodeRunner::new(":help").unwrap());
+    assert!(help.contains("*   variant - set to the specified variant\n*   flip - switch between waxing and waning\n\nValid variants:\n"), "{}", help);
+
+    let mut orbit = Orbit { bodies: vec!(Moon::default(), Moon::default()) };
+    assert_eq!(orbit.node_step(NodeRunner::new(":size").unwrap()), "2");
+    assert_eq!(orbit.node_step(NodeRunner::new("bodies[1].radius:set 4").unwrap()), "");
+    assert_eq!(orbit.bodies[1].radius, 4.0);
+}
+
+/// Nothing from treeflection is imported here, and these names would clash with unqualified paths in the generated code
+mod hygiene {
+    #[derive(Debug, PartialEq)]
+    pub struct NodeToken;
+    #[derive(Debug, PartialEq)]
+    pub struct NodeRunner;
+
+    #[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+    #[node(actions)]
+    pub struct Comet {
+        pub speed: f32,
+        pub tail: Tail,
+        pub path: Vec<(f32, f32)>,
+    }
+
+    #[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+    pub enum Tail {
+        #[default]
+        None,
+        Dust { length: f32 },
+    }
+
+    #[node_actions]
+    impl Comet {
+        #[action]
+        fn accelerate(&mut self, amount: f32) {
+            self.speed += amount;
+        }
+    }
+
+    pub mod engine {
+        pub(crate) use treeflection as reflection;
+    }
+
+    #[derive(Node, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+    #[node(crate = "crate::hygiene::engine::reflection", actions)]
+    pub struct Asteroid {
+        pub mass: f32,
+    }
+
+    #[node_actions(crate = "self::engine::reflection")]
+    impl Asteroid {
+        #[action]
+        fn split(&mut self) {
+            self.mass /= 2.0;
+        }
+    }
+}
+
+#[test]
+fn hygiene() {
+    assert_eq!(hygiene::NodeToken, hygiene::NodeToken);
+    assert_eq!(hygiene::NodeRunner, hygiene::NodeRunner);
+
+    let mut comet = hygiene::Comet::default();
+    assert_eq!(comet.node_step(NodeRunner::new("speed:set 2").unwrap()), "");
+    assert_eq!(comet.node_step(NodeRunner::new(":accelerate 3").unwrap()), "");
+    assert_eq!(comet.node_step(NodeRunner::new("tail:variant Dust").unwrap()), "");
+    assert_eq!(comet.node_step(NodeRunner::new("tail.length:set 7").unwrap()), "");
+    assert_eq!(comet, hygiene::Comet { speed: 5.0, tail: hygiene::Tail::Dust { length: 7.0 }, path: vec!() });
+
+    let mut asteroid = hygiene::Asteroid { mass: 8.0 };
+    assert_eq!(asteroid.node_step(NodeRunner::new(":split").unwrap()), "");
+    assert_eq!(asteroid.node_step(NodeRunner::new("mass:get").unwrap()), "4");
+}
+
+/// Stands in for a GPU resource, which can't be serialized, cloned or defaulted
+#[derive(Debug, PartialEq)]
+struct TextureHandle(u32);
+
+#[derive(Node, Debug, PartialEq)]
+#[node(no_serde, no_default, no_clone)]
+struct Sprite {
+    pub name: String,
+    pub position: Point,
+    pub scale: f32,
+    #[node(rename = "tags")]
+    pub layers: Vec<u32>,
+    texture: TextureHandle,
+}
+
+impl Sprite {
+    fn new() -> Sprite {
+        Sprite {
+            name:     String::from("Fox"),
+            position: Point(1.0, 2.0, 3),
+            scale:    0.5,
+            layers:   vec!(1, 2),
+            texture:  TextureHandle(7),
+        }
+    }
+}
+
+#[derive(Node, Default, Clone, Debug, PartialEq)]
+#[node(no_serde)]
+enum Light {
+    #[default]
+    Off,
+    Level(f32),
+    Color(u8, u8, u8),
+    Spot {
+        radius: f32,
+        label: String,
+    },
+}
+
+#[test]
+fn no_serde_struct_get() {
+    let mut sprite = Sprite::new();
+    let output =
+r#"{
+  "name": "Fox",
+  "position": [
+    1.0,
+    2.0,
+    3
+  ],
+  "scale": 0.5,
+  "tags": [
+    1,
+    2
+  ]
+}"#;
+    assert_eq!(sprite.node_step(NodeRunner::new(":get").unwrap()), output);
+    assert_eq!(sprite.node_step(NodeRunner::new("tags[1]:get").unwrap()), "2");
+    assert_eq!(sprite.texture, TextureHandle(7));
+}
+
+#[test]
+fn no_serde_struct_set() {
+    let mut sprite = Sprite::new();
+    assert_eq!(sprite.node_step(NodeRunner::new(r#":set {\"name\":\"Falco\",\"tags\":[3],\"position\":[4,5,6]}"#).unwrap()), "");
+    assert_eq!(sprite, Sprite {
+        name:     String::from("Falco"),
+        position: Point(4.0, 5.0, 6),
+        scale:    0.5,
+        layers:   vec!(3),
+        texture:  TextureHandle(7),
+    });
+
+    let get = sprite.node_step(NodeRunner::new(":get").unwrap());
+    let mut other = Sprite::new();
+    assert_eq!(other.node_step(NodeRunner { tokens: vec!(NodeToken::Set(get)) }), "");
+    assert_eq!(other, sprite);
+
+    assert_eq!(sprite.node_step(NodeRunner::new(r#":set {\"texture\":1}"#).unwrap()), "Sprite set error: Sprite does not have a property 'texture'");
+    assert_eq!(sprite.node_step(NodeRunner::new(":set [1]").unwrap()), "Sprite set error: Expected an object");
+    assert_eq!(sprite.node_step(NodeRunner::new(":set {").unwrap()), "Sprite set error: EOF while parsing an object at line 1 column 1");
+    assert_eq!(sprite.node_step(NodeRunner::new(r#":set {\"position\":[1]}"#).unwrap()), "Point set error: invalid length 1, expected tuple struct Point with 3 elements at line 1 column 3");
+    assert_eq!(sprite.node_step(NodeRunner::new(r#":set {\"scale\":\"big\"}"#).unwrap()), "Invalid value for f32 (needs to be: A number with a decimal point)");
+}
+
+#[test]
+fn no_default_no_clone() {
+    let mut sprite = Sprite::new();
+    assert_eq!(sprite.node_step(NodeRunner::new(":reset").unwrap()), "Sprite cannot 'SetDefault'");
+    assert_eq!(sprite.node_step(NodeRunner::new(":copy").unwrap()), "Sprite cannot 'CopyFrom'");
+    assert_eq!(sprite.node_step(NodeRunner::new(":paste").unwrap()), "Sprite cannot 'PasteTo'");
+    assert_eq!(sprite, Sprite::new());
+
+    let output =
+r#"
+Sprite Help
+
+Actions:
+*   help  - display this help
+*   get   - display JSON
+*   set   - set to JSON
+*   edit  - edit JSON in your editor
+*   type  - display the Rust type
+
+Accessors:
+*   name - String
+*   position - Point
+*   scale - f32
+*   tags - Vec"#;
+    assert_eq!(sprite.node_step(NodeRunner::new(":help").unwrap()), output);
+}
+
+#[test]
+fn no_serde_enum() {
+    let mut light = Light::Off;
+    assert_eq!(light.node_step(NodeRunner::new(":get").unwrap()), "\"Off\"");
+    light = Light::Level(0.5);
+    assert_eq!(light.node_step(NodeRunner::new(":get").unwrap()), "{\n  \"Level\": 0.5\n}");
+    light = Light::Color(1, 2, 3);
+    assert_eq!(light.node_step(NodeRunner::new(":get").unwrap()), "{\n  \"Color\": [\n    1,\n    2,\n    3\n  ]\n}");
+    light = Light::Spot { radius: 4.0, label: String::from("stage") };
+    assert_eq!(light.node_step(NodeRunner::new(":get").unwrap()), "{\n  \"Spot\": {\n    \"label\": \"stage\",\n    \"radius\": 4\n  }\n}");
+
+    assert_eq!(light.node_step(NodeRunner::new(r#":set \"Off\""#).unwrap()), "");
+    assert_eq!(light, Light::Off);
+    assert_eq!(light.node_step(NodeRunner::new(r#":set {\"Level\":0.25}"#).unwrap()), "");
+    assert_eq!(light, Light::Level(0.25));
+    assert_eq!(light.node_step(NodeRunner::new(r#":set {\"Color\":[4,5,6]}"#).unwrap()), "");
+    assert_eq!(light, Light::Color(4, 5, 6));
+    assert_eq!(light.node_step(NodeRunner::new(r#":set {\"Spot\":{\"label\":\"wings\"}}"#).unwrap()), "");
+    assert_eq!(light, Light::Spot { radius: 0.0, label: String::from("wings") });
+
+    assert_eq!(light.node_step(NodeRunner::new(r#":set \"Strobe\""#).unwrap()), "Light does not have a variant 'Strobe'");
+    assert_eq!(light.node_step(NodeRunner::new(r#":set {\"Off\":1}"#).unwrap()), "Light set error: Off does not have any fields");
+    assert_eq!(light.node_step(NodeRunner::new(":set 1").unwrap()), "Light set error: Expected a variant name or an object containing a single variant");
+
+    let help = light.node_step(NodeRunner::new(":help").unwrap());
+    assert!(help.contains("*   paste   - paste the copied values to this enum\n*   reset   - reset to default variant\n"), "{}", help);
+}
+
+#[test]
+fn ron_format() {
+    let mut knockback = Knockback { value: 2.5, facing: Direction::Right };
+    assert_eq!(knockback.node_step(NodeRunner::new(":get ron").unwrap()), "(\n    value: 2.5,\n    facing: Right,\n)");
+    assert_eq!(knockback.node_step(NodeRunner::new(":set ron (value: 4, facing: Left)").unwrap()), "");
+    assert_eq!(knockback, Knockback { value: 4.0, facing: Direction::Left });
+    assert_eq!(knockback.node_step(NodeRunner::new("facing:set ron Right").unwrap()), "");
+    assert_eq!(knockback.facing, Direction::Right);
+    assert_eq!(knockback.node_step(NodeRunner::new(":set ron (value: 1)").unwrap()), "Knockback set error: 1:10: Unexpected missing field `facing` in `Knockback`");
+    assert_eq!(knockback.node_step(NodeRunner::new(":get compact").unwrap()), r#"{"value":4.0,"facing":"Right"}"#);
+}
+
+#[test]
+fn no_serde_ron_format() {
+    let mut sprite = Sprite::new();
+    assert_eq!(sprite.node_step(NodeRunner::new(":get ron").unwrap()), "{\n    \"name\": \"Fox\",\n    \"position\": [\n        1.0,\n        2.0,\n        3,\n    ],\n    \"scale\": 0.5,\n    \"tags\": [\n        1,\n        2,\n    ],\n}");
+    assert_eq!(sprite.node_step(NodeRunner::new(r#":set ron {\"scale\": 2.0, \"tags\": []}"#).unwrap()), "");
+    assert_eq!(sprite.scale, 2.0);
+    assert!(sprite.layers.is_empty());
+
+    let mut light = Light::Level(0.5);
+    assert_eq!(light.node_step(NodeRunner::new(":get compact").unwrap()), r#"{"Level":0.5}"#);
+    assert_eq!(light.node_step(NodeRunner::new(r#":set compact \"Off\""#).unwrap()), "");
+    assert_eq!(light, Light::Off);
+}
+
+#[test]
+fn get_options() {
+    let mut knockback = Knockback { value: 2.5, facing: Direction::Right };
+    assert_eq!(knockback.node_step(NodeRunner::new(":get --compact").unwrap()), r#"{"value":2.5,"facing":"Right"}"#);
+    assert_eq!(knockback.node_step(NodeRunner::new(":get --compact --sorted").unwrap()), r#"{"facing":"Right","value":2.5}"#);
+
+    let mut sprite = Sprite::new();
+    assert_eq!(sprite.node_step(NodeRunner::new(":get --depth 1 --compact").unwrap()), r#"{"name":"Fox","position":"…","scale":0.5,"tags":"…"}"#);
+    assert_eq!(sprite.node_step(NodeRunner::new("position:get --depth 0").unwrap()), r#""…""#);
+}
@@ -0,0 +1,20 @@
+#[macro_use] extern crate treeflection_derive;
+
+struct Planet {
+    pub radius: f32,
+}
+
+#[node_actions]
+impl Planet {
+    #[action(rename = "boom")]
+    fn explode(&mut self) {
+        self.radius = 0.0;
+    }
+
+    #[action(help = 1)]
+    fn shrink(&mut self) {
+        self.radius /= 2.0;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#[macro_use] extern crate treeflection_derive;
+
+struct Planet {
+    pub radius: f32,
+}
+
+#[node_actions]
+impl Planet {
+    #[action]
+    fn scale<T: Into<f32>>(&mut self, factor: T) {
+        self.radius *= factor.into();
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#[macro_use] extern crate treeflection_derive;
+
+struct Planet {
+    pub radius: f32,
+}
+
+#[node_actions]
+impl Planet {
+    #[action]
+    fn create() -> Planet {
+        Planet { radius: 1.0 }
+    }
+
+    #[action]
+    fn explode(self) {
+    }
+}
+
+fn main() {}
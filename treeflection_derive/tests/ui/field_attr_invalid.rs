@@ -0,0 +1,13 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+struct Planet {
+    #[node(hidden)]
+    pub radius: f32,
+    #[node(rename = 3)]
+    pub mass: f32,
+    #[node(alias = "moons")]
+    pub satellites: u32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+#[NodeActions(
+    NodeAction(action = "explode"),
+)]
+struct Planet {
+    pub radius: f32,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#[macro_use] extern crate treeflection_derive;
+
+struct Planet {
+    pub radius: f32,
+}
+
+#[node_actions(explode)]
+impl Planet {
+    #[action]
+    fn explode(&mut self) {
+        self.radius = 0.0;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#[macro_use] extern crate treeflection_derive;
+
+struct Planet {
+    pub radius: f32,
+}
+
+#[node_actions]
+impl Default for Planet {
+    fn default() -> Planet {
+        Planet { radius: 1.0 }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+#[node(bound = "T Node")]
+struct Track<T> {
+    pub keys: Vec<T>,
+}
+
+fn main() {}
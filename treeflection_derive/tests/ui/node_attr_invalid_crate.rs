@@ -0,0 +1,9 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+#[node(crate = "my engine::treeflection")]
+struct Planet {
+    pub radius: f32,
+}
+
+fn main() {}
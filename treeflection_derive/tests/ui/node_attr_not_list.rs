@@ -0,0 +1,9 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+#[node = "transparent"]
+struct Planet {
+    pub radius: f32,
+}
+
+fn main() {}
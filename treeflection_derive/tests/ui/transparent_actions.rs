@@ -0,0 +1,7 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+#[node(transparent, actions)]
+struct FrameIndex(u32);
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+#[node(transparent)]
+struct Marker;
+
+fn main() {}
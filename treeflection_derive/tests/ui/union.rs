@@ -0,0 +1,9 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+union Bits {
+    pub int: u32,
+    pub float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#[macro_use] extern crate treeflection_derive;
+
+#[derive(Node)]
+enum Orbit {
+    #[node(skip)]
+    Circular,
+    Elliptical {
+        #[node(readonly = "true")]
+        eccentricity: f32,
+    },
+}
+
+fn main() {}